@@ -85,12 +85,13 @@ pub fn build(args: &Args, data_source: Option<&dyn DataSource>) -> Result<BuildS
         )
         .into());
     }
-    let artifact = build::build(BuildRequest {
+    let request = BuildRequest {
         blocks: args.layout.blocks.clone(),
         data_source,
         strict: args.layout.strict,
         capture_values: args.output.export_json.is_some(),
-    })?;
+    };
+    let artifact = build::build(request.with_keys(args.layout.key_ring()))?;
 
     let contents = artifact.render(args.output.format, args.output.record_width as usize)?;
 
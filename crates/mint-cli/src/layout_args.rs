@@ -1,6 +1,8 @@
 use clap::Args;
 use mint_core::build::BlockSelector;
+use mint_core::keys::{KeyRing, KeySource};
 use mint_core::layout::error::LayoutError;
+use std::path::PathBuf;
 
 pub fn parse_block_arg(block: &str) -> Result<BlockSelector, LayoutError> {
     if let Some((file, name)) = block.rsplit_once('#') {
@@ -16,6 +18,22 @@ pub fn parse_block_arg(block: &str) -> Result<BlockSelector, LayoutError> {
     Ok(BlockSelector::all(block))
 }
 
+pub fn parse_key_arg(key: &str) -> Result<(String, KeySource), String> {
+    match key.split_once('=') {
+        Some((name, location)) if !name.is_empty() && !location.is_empty() => {
+            let source = match location.strip_prefix("env:") {
+                Some("") => return Err(format!("invalid key '{key}'; env: needs a variable name")),
+                Some(var) => KeySource::Env(var.to_owned()),
+                None => KeySource::File(PathBuf::from(location)),
+            };
+            Ok((name.to_owned(), source))
+        }
+        _ => Err(format!(
+            "invalid key '{key}'; use NAME=PATH or NAME=env:VAR"
+        )),
+    }
+}
+
 #[derive(Args, Debug)]
 pub struct LayoutArgs {
     #[arg(value_name = "FILE[#BLOCK] | FILE", required = true, num_args = 1.., value_parser = parse_block_arg, help = "One or more layout selectors as file[#block] or a layout file (typically .toml) to build all blocks")]
@@ -27,4 +45,22 @@ pub struct LayoutArgs {
         default_value_t = false
     )]
     pub strict: bool,
+
+    #[arg(
+        long = "key",
+        value_name = "NAME=PATH|NAME=env:VAR",
+        value_parser = parse_key_arg,
+        help = "Key file or environment variable for [mint.signature.NAME], [mint.hmac.NAME] or [mint.encryption.NAME]; overrides the layout (repeatable)"
+    )]
+    pub keys: Vec<(String, KeySource)>,
+}
+
+impl LayoutArgs {
+    pub fn key_ring(&self) -> KeyRing {
+        let mut ring = KeyRing::new();
+        for (name, source) in &self.keys {
+            ring.insert(name.clone(), source.clone());
+        }
+        ring
+    }
 }
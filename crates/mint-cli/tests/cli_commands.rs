@@ -391,3 +391,59 @@ fn format_extension_mismatch_warning_respects_quiet() {
         assert!(out.exists(), "expected output file: {}", out.display());
     }
 }
+
+#[test]
+fn build_key_argument_supplies_signature_keys() {
+    let layout = common::write_layout_file(
+        "signed",
+        r#"
+[mint]
+abi = "generic-le"
+
+[mint.signature.boot]
+algorithm = "ed25519"
+
+[block.header]
+start_address = 0
+length = 0x80
+signature = "boot"
+
+[block.data]
+value = { value = 1, type = "u32" }
+"#,
+    );
+    let out = common::unique_out_path("signed", "hex");
+    let build = |key: Option<&str>| {
+        let mut command = mint_command();
+        command
+            .args(["build", &layout, "--quiet", "--out"])
+            .arg(&out);
+        if let Some(key) = key {
+            command.args(["--key", key]);
+        }
+        command.output().expect("mint build should run")
+    };
+
+    let missing = build(None);
+    assert!(!missing.status.success());
+    assert!(
+        String::from_utf8_lossy(&missing.stderr).contains("pass --key boot=PATH"),
+        "stderr: {}",
+        String::from_utf8_lossy(&missing.stderr)
+    );
+
+    let signed = build(Some("boot=../mint-core/tests/data/keys/ed25519.pem"));
+    assert!(
+        signed.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&signed.stderr)
+    );
+    assert!(out.exists(), "expected output file: {}", out.display());
+
+    let invalid = build(Some("boot"));
+    assert!(!invalid.status.success());
+    assert!(
+        String::from_utf8_lossy(&invalid.stderr)
+            .contains("invalid key 'boot'; use NAME=PATH or NAME=env:VAR")
+    );
+}
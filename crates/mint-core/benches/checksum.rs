@@ -0,0 +1,114 @@
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use mint_core::build::{self, BlockSelector, BuildFromLayoutsRequest, NamedLayout};
+use mint_core::layout;
+use mint_core::output::checksum::{self, calculate_crc};
+use std::hint::black_box;
+use std::path::PathBuf;
+
+const IMAGE_SIZE: usize = 4 << 20;
+
+/// The former bit-at-a-time implementation, kept as the baseline for the sliced tables.
+fn bitwise_crc(data: &[u8], config: &mint_core::layout::settings::CrcConfig) -> u32 {
+    let width = u32::from(config.width);
+    let mask = u32::MAX >> (32 - width);
+    let top_bit = 1u32 << (width - 1);
+    let mut crc = config.start & mask;
+    for &byte in data {
+        let byte = if config.ref_in {
+            byte.reverse_bits()
+        } else {
+            byte
+        };
+        crc ^= u32::from(byte) << (width - 8);
+        for _ in 0..8 {
+            crc = if crc & top_bit != 0 {
+                (crc << 1) ^ config.polynomial
+            } else {
+                crc << 1
+            };
+        }
+        crc &= mask;
+    }
+    if config.ref_out {
+        crc = crc.reverse_bits() >> (32 - width);
+    }
+    (crc ^ config.xor_out) & mask
+}
+
+fn crc_implementations(c: &mut Criterion) {
+    let data = (0..IMAGE_SIZE)
+        .map(|index| (index.wrapping_mul(2_654_435_761) >> 11) as u8)
+        .collect::<Vec<_>>();
+    let mut group = c.benchmark_group("crc");
+    group.throughput(Throughput::Bytes(IMAGE_SIZE as u64));
+    group.sample_size(10);
+    for name in ["CRC-32", "CRC-32/MPEG-2", "CRC-16/IBM-3740"] {
+        let config = checksum::crc_preset(name)
+            .expect("catalogue preset")
+            .config();
+        group.bench_with_input(BenchmarkId::new("bitwise", name), &data, |b, data| {
+            b.iter(|| bitwise_crc(black_box(data), &config));
+        });
+        group.bench_with_input(BenchmarkId::new("sliced", name), &data, |b, data| {
+            b.iter(|| calculate_crc(black_box(data), &config));
+        });
+    }
+    group.finish();
+}
+
+/// A block with `count` CRC fields, each covering everything before it.
+fn chained_checksum_layout(count: usize) -> String {
+    let chunk = IMAGE_SIZE / count;
+    let mut layout = format!(
+        r#"
+[mint]
+abi = "generic-le"
+
+[mint.checksum.crc]
+algorithm = "CRC-32"
+
+[block.header]
+start_address = 0
+length = {}
+
+[block.data]
+"#,
+        IMAGE_SIZE + 4 * count
+    );
+    for index in 0..count {
+        layout.push_str(&format!(
+            "part{index} = {{ value = [{index}], type = \"u8\", size = {chunk} }}\ncrc{index} = {{ checksum = \"crc\", type = \"u32\" }}\n"
+        ));
+    }
+    layout
+}
+
+fn chained_block_checksums(c: &mut Criterion) {
+    let mut group = c.benchmark_group("block_checksums");
+    group.throughput(Throughput::Bytes(IMAGE_SIZE as u64));
+    group.sample_size(10);
+    for count in [1, 4, 16] {
+        let text = chained_checksum_layout(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &text, |b, text| {
+            b.iter(|| {
+                let config = layout::parse_toml_layout(text).expect("layout parses");
+                let name = PathBuf::from("bench.toml");
+                build::build_from_layouts(BuildFromLayoutsRequest {
+                    layouts: vec![NamedLayout {
+                        name: name.clone(),
+                        config,
+                    }],
+                    blocks: vec![BlockSelector::named(name, "block")],
+                    data_source: None,
+                    strict: false,
+                    capture_values: false,
+                })
+                .expect("block builds")
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, crc_implementations, chained_block_checksums);
+criterion_main!(benches);
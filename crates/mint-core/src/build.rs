@@ -1,7 +1,9 @@
 use crate::data::DataSource;
 use crate::error::MintError;
+use crate::extensions::Extensions;
+use crate::keys::{KeyContext, KeyRing};
 use crate::layout;
-use crate::layout::block::Config;
+use crate::layout::block::{BlockImage, Config};
 use crate::layout::error::LayoutError;
 use crate::layout::used_values::{NoopValueSink, ValueCollector};
 use crate::output;
@@ -9,7 +11,7 @@ use crate::output::error::OutputError;
 use crate::output::{DataRange, OutputFormat};
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
@@ -97,17 +99,86 @@ pub struct BuildFromLayoutsRequest<'a> {
     pub capture_values: bool,
 }
 
+/// A build request with the key locations that override those named in its layouts and the
+/// extensions it builds with.
+///
+/// Requests convert into this with no overrides or extensions, so [`build`] and
+/// [`build_from_layouts`] take either. Relative layout key paths resolve from each layout's
+/// parent directory.
+pub struct BuildWith<R> {
+    request: R,
+    keys: KeyRing,
+    extensions: Extensions,
+}
+
+impl<R> BuildWith<R> {
+    /// Sets the key locations that override those named in the layouts.
+    pub fn with_keys(mut self, keys: KeyRing) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Sets the custom checksum algorithms used by the layouts, and the output writers for the
+    /// artifact.
+    pub fn with_extensions(mut self, extensions: Extensions) -> Self {
+        self.extensions = extensions;
+        self
+    }
+}
+
+impl<R> From<R> for BuildWith<R> {
+    fn from(request: R) -> Self {
+        Self {
+            request,
+            keys: KeyRing::default(),
+            extensions: Extensions::default(),
+        }
+    }
+}
+
+impl BuildRequest<'_> {
+    /// Builds with `keys` overriding the key locations named in the layouts.
+    pub fn with_keys(self, keys: KeyRing) -> BuildWith<Self> {
+        BuildWith::from(self).with_keys(keys)
+    }
+
+    /// Builds with the custom checksum algorithms and output writers in `extensions`.
+    pub fn with_extensions(self, extensions: Extensions) -> BuildWith<Self> {
+        BuildWith::from(self).with_extensions(extensions)
+    }
+}
+
+impl BuildFromLayoutsRequest<'_> {
+    /// Builds with `keys` overriding the key locations named in the layouts.
+    pub fn with_keys(self, keys: KeyRing) -> BuildWith<Self> {
+        BuildWith::from(self).with_keys(keys)
+    }
+
+    /// Builds with the custom checksum algorithms and output writers in `extensions`.
+    pub fn with_extensions(self, extensions: Extensions) -> BuildWith<Self> {
+        BuildWith::from(self).with_extensions(extensions)
+    }
+}
+
 #[derive(Debug)]
 pub struct BuildArtifact {
     pub ranges: Vec<DataRange>,
     pub stats: BuildStats,
     pub used_values: Option<serde_json::Value>,
+    /// Extensions the request was built with, whose writers [`Self::write`] uses.
+    pub extensions: Extensions,
 }
 
 impl BuildArtifact {
     pub fn render(&self, format: OutputFormat, record_width: usize) -> Result<String, OutputError> {
         output::render(&self.ranges, record_width, format)
     }
+
+    /// Renders the artifact with an output writer registered through
+    /// [`BuildWith::with_extensions`].
+    pub fn write(&self, writer: &str) -> Result<Vec<u8>, OutputError> {
+        self.extensions.writer(writer)?.write(self)
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -152,54 +223,120 @@ struct BlockBuildResult {
     used_values: Option<serde_json::Value>,
 }
 
-pub fn build(request: BuildRequest<'_>) -> Result<BuildArtifact, MintError> {
+pub fn build<'a>(
+    request: impl Into<BuildWith<BuildRequest<'a>>>,
+) -> Result<BuildArtifact, MintError> {
     let start_time = Instant::now();
+    let BuildWith {
+        request,
+        keys,
+        extensions,
+    } = request.into();
 
-    let (resolved_blocks, layouts) = resolve_blocks(&request.blocks)?;
+    let (resolved_blocks, mut layouts) = resolve_blocks(&request.blocks)?;
     let mut artifact = build_resolved(
         resolved_blocks,
-        &layouts,
+        &mut layouts,
         request.data_source,
         request.strict,
         request.capture_values,
+        &keys,
+        extensions,
     )?;
     artifact.stats.total_duration = start_time.elapsed();
     Ok(artifact)
 }
 
-pub fn build_from_layouts(
-    request: BuildFromLayoutsRequest<'_>,
+pub fn build_from_layouts<'a>(
+    request: impl Into<BuildWith<BuildFromLayoutsRequest<'a>>>,
 ) -> Result<BuildArtifact, MintError> {
     let start_time = Instant::now();
+    let BuildWith {
+        request,
+        keys,
+        extensions,
+    } = request.into();
 
-    let layouts = collect_named_layouts(request.layouts)?;
+    let mut layouts = collect_named_layouts(request.layouts)?;
     let resolved_blocks = resolve_blocks_from_layouts(&request.blocks, &layouts)?;
     let mut artifact = build_resolved(
         resolved_blocks,
-        &layouts,
+        &mut layouts,
         request.data_source,
         request.strict,
         request.capture_values,
+        &keys,
+        extensions,
     )?;
     artifact.stats.total_duration = start_time.elapsed();
     Ok(artifact)
 }
 
+/// Decrypts one block's emitted image, for decode and verify tooling.
+///
+/// Returns the plaintext data as it was before encryption, with the nonce and tag fields zeroed
+/// so integrity fields check against it. The header signature covers the encrypted image, so
+/// verify it against `image` before decrypting.
+/// Relative layout key paths resolve from the layout name's parent directory; `keys` overrides
+/// them as in a build.
+pub fn decrypt_block(
+    layout: &NamedLayout,
+    block: &str,
+    image: &[u8],
+    keys: &KeyRing,
+) -> Result<Vec<u8>, MintError> {
+    let result = (|| {
+        let found = layout.config.blocks.get(block).ok_or_else(|| {
+            LayoutError::BlockNotFound(format!(
+                "'{block}' in '{}'. Available blocks: {}",
+                layout.name.display(),
+                layout
+                    .config
+                    .blocks
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        })?;
+        let keys = KeyContext {
+            ring: keys,
+            layout_dir: layout.name.parent().unwrap_or(Path::new("")),
+        };
+        found.decrypt(image, &layout.config.mint, &keys)
+    })();
+    result.map_err(|error| MintError::InDecryptBlock {
+        block_name: block.to_owned(),
+        layout_file: layout.name.display().to_string(),
+        source: Box::new(error.into()),
+    })
+}
+
 fn build_resolved(
     resolved_blocks: Vec<ResolvedBlock>,
-    layouts: &HashMap<PathBuf, Config>,
+    layouts: &mut HashMap<PathBuf, Config>,
     data_source: Option<&dyn DataSource>,
     strict: bool,
     capture_values: bool,
+    keys: &KeyRing,
+    extensions: Extensions,
 ) -> Result<BuildArtifact, MintError> {
-    let fingerprints = calculate_layout_fingerprints(layouts, &resolved_blocks)?;
+    for config in layouts.values_mut() {
+        extensions.bind(config)?;
+    }
+    let layouts = &*layouts;
+    let levels = plan_build_levels(&resolved_blocks, layouts)?;
+    let planned = levels.iter().flatten().cloned().collect::<Vec<_>>();
+    let fingerprints = calculate_layout_fingerprints(layouts, &planned)?;
     let mut results = build_bytestreams(
         &resolved_blocks,
+        &levels,
         layouts,
         &fingerprints,
         data_source,
         strict,
         capture_values,
+        keys,
     )?;
 
     let used_values = if capture_values {
@@ -214,6 +351,7 @@ fn build_resolved(
         ranges,
         stats,
         used_values,
+        extensions,
     })
 }
 
@@ -338,36 +476,146 @@ pub(crate) fn resolve_blocks(
     Ok((resolved, layouts))
 }
 
+/// Groups the selected blocks and the blocks their integrity fields cover into build levels.
+///
+/// Every block is built after the blocks it covers; blocks within a level are independent.
+/// Covered blocks that were not selected are built for their images only.
+fn plan_build_levels(
+    blocks: &[ResolvedBlock],
+    layouts: &HashMap<PathBuf, Config>,
+) -> Result<Vec<Vec<ResolvedBlock>>, MintError> {
+    fn visit(
+        block: &ResolvedBlock,
+        layouts: &HashMap<PathBuf, Config>,
+        levels: &mut HashMap<(PathBuf, String), usize>,
+        order: &mut Vec<ResolvedBlock>,
+        stack: &mut Vec<String>,
+    ) -> Result<usize, MintError> {
+        let key = (block.layout.clone(), block.name.clone());
+        if let Some(level) = levels.get(&key) {
+            return Ok(*level);
+        }
+        if let Some(position) = stack.iter().position(|name| name == &block.name) {
+            return Err(LayoutError::InvalidLayout(format!(
+                "cross-block coverage is circular between blocks [{}]",
+                stack[position..].join(", ")
+            ))
+            .into());
+        }
+
+        let Some(layout) = layouts.get(&block.layout) else {
+            return Ok(0);
+        };
+        let targets = layout
+            .coverage_targets(&block.name)
+            .map_err(|error| MintError::InBlock {
+                block_name: block.name.clone(),
+                layout_file: block.layout.display().to_string(),
+                source: Box::new(error.into()),
+            })?;
+        stack.push(block.name.clone());
+        let mut level = 0;
+        for target in targets {
+            let target = ResolvedBlock {
+                name: target.to_owned(),
+                layout: block.layout.clone(),
+            };
+            level = level.max(visit(&target, layouts, levels, order, stack)? + 1);
+        }
+        stack.pop();
+
+        levels.insert(key, level);
+        order.push(block.clone());
+        Ok(level)
+    }
+
+    let mut levels = HashMap::new();
+    let mut order = Vec::new();
+    for block in blocks {
+        visit(block, layouts, &mut levels, &mut order, &mut Vec::new())?;
+    }
+
+    let mut planned: Vec<Vec<ResolvedBlock>> = Vec::new();
+    for block in order {
+        let level = levels[&(block.layout.clone(), block.name.clone())];
+        if planned.len() <= level {
+            planned.resize_with(level + 1, Vec::new);
+        }
+        planned[level].push(block);
+    }
+    Ok(planned)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn build_bytestreams(
     blocks: &[ResolvedBlock],
+    levels: &[Vec<ResolvedBlock>],
     layouts: &HashMap<PathBuf, Config>,
     fingerprints: &HashMap<PathBuf, HashMap<String, u64>>,
     data_source: Option<&dyn DataSource>,
     strict: bool,
     capture_values: bool,
+    keys: &KeyRing,
 ) -> Result<Vec<BlockBuildResult>, MintError> {
-    blocks
-        .par_iter()
-        .map(|resolved| {
-            build_single_bytestream(
-                resolved,
-                layouts,
-                fingerprints,
-                data_source,
-                strict,
-                capture_values,
-            )
-        })
-        .collect()
+    let no_images = HashMap::new();
+    let mut images: HashMap<PathBuf, HashMap<String, BlockImage>> = HashMap::new();
+    let mut built = HashMap::new();
+    let last_level = levels.len().saturating_sub(1);
+
+    for (index, level) in levels.iter().enumerate() {
+        let results = level
+            .par_iter()
+            .map(|resolved| {
+                build_single_bytestream(
+                    resolved,
+                    layouts,
+                    fingerprints,
+                    images.get(&resolved.layout).unwrap_or(&no_images),
+                    data_source,
+                    strict,
+                    capture_values,
+                    keys,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (resolved, result) in level.iter().zip(results) {
+            // Only blocks below the last level can be covered by a later block.
+            if index < last_level
+                && let Some(header) = layouts
+                    .get(&resolved.layout)
+                    .and_then(|layout| layout.blocks.get(&resolved.name))
+                    .map(|block| &block.header)
+            {
+                images.entry(resolved.layout.clone()).or_default().insert(
+                    resolved.name.clone(),
+                    BlockImage {
+                        bytes: result.data_range.bytestream.clone(),
+                        length: header.length as usize,
+                        padding: header.padding,
+                    },
+                );
+            }
+            built.insert((resolved.layout.clone(), resolved.name.clone()), result);
+        }
+    }
+
+    Ok(blocks
+        .iter()
+        .filter_map(|block| built.remove(&(block.layout.clone(), block.name.clone())))
+        .collect())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn build_single_bytestream(
     resolved: &ResolvedBlock,
     layouts: &HashMap<PathBuf, Config>,
     fingerprints: &HashMap<PathBuf, HashMap<String, u64>>,
+    block_images: &HashMap<String, BlockImage>,
     data_source: Option<&dyn DataSource>,
     strict: bool,
     capture_values: bool,
+    keys: &KeyRing,
 ) -> Result<BlockBuildResult, MintError> {
     let result = (|| {
         let layout = layouts.get(&resolved.layout).ok_or_else(|| {
@@ -399,11 +647,17 @@ fn build_single_bytestream(
             &mut noop as &mut dyn crate::layout::used_values::ValueSink
         };
 
+        let keys = KeyContext {
+            ring: keys,
+            layout_dir: resolved.layout.parent().unwrap_or(Path::new("")),
+        };
         let build_output = block.emit(
             &resolved.name,
             fingerprints,
+            block_images,
             data_source,
             &layout.mint,
+            &keys,
             strict,
             value_sink,
         )?;
@@ -4,7 +4,7 @@ use std::path::Path;
 
 use super::DataSource;
 use super::error::DataError;
-use crate::layout::value::{DataValue, ValueSource};
+use crate::layout::value::{DataValue, LookupTable, TableKind, ValueSource};
 
 #[derive(Debug, Clone)]
 pub struct ExcelDataSourceOptions {
@@ -24,6 +24,7 @@ impl ExcelDataSourceOptions {
 /// Excel-backed data source for variants.
 pub struct ExcelDataSource {
     names: Vec<String>,
+    variants: Vec<String>,
     variant_columns: Vec<Vec<Data>>,
     sheets: HashMap<String, Range<Data>>,
 }
@@ -77,6 +78,7 @@ impl ExcelDataSource {
 
         Ok(Self {
             names,
+            variants: options.variants,
             variant_columns,
             sheets,
         })
@@ -98,6 +100,17 @@ impl ExcelDataSource {
         ))
     }
 
+    fn sheet(&self, sheet_name: &str) -> Result<&Range<Data>, DataError> {
+        self.sheets.get(sheet_name).ok_or_else(|| {
+            let available: Vec<_> = self.sheets.keys().map(|s| s.as_str()).collect();
+            DataError::RetrievalError(format!(
+                "Sheet not found: '{}'. Available sheets: {}",
+                sheet_name,
+                available.join(", ")
+            ))
+        })
+    }
+
     fn cell_eq(cell: &Data, target: &str) -> bool {
         match cell {
             Data::String(s) => s == target,
@@ -171,13 +184,13 @@ impl ExcelDataSource {
 
 impl DataSource for ExcelDataSource {
     fn retrieve_single_value(&self, name: &str) -> Result<DataValue, DataError> {
+        DataError::while_retrieving(name, || single_value(self.retrieve_cell(name)?))
+    }
+
+    fn retrieve_symbol(&self, name: &str) -> Result<DataValue, DataError> {
         DataError::while_retrieving(name, || match self.retrieve_cell(name)? {
-            Data::Int(i) => Ok(DataValue::I64(*i)),
-            Data::Float(f) => Ok(DataValue::F64(*f)),
-            Data::Bool(b) => Ok(DataValue::Bool(*b)),
-            _ => Err(DataError::RetrievalError(
-                "Found non-numeric single value".to_owned(),
-            )),
+            Data::String(symbol) => Ok(DataValue::Str(symbol.to_owned())),
+            cell => single_value(cell),
         })
     }
 
@@ -191,14 +204,7 @@ impl DataSource for ExcelDataSource {
 
             // Check if the value starts with '#' to indicate a sheet reference
             if let Some(sheet_name) = cell_string.strip_prefix('#') {
-                let sheet = self.sheets.get(sheet_name).ok_or_else(|| {
-                    let available: Vec<_> = self.sheets.keys().map(|s| s.as_str()).collect();
-                    DataError::RetrievalError(format!(
-                        "Sheet not found: '{}'. Available sheets: {}",
-                        sheet_name,
-                        available.join(", ")
-                    ))
-                })?;
+                let sheet = self.sheet(sheet_name)?;
 
                 let mut out = Vec::new();
 
@@ -244,14 +250,7 @@ impl DataSource for ExcelDataSource {
                 ))
             })?;
 
-            let sheet = self.sheets.get(sheet_name).ok_or_else(|| {
-                let available: Vec<_> = self.sheets.keys().map(|s| s.as_str()).collect();
-                DataError::RetrievalError(format!(
-                    "Sheet not found: '{}'. Available sheets: {}",
-                    sheet_name,
-                    available.join(", ")
-                ))
-            })?;
+            let sheet = self.sheet(sheet_name)?;
 
             let convert = |cell: &Data| -> Result<DataValue, DataError> {
                 match cell {
@@ -306,6 +305,112 @@ impl DataSource for ExcelDataSource {
             Ok(out)
         })
     }
+
+    /// Reads a curve or map sheet: the first row holds the x breakpoints from the second column
+    /// on, and each following row holds its y breakpoint (a map) or a label (a curve) in the first
+    /// column, then one value per x breakpoint.
+    fn retrieve_table(&self, name: &str, kind: TableKind) -> Result<LookupTable, DataError> {
+        DataError::while_retrieving(name, || {
+            let kind_name = kind.name();
+            let Data::String(cell_string) = self.retrieve_cell(name)? else {
+                return Err(DataError::RetrievalError(format!(
+                    "Expected a sheet reference for a {kind_name}"
+                )));
+            };
+            let sheet_name = cell_string.strip_prefix('#').ok_or_else(|| {
+                DataError::RetrievalError(format!(
+                    "{kind_name} reference must start with '#' prefix, got: {cell_string}"
+                ))
+            })?;
+            let sheet = self.sheet(sheet_name)?;
+
+            let convert = |cell: &Data, row: usize, column: usize| match cell {
+                Data::Int(i) => Ok(DataValue::I64(*i)),
+                Data::Float(f) => Ok(DataValue::F64(*f)),
+                Data::Bool(b) => Ok(DataValue::Bool(*b)),
+                _ => Err(DataError::RetrievalError(format!(
+                    "Non-numeric cell in {kind_name} at row {row}, column {column}"
+                ))),
+            };
+
+            let mut rows = sheet.rows();
+            let headers = rows.next().ok_or_else(|| {
+                DataError::RetrievalError(format!("No axis headers found in {kind_name}"))
+            })?;
+            let x = headers
+                .iter()
+                .enumerate()
+                .skip(1)
+                .take_while(|(_, cell)| !Self::cell_is_empty(cell))
+                .map(|(column, cell)| convert(cell, 1, column + 1))
+                .collect::<Result<Vec<_>, _>>()?;
+            if x.is_empty() {
+                return Err(DataError::RetrievalError(format!(
+                    "No x axis breakpoints found in the first row of {kind_name}"
+                )));
+            }
+            let width = x.len();
+
+            let mut y = Vec::new();
+            let mut values = Vec::new();
+            for (row_index, row) in rows.enumerate() {
+                if (0..=width).all(|col| row.get(col).is_none_or(Self::cell_is_empty)) {
+                    break;
+                }
+                let row_number = row_index + 2;
+                if kind == TableKind::Map {
+                    match row.first() {
+                        Some(cell) if !Self::cell_is_empty(cell) => {
+                            y.push(convert(cell, row_number, 1)?);
+                        }
+                        _ => {
+                            return Err(DataError::RetrievalError(format!(
+                                "Missing y axis breakpoint in map at row {row_number}"
+                            )));
+                        }
+                    }
+                }
+
+                let mut vals = Vec::with_capacity(width);
+                for col in 1..=width {
+                    match row.get(col) {
+                        Some(cell) if !Self::cell_is_empty(cell) => {
+                            vals.push(convert(cell, row_number, col + 1)?);
+                        }
+                        _ => {
+                            return Err(DataError::RetrievalError(format!(
+                                "Empty cell in {kind_name} at row {row_number}, column {}",
+                                col + 1
+                            )));
+                        }
+                    }
+                }
+                values.push(vals);
+            }
+
+            Ok(LookupTable { x, y, values })
+        })
+    }
+
+    fn variant_of(&self, name: &str) -> Option<&str> {
+        let index = self.names.iter().position(|n| n == name)?;
+        self.variant_columns
+            .iter()
+            .position(|column| column.get(index).is_some_and(|v| !Self::cell_is_empty(v)))
+            .and_then(|column| self.variants.get(column))
+            .map(String::as_str)
+    }
+}
+
+fn single_value(cell: &Data) -> Result<DataValue, DataError> {
+    match cell {
+        Data::Int(i) => Ok(DataValue::I64(*i)),
+        Data::Float(f) => Ok(DataValue::F64(*f)),
+        Data::Bool(b) => Ok(DataValue::Bool(*b)),
+        _ => Err(DataError::RetrievalError(
+            "Found non-numeric single value".to_owned(),
+        )),
+    }
 }
 
 #[cfg(test)]
@@ -332,6 +437,7 @@ mod tests {
 
         let ds = ExcelDataSource {
             names: vec!["Array".to_owned()],
+            variants: vec!["Default".to_owned()],
             variant_columns: vec![vec![Data::String("#Array".to_owned())]],
             sheets,
         };
@@ -348,6 +454,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn retrieve_table_reads_axis_headers() {
+        let mut sheets = HashMap::new();
+        sheets.insert(
+            "Fuel".to_owned(),
+            Range::from_sparse(vec![
+                Cell::new((0, 0), Data::String("load \\ rpm".to_owned())),
+                Cell::new((0, 1), Data::Int(800)),
+                Cell::new((0, 2), Data::Int(1500)),
+                Cell::new((1, 0), Data::Int(20)),
+                Cell::new((1, 1), Data::Int(10)),
+                Cell::new((1, 2), Data::Float(12.5)),
+                Cell::new((2, 0), Data::Int(80)),
+                Cell::new((2, 1), Data::Int(30)),
+                Cell::new((2, 2), Data::Int(40)),
+            ]),
+        );
+        sheets.insert(
+            "Idle".to_owned(),
+            Range::from_sparse(vec![
+                Cell::new((0, 0), Data::String("coolant".to_owned())),
+                Cell::new((0, 1), Data::Int(-20)),
+                Cell::new((0, 2), Data::Int(90)),
+                Cell::new((1, 0), Data::String("target".to_owned())),
+                Cell::new((1, 1), Data::Int(1200)),
+                Cell::new((1, 2), Data::Int(800)),
+            ]),
+        );
+        let ds = ExcelDataSource {
+            names: vec!["Fuel".to_owned(), "Idle".to_owned(), "Broken".to_owned()],
+            variants: vec!["Default".to_owned()],
+            variant_columns: vec![vec![
+                Data::String("#Fuel".to_owned()),
+                Data::String("#Idle".to_owned()),
+                Data::String("#Idle".to_owned()),
+            ]],
+            sheets,
+        };
+
+        let map = ds
+            .retrieve_table("Fuel", TableKind::Map)
+            .expect("map sheet reads");
+        assert!(matches!(
+            map.x[..],
+            [DataValue::I64(800), DataValue::I64(1500)]
+        ));
+        assert!(matches!(
+            map.y[..],
+            [DataValue::I64(20), DataValue::I64(80)]
+        ));
+        assert!(matches!(
+            map.values[0][..],
+            [DataValue::I64(10), DataValue::F64(12.5)]
+        ));
+        assert_eq!(map.values.len(), 2);
+
+        let curve = ds
+            .retrieve_table("Idle", TableKind::Curve)
+            .expect("curve sheet reads");
+        assert!(curve.y.is_empty());
+        assert!(matches!(curve.values[..], [ref row] if row.len() == 2));
+
+        let err = ds
+            .retrieve_table("Broken", TableKind::Map)
+            .expect_err("a label is not a y breakpoint");
+        let cause = std::error::Error::source(&err)
+            .expect("wrapped error should carry a cause")
+            .to_string();
+        assert!(
+            cause.contains("Non-numeric cell in map at row 2, column 1"),
+            "unexpected error: {cause}"
+        );
+    }
+
     #[test]
     fn validate_unique_names_rejects_duplicate_non_empty_name() {
         let err = ExcelDataSource::validate_unique_names(&[
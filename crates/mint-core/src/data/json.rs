@@ -4,17 +4,21 @@ use std::path::Path;
 
 use super::DataSource;
 use super::error::DataError;
-use crate::layout::value::{DataValue, ValueSource};
+use crate::layout::value::{DataValue, LookupTable, TableKind, ValueSource};
 
 /// Shared JSON-based data source that reads variant data from JSON objects.
 /// Result: `Vec<HashMap<String, Value>>` in variant priority order.
 pub struct JsonDataSource {
+    variants: Vec<String>,
     variant_columns: Vec<HashMap<String, Value>>,
 }
 
 impl JsonDataSource {
-    fn new(variant_columns: Vec<HashMap<String, Value>>) -> Self {
-        JsonDataSource { variant_columns }
+    fn new(variants: Vec<String>, variant_columns: Vec<HashMap<String, Value>>) -> Self {
+        JsonDataSource {
+            variants,
+            variant_columns,
+        }
     }
 
     /// Creates a JSON data source from a JSON object.
@@ -63,13 +67,20 @@ impl JsonDataSource {
             variant_columns.push(map);
         }
 
-        Ok(Self::new(variant_columns))
+        Ok(Self::new(variants.to_vec(), variant_columns))
     }
 
     fn lookup(&self, name: &str) -> Option<&Value> {
         self.variant_columns
             .iter()
-            .find_map(|map| map.get(name).filter(|v| !v.is_null()))
+            .find_map(|map| lookup_indexed(&|key| map.get(key), name).filter(|v| !v.is_null()))
+    }
+
+    fn single_value(&self, name: &str) -> Result<DataValue, DataError> {
+        let value = self
+            .lookup(name)
+            .ok_or_else(|| DataError::RetrievalError("key not found in any variant".into()))?;
+        Self::value_to_data_value(value)
     }
 
     fn value_to_data_value(value: &Value) -> Result<DataValue, DataError> {
@@ -98,21 +109,18 @@ impl JsonDataSource {
 
 impl DataSource for JsonDataSource {
     fn retrieve_single_value(&self, name: &str) -> Result<DataValue, DataError> {
-        DataError::while_retrieving(name, || {
-            let value = self
-                .lookup(name)
-                .ok_or_else(|| DataError::RetrievalError("key not found in any variant".into()))?;
-
-            let dv = Self::value_to_data_value(value)?;
-            match dv {
-                DataValue::Str(_) => Err(DataError::RetrievalError(
-                    "Found non-numeric single value".to_owned(),
-                )),
-                _ => Ok(dv),
-            }
+        DataError::while_retrieving(name, || match self.single_value(name)? {
+            DataValue::Str(_) => Err(DataError::RetrievalError(
+                "Found non-numeric single value".to_owned(),
+            )),
+            dv => Ok(dv),
         })
     }
 
+    fn retrieve_symbol(&self, name: &str) -> Result<DataValue, DataError> {
+        DataError::while_retrieving(name, || self.single_value(name))
+    }
+
     fn retrieve_1d_array_or_string(&self, name: &str) -> Result<ValueSource, DataError> {
         DataError::while_retrieving(name, || {
             let value = self
@@ -158,4 +166,101 @@ impl DataSource for JsonDataSource {
                 .collect()
         })
     }
+
+    fn retrieve_table(&self, name: &str, kind: TableKind) -> Result<LookupTable, DataError> {
+        DataError::while_retrieving(name, || {
+            let value = self
+                .lookup(name)
+                .ok_or_else(|| DataError::RetrievalError("key not found in any variant".into()))?;
+            let Value::Object(object) = value else {
+                return Err(DataError::RetrievalError(format!(
+                    "expected an object with 'x' and 'values' for a {}",
+                    kind.name()
+                )));
+            };
+            let axes: &[&str] = match kind {
+                TableKind::Curve => &["x", "values"],
+                TableKind::Map => &["x", "y", "values"],
+            };
+            if let Some(key) = object.keys().find(|key| !axes.contains(&key.as_str())) {
+                return Err(DataError::RetrievalError(format!(
+                    "unexpected key '{key}' in {}; expected {}",
+                    kind.name(),
+                    axes.join(", ")
+                )));
+            }
+
+            let array = |key: &str| -> Result<Vec<DataValue>, DataError> {
+                match object.get(key) {
+                    None => Ok(Vec::new()),
+                    Some(Value::Array(items)) => {
+                        items.iter().map(Self::value_to_data_value).collect()
+                    }
+                    Some(_) => Err(DataError::RetrievalError(format!(
+                        "expected an array for '{key}'"
+                    ))),
+                }
+            };
+            let values = match (kind, object.get("values")) {
+                (_, None) => {
+                    return Err(DataError::RetrievalError(format!(
+                        "{} has no 'values'",
+                        kind.name()
+                    )));
+                }
+                (TableKind::Curve, Some(_)) => vec![array("values")?],
+                (TableKind::Map, Some(Value::Array(rows))) => rows
+                    .iter()
+                    .map(|row| {
+                        let Value::Array(row) = row else {
+                            return Err(DataError::RetrievalError(
+                                "expected an array for each row of map 'values'".to_owned(),
+                            ));
+                        };
+                        row.iter().map(Self::value_to_data_value).collect()
+                    })
+                    .collect::<Result<_, _>>()?,
+                (TableKind::Map, Some(_)) => {
+                    return Err(DataError::RetrievalError(
+                        "expected an array of rows for map 'values'".to_owned(),
+                    ));
+                }
+            };
+            Ok(LookupTable {
+                x: array("x")?,
+                y: array("y")?,
+                values,
+            })
+        })
+    }
+
+    fn variant_of(&self, name: &str) -> Option<&str> {
+        self.variant_columns
+            .iter()
+            .position(|map| lookup_indexed(&|key| map.get(key), name).is_some_and(|v| !v.is_null()))
+            .and_then(|index| self.variants.get(index))
+            .map(String::as_str)
+    }
+}
+
+/// Looks `name` up with `get`, falling back to indexed names such as `ch[2].Gain`, which read
+/// key `Gain` from element 2 of an array of objects under `ch`.
+fn lookup_indexed<'a>(get: &dyn Fn(&str) -> Option<&'a Value>, name: &str) -> Option<&'a Value> {
+    if let Some(value) = get(name) {
+        return Some(value);
+    }
+    let open = name.find('[')?;
+    let mut value = get(&name[..open])?;
+    let mut rest = &name[open..];
+    while let Some(indexed) = rest.strip_prefix('[') {
+        let close = indexed.find(']')?;
+        let index = indexed[..close].parse::<usize>().ok()?;
+        value = value.as_array()?.get(index)?;
+        rest = &indexed[close + 1..];
+    }
+    if rest.is_empty() {
+        return Some(value);
+    }
+    let object = value.as_object()?;
+    lookup_indexed(&|key| object.get(key), rest.strip_prefix('.')?)
 }
@@ -2,7 +2,7 @@ pub mod error;
 mod excel;
 mod json;
 
-use crate::layout::value::{DataValue, ValueSource};
+use crate::layout::value::{DataValue, LookupTable, TableKind, ValueSource};
 use error::DataError;
 pub use excel::{ExcelDataSource, ExcelDataSourceOptions};
 pub use json::JsonDataSource;
@@ -12,9 +12,68 @@ pub trait DataSource: Sync {
     /// Retrieves a single numeric or boolean value.
     fn retrieve_single_value(&self, name: &str) -> Result<DataValue, DataError>;
 
+    /// Retrieves a single value that may also be a symbol string, for `enum` fields.
+    fn retrieve_symbol(&self, name: &str) -> Result<DataValue, DataError> {
+        self.retrieve_single_value(name)
+    }
+
     /// Retrieves a 1D array (from sheet reference) or a literal string.
     fn retrieve_1d_array_or_string(&self, name: &str) -> Result<ValueSource, DataError>;
 
     /// Retrieves a 2D array from a sheet reference.
     fn retrieve_2d_array(&self, name: &str) -> Result<Vec<Vec<DataValue>>, DataError>;
+
+    /// Retrieves a curve or map with its axis breakpoints.
+    fn retrieve_table(&self, name: &str, kind: TableKind) -> Result<LookupTable, DataError> {
+        DataError::while_retrieving(name, || {
+            Err(DataError::RetrievalError(format!(
+                "this data source does not support {}s",
+                kind.name()
+            )))
+        })
+    }
+
+    /// Returns the variant that supplies `name`, for error messages, or `None` when the source
+    /// has no variants or no value for `name`.
+    fn variant_of(&self, _name: &str) -> Option<&str> {
+        None
+    }
+}
+
+/// View of a data source that prefixes every name, so leaves inside an array of structs read
+/// their element's values, such as `ch[2].Gain`.
+pub(crate) struct PrefixedDataSource<'a> {
+    pub(crate) source: &'a dyn DataSource,
+    pub(crate) prefix: &'a str,
+}
+
+impl DataSource for PrefixedDataSource<'_> {
+    fn retrieve_single_value(&self, name: &str) -> Result<DataValue, DataError> {
+        self.source
+            .retrieve_single_value(&format!("{}{name}", self.prefix))
+    }
+
+    fn retrieve_symbol(&self, name: &str) -> Result<DataValue, DataError> {
+        self.source
+            .retrieve_symbol(&format!("{}{name}", self.prefix))
+    }
+
+    fn retrieve_1d_array_or_string(&self, name: &str) -> Result<ValueSource, DataError> {
+        self.source
+            .retrieve_1d_array_or_string(&format!("{}{name}", self.prefix))
+    }
+
+    fn retrieve_2d_array(&self, name: &str) -> Result<Vec<Vec<DataValue>>, DataError> {
+        self.source
+            .retrieve_2d_array(&format!("{}{name}", self.prefix))
+    }
+
+    fn retrieve_table(&self, name: &str, kind: TableKind) -> Result<LookupTable, DataError> {
+        self.source
+            .retrieve_table(&format!("{}{name}", self.prefix), kind)
+    }
+
+    fn variant_of(&self, name: &str) -> Option<&str> {
+        self.source.variant_of(&format!("{}{name}", self.prefix))
+    }
 }
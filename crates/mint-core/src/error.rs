@@ -31,4 +31,12 @@ pub enum MintError {
         #[source]
         source: Box<MintError>,
     },
+
+    #[error("while decrypting block '{block_name}' from '{layout_file}'")]
+    InDecryptBlock {
+        block_name: String,
+        layout_file: String,
+        #[source]
+        source: Box<MintError>,
+    },
 }
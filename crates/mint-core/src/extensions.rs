@@ -0,0 +1,107 @@
+use crate::build::BuildArtifact;
+use crate::layout::block::Config;
+use crate::layout::error::LayoutError;
+use crate::layout::settings::ChecksumAlgorithm;
+use crate::output::error::OutputError;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Checksum algorithm implemented outside Mint, selected with `custom = "<name>"` in a
+/// `[mint.checksum]` config.
+///
+/// The config's `byte_order` and input word options apply as for built-in algorithms.
+pub trait IntegrityAlgorithm: Send + Sync {
+    /// Width of the value in bits: 8, 16 or 32. The field's storage type must match.
+    fn width(&self) -> u8;
+
+    /// Computes the value over the covered bytes, in the order the device feeds them. Bits
+    /// above `width` are discarded.
+    fn checksum(&self, data: &[u8]) -> u32;
+}
+
+/// Output container implemented outside Mint, rendered with [`BuildArtifact::write`].
+pub trait OutputWriter: Send + Sync {
+    fn write(&self, artifact: &BuildArtifact) -> Result<Vec<u8>, OutputError>;
+}
+
+/// Integrity algorithms and output writers registered by name on a build request.
+#[derive(Clone, Default)]
+pub struct Extensions {
+    checksums: HashMap<String, Arc<dyn IntegrityAlgorithm>>,
+    writers: HashMap<String, Arc<dyn OutputWriter>>,
+}
+
+impl std::fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Extensions")
+            .field("checksums", &sorted_names(&self.checksums))
+            .field("writers", &sorted_names(&self.writers))
+            .finish()
+    }
+}
+
+fn sorted_names<T: ?Sized>(map: &HashMap<String, Arc<T>>) -> Vec<&str> {
+    let mut names = map.keys().map(String::as_str).collect::<Vec<_>>();
+    names.sort_unstable();
+    names
+}
+
+impl Extensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a checksum algorithm for `custom = "<name>"`, returning any previous one.
+    pub fn register_checksum(
+        &mut self,
+        name: impl Into<String>,
+        algorithm: impl IntegrityAlgorithm + 'static,
+    ) -> Option<Arc<dyn IntegrityAlgorithm>> {
+        self.checksums.insert(name.into(), Arc::new(algorithm))
+    }
+
+    /// Registers an output writer for [`BuildArtifact::write`], returning any previous one.
+    pub fn register_writer(
+        &mut self,
+        name: impl Into<String>,
+        writer: impl OutputWriter + 'static,
+    ) -> Option<Arc<dyn OutputWriter>> {
+        self.writers.insert(name.into(), Arc::new(writer))
+    }
+
+    /// Attaches registered algorithms to the `custom` checksum configs of `config`.
+    ///
+    /// Builds do this for every layout. Configs naming an unregistered algorithm stay unbound and
+    /// fail only when a field uses them.
+    pub fn bind(&self, config: &mut Config) -> Result<(), LayoutError> {
+        for checksum in config.mint.checksum.values_mut() {
+            let ChecksumAlgorithm::Custom(custom) = &mut checksum.algorithm else {
+                continue;
+            };
+            let Some(algorithm) = self.checksums.get(&custom.name) else {
+                continue;
+            };
+            let width = algorithm.width();
+            if !matches!(width, 8 | 16 | 32) {
+                return Err(LayoutError::InvalidLayout(format!(
+                    "custom checksum algorithm '{}' has unsupported width {width}; must be 8, 16, or 32",
+                    custom.name
+                )));
+            }
+            custom.bind(Arc::clone(algorithm));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn writer(&self, name: &str) -> Result<&dyn OutputWriter, OutputError> {
+        self.writers
+            .get(name)
+            .map(|writer| writer.as_ref())
+            .ok_or_else(|| {
+                OutputError::WriterError(format!(
+                    "output writer '{name}' is not registered. Registered: [{}]",
+                    sorted_names(&self.writers).join(", ")
+                ))
+            })
+    }
+}
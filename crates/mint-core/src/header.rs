@@ -1,14 +1,18 @@
 use crate::build::{BlockSelector, resolve_blocks};
 use crate::error::MintError;
 use crate::layout::abi::Abi;
-use crate::layout::block::{Block, Entry};
+use crate::layout::block::{Block, Config, Entry, EnumType, StructType};
+use crate::layout::constraint::Constraints;
 use crate::layout::entry::{BitmapFieldSource, EntrySource, LeafEntry, SizeSource};
 use crate::layout::error::LayoutError;
 use crate::layout::fingerprint;
-use crate::layout::resolved::{ResolvedNode, validate_static};
+use crate::layout::resolved::{ResolvedNode, resolve_fields, validate_static};
+use crate::layout::scalar_type::{LinearScale, ScalarType};
 use crate::layout::settings::MintConfig;
+use crate::layout::value::{DataValue, ValueSource};
 use indexmap::IndexMap;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Generate a complete C11 header for the selected layout blocks.
 pub fn generate(blocks: &[BlockSelector]) -> Result<String, MintError> {
@@ -25,7 +29,27 @@ pub fn generate(blocks: &[BlockSelector]) -> Result<String, MintError> {
         .collect::<Result<HashMap<_, _>, LayoutError>>()?;
     let mut rendered = Vec::with_capacity(resolved.len());
     let mut names = NameRegistry::default();
+    let mut enums = IndexMap::new();
+    let mut types = IndexMap::new();
     let mut guard_parts = Vec::with_capacity(resolved.len());
+    let mut const_paths = Vec::new();
+    for selected in &resolved {
+        if !const_paths.contains(&&selected.layout) {
+            const_paths.push(&selected.layout);
+        }
+    }
+    let mut consts = Vec::new();
+    for path in const_paths {
+        if let Some(layout) = layouts.get(path) {
+            collect_const_macros(layout, &mut names, &mut consts).map_err(|source| {
+                MintError::InHeaderBlock {
+                    block_name: "mint.const".to_owned(),
+                    layout_file: path.display().to_string(),
+                    source: Box::new(source.into()),
+                }
+            })?;
+        }
+    }
 
     for selected in resolved {
         let layout = layouts.get(&selected.layout).ok_or_else(|| {
@@ -48,13 +72,17 @@ pub fn generate(blocks: &[BlockSelector]) -> Result<String, MintError> {
             ))
         })?;
 
-        let result = render_block(
-            &selected.name,
-            block,
-            &layout.mint,
-            block_fingerprints,
-            &mut names,
-        );
+        let result = layout.coverage_targets(&selected.name).and_then(|_| {
+            render_block(
+                &selected.name,
+                block,
+                &layout.mint,
+                block_fingerprints,
+                &mut names,
+                &mut enums,
+                &mut types,
+            )
+        });
         let block_output = result.map_err(|source| MintError::InHeaderBlock {
             block_name: selected.name.clone(),
             layout_file: selected.layout.display().to_string(),
@@ -68,10 +96,23 @@ pub fn generate(blocks: &[BlockSelector]) -> Result<String, MintError> {
     let mut output = format!(
         "#ifndef {guard}\n#define {guard}\n\n#include <limits.h>\n#include <stddef.h>\n#include <stdint.h>\n"
     );
+    if types.values().any(|struct_type| struct_type.uses_packing)
+        || rendered.iter().any(|block| block.uses_packing)
+    {
+        names.add_macro("MINT_PACKED", "packed struct attribute".to_owned())?;
+        output.push_str(
+            "\n#ifdef __GNUC__\n#define MINT_PACKED __attribute__((packed))\n#else\n#define MINT_PACKED\n#endif\n",
+        );
+    }
 
-    let macros = rendered
+    let macros = consts
         .iter()
-        .flat_map(|block| block.macros.iter())
+        .chain(
+            types
+                .values()
+                .flat_map(|struct_type: &RenderedType| struct_type.macros.iter()),
+        )
+        .chain(rendered.iter().flat_map(|block| block.macros.iter()))
         .collect::<Vec<_>>();
     if !macros.is_empty() {
         output.push('\n');
@@ -84,9 +125,13 @@ pub fn generate(blocks: &[BlockSelector]) -> Result<String, MintError> {
         }
     }
 
-    for block in &rendered {
+    for typedef in enums
+        .values()
+        .chain(types.values().map(|struct_type| &struct_type.typedef))
+        .chain(rendered.iter().map(|block| &block.typedef))
+    {
         output.push('\n');
-        output.push_str(&block.typedef);
+        output.push_str(typedef);
     }
 
     for block in &rendered {
@@ -101,10 +146,41 @@ pub fn generate(blocks: &[BlockSelector]) -> Result<String, MintError> {
 struct RenderedBlock {
     macro_prefix: String,
     macros: Vec<MacroDefinition>,
+    /// Whether the typedef declares packed structs with `MINT_PACKED`.
+    uses_packing: bool,
     typedef: String,
     assertions: String,
 }
 
+/// Shared `typedef` of a `[mint.types]` struct type and the shape macros it uses.
+struct RenderedType {
+    macros: Vec<MacroDefinition>,
+    uses_packing: bool,
+    typedef: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MacroScope {
+    /// Fields of a block: shape and fingerprint macros named by block path.
+    Block,
+    /// Fields of a struct type: shape macros named by type path.
+    Type,
+    /// Struct type fields inside a block: only the per-block fingerprint macros.
+    Fingerprints,
+}
+
+impl MacroScope {
+    /// Scope of the fields of a struct type nested in this scope, or `None` when the type's
+    /// own rendering already covers them.
+    fn nested_type(self) -> Option<Self> {
+        match self {
+            Self::Block | Self::Fingerprints => Some(Self::Fingerprints),
+            Self::Type => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq)]
 struct MacroDefinition {
     text: String,
     group_start: bool,
@@ -112,18 +188,16 @@ struct MacroDefinition {
 
 #[derive(Default)]
 struct NameRegistry {
-    block_prefixes: HashMap<String, String>,
+    prefixes: HashMap<String, String>,
     macros: HashMap<String, String>,
 }
 
 impl NameRegistry {
-    fn add_block_prefix(&mut self, prefix: &str, block: &str) -> Result<(), LayoutError> {
-        if let Some(existing) = self
-            .block_prefixes
-            .insert(prefix.to_owned(), block.to_owned())
-        {
+    /// Registers the macro prefix of a block or struct type, described by `origin`.
+    fn add_prefix(&mut self, prefix: &str, origin: String) -> Result<(), LayoutError> {
+        if let Some(existing) = self.prefixes.insert(prefix.to_owned(), origin.clone()) {
             return Err(header_error(format!(
-                "block names '{existing}' and '{block}' both convert to macro prefix '{prefix}'"
+                "{existing} and {origin} both convert to macro prefix '{prefix}'"
             )));
         }
         Ok(())
@@ -139,16 +213,83 @@ impl NameRegistry {
     }
 }
 
+/// Adds a macro for each numeric `[mint.const]` value, sorted by name. Auto-promoted block
+/// header consts, booleans, strings and arrays have no macro. A const shared by several
+/// layouts is emitted once when every layout gives it the same value.
+fn collect_const_macros(
+    layout: &Config,
+    names: &mut NameRegistry,
+    output: &mut Vec<MacroDefinition>,
+) -> Result<(), LayoutError> {
+    let promoted = layout
+        .blocks
+        .keys()
+        .flat_map(|block| [format!("{block}.start_address"), format!("{block}.length")])
+        .collect::<Vec<_>>();
+    let mut consts = layout
+        .mint
+        .consts
+        .iter()
+        .filter(|(name, _)| !promoted.contains(name))
+        .filter_map(|(name, value)| match value {
+            ValueSource::Single(value) => const_literal(value).map(|literal| (name, literal)),
+            ValueSource::Array(_) => None,
+        })
+        .collect::<Vec<_>>();
+    consts.sort_by_key(|(name, _)| *name);
+
+    for (index, (name, literal)) in consts.into_iter().enumerate() {
+        let macro_name = to_upper_snake(name, "const")?;
+        let text = format!("#define {macro_name} {literal}");
+        if output.iter().any(|existing| existing.text == text) {
+            continue;
+        }
+        add_macro(
+            names,
+            output,
+            macro_name,
+            literal,
+            format!("const '{name}'"),
+            index == 0,
+        )?;
+    }
+    Ok(())
+}
+
+/// Returns the C literal of a numeric const or limit, or `None` for values with no macro form.
+fn const_literal(value: &DataValue) -> Option<String> {
+    match *value {
+        DataValue::U64(value) => Some(unsigned_literal(value)),
+        DataValue::I64(value) => Some(match u64::try_from(value) {
+            Ok(value) => unsigned_literal(value),
+            Err(_) if value == i64::MIN => "(-INT64_C(9223372036854775807) - 1)".to_owned(),
+            Err(_) => format!("INT64_C({value})"),
+        }),
+        DataValue::F64(value) if value.is_finite() => Some(format!("{value:?}")),
+        DataValue::F64(_) | DataValue::Bool(_) | DataValue::Str(_) => None,
+    }
+}
+
+fn unsigned_literal(value: u64) -> String {
+    if value <= u64::from(u32::MAX) {
+        format!("{value}u")
+    } else {
+        format!("UINT64_C({value})")
+    }
+}
+
 fn render_block(
     block_name: &str,
     block: &Block,
     settings: &MintConfig,
     fingerprints: &IndexMap<String, u64>,
     names: &mut NameRegistry,
+    enums: &mut IndexMap<String, String>,
+    types: &mut IndexMap<String, RenderedType>,
 ) -> Result<RenderedBlock, LayoutError> {
     let typedef_name = format!("{block_name}_t");
     let macro_prefix = to_upper_snake(block_name, "block name")?;
-    names.add_block_prefix(&macro_prefix, block_name)?;
+    names.add_prefix(&macro_prefix, format!("block '{block_name}'"))?;
 
     let resolved = validate_static(block, settings)?;
 
@@ -156,10 +297,54 @@ fn render_block(
         return Err(header_error("block data must be a table"));
     };
 
+    let mut used_enums = Vec::new();
+    collect_enums(source, &mut used_enums);
+    for name in used_enums {
+        let enum_type = settings
+            .enums
+            .get(name)
+            .ok_or_else(|| header_error(format!("enum '{name}' not found in [mint.enum]")))?;
+        match enums.get(name) {
+            Some(existing) => {
+                if render_enum(enum_type, &mut NameRegistry::default())? != *existing {
+                    return Err(header_error(format!(
+                        "enum '{name}' renders differently in two selected layouts"
+                    )));
+                }
+            }
+            None => {
+                let rendered = render_enum(enum_type, names)?;
+                enums.insert(name.to_owned(), rendered);
+            }
+        }
+    }
+
+    let mut used_types = IndexMap::new();
+    collect_types(source, &mut used_types);
+    for struct_type in used_types.into_values() {
+        match types.get(&struct_type.name) {
+            Some(existing) => {
+                let rendered =
+                    render_type(struct_type, settings.abi, &mut NameRegistry::default())?;
+                if rendered.typedef != existing.typedef || rendered.macros != existing.macros {
+                    return Err(header_error(format!(
+                        "struct type '{}' renders differently in two selected layouts",
+                        struct_type.name
+                    )));
+                }
+            }
+            None => {
+                let rendered = render_type(struct_type, settings.abi, names)?;
+                types.insert(struct_type.name.clone(), rendered);
+            }
+        }
+    }
+
     let mut macros = Vec::new();
     let mut path = Vec::new();
     collect_macros(
         source,
+        MacroScope::Block,
         block_name,
         &macro_prefix,
         fingerprints,
@@ -169,31 +354,169 @@ fn render_block(
         settings.abi,
     )?;
 
-    let mut typedef = String::from("typedef struct {\n");
+    let packed = source.packed;
+    let pragma = PackPragma::new(&source.fields, packed);
+    let mut typedef = format!(
+        "{}typedef {} {{\n",
+        pragma.push(&typedef_name),
+        struct_keyword(packed)
+    );
     render_fields(
         source,
+        &resolved.root,
         1,
         &macro_prefix,
         &mut path,
         &mut typedef,
         settings.abi,
     )?;
-    typedef.push_str(&format!("}} {typedef_name};\n"));
+    typedef.push_str(&format!("}} {typedef_name};\n{}", pragma.pop()));
 
     let assertions = render_layout_assertions(block_name, &typedef_name, &resolved.root);
 
     Ok(RenderedBlock {
         macro_prefix,
         macros,
+        uses_packing: uses_packing(&resolved.root),
         typedef,
         assertions,
     })
 }
 
+/// Collects the enums used by the leaves of `fields`, including those inside struct types, in
+/// first-use order.
+fn collect_enums<'a>(fields: &'a IndexMap<String, Entry>, output: &mut Vec<&'a str>) {
+    for entry in fields.values() {
+        let entry = match entry {
+            Entry::Array(array) => &array.element,
+            entry => entry,
+        };
+        match entry {
+            Entry::Leaf(leaf) => {
+                if let Some(name) = &leaf.enum_name
+                    && !output.contains(&name.as_str())
+                {
+                    output.push(name);
+                }
+            }
+            entry => {
+                if let Some(fields) = entry.struct_fields() {
+                    collect_enums(fields, output);
+                }
+            }
+        }
+    }
+}
+
+/// Renders an enum as a C `enum` when every value is within the range C guarantees for `int`,
+/// and as macros otherwise.
+fn render_enum(enum_type: &EnumType, names: &mut NameRegistry) -> Result<String, LayoutError> {
+    let prefix = to_upper_snake(&enum_type.name, "enum name")?;
+    names.add_prefix(&prefix, format!("enum '{}'", enum_type.name))?;
+    let mut constants = Vec::with_capacity(enum_type.symbols.len());
+    for (symbol, value) in &enum_type.symbols {
+        let name = format!("{prefix}_{}", to_upper_snake(symbol, "enum symbol")?);
+        names.add_macro(&name, format!("enum symbol '{}.{symbol}'", enum_type.name))?;
+        constants.push((name, *value));
+    }
+
+    if constants
+        .iter()
+        .all(|(_, value)| (-32767..=32767).contains(value))
+    {
+        let body = constants
+            .iter()
+            .map(|(name, value)| format!("  {name} = {value}"))
+            .collect::<Vec<_>>()
+            .join(",\n");
+        return Ok(format!("enum {} {{\n{body}\n}};\n", enum_type.name));
+    }
+    let mut output = format!("/* enum {} */\n", enum_type.name);
+    for (name, value) in constants {
+        output.push_str(&format!("#define {name} INT64_C({value})\n"));
+    }
+    Ok(output)
+}
+
+/// Collects the struct types used by `fields`, each after the types its own fields use.
+fn collect_types<'a>(
+    fields: &'a IndexMap<String, Entry>,
+    output: &mut IndexMap<String, &'a Arc<StructType>>,
+) {
+    for entry in fields.values() {
+        let entry = match entry {
+            Entry::Array(array) => &array.element,
+            entry => entry,
+        };
+        match entry {
+            Entry::Branch(children) => collect_types(children, output),
+            Entry::Struct(field) => {
+                if let Some(struct_type) = field.struct_type()
+                    && !output.contains_key(&struct_type.name)
+                {
+                    collect_types(&struct_type.fields, output);
+                    output.insert(struct_type.name.clone(), struct_type);
+                }
+            }
+            Entry::Leaf(_) | Entry::Array(_) => {}
+        }
+    }
+}
+
+fn render_type(
+    struct_type: &StructType,
+    abi: Abi,
+    names: &mut NameRegistry,
+) -> Result<RenderedType, LayoutError> {
+    let typedef_name = format!("{}_t", struct_type.name);
+    let macro_prefix = to_upper_snake(&struct_type.name, "struct type name")?;
+    names.add_prefix(&macro_prefix, format!("struct type '{}'", struct_type.name))?;
+
+    let mut macros = Vec::new();
+    let mut path = Vec::new();
+    collect_macros(
+        &struct_type.fields,
+        MacroScope::Type,
+        &typedef_name,
+        &macro_prefix,
+        &IndexMap::new(),
+        names,
+        &mut path,
+        &mut macros,
+        abi,
+    )?;
+
+    let packed = struct_type.packed;
+    let resolved = resolve_fields(&struct_type.fields, packed, abi)?;
+    let pragma = PackPragma::new(&struct_type.fields, packed);
+    let mut typedef = format!(
+        "{}typedef {} {{\n",
+        pragma.push(&typedef_name),
+        struct_keyword(packed)
+    );
+    render_fields(
+        &struct_type.fields,
+        &resolved,
+        1,
+        &macro_prefix,
+        &mut path,
+        &mut typedef,
+        abi,
+    )?;
+    typedef.push_str(&format!("}} {typedef_name};\n{}", pragma.pop()));
+    Ok(RenderedType {
+        macros,
+        uses_packing: uses_packing(&resolved),
+        typedef,
+    })
+}
+
+/// Collects the macros of `fields`, which belong to block or struct type `owner`.
 #[allow(clippy::too_many_arguments)]
 fn collect_macros(
     fields: &IndexMap<String, Entry>,
-    block_name: &str,
+    scope: MacroScope,
+    owner: &str,
     block_prefix: &str,
     fingerprints: &IndexMap<String, u64>,
     names: &mut NameRegistry,
@@ -203,11 +526,35 @@ fn collect_macros(
 ) -> Result<(), LayoutError> {
     for (name, node) in fields {
         path.push(name.clone());
-        match node {
-            Entry::Branch(children) => {
+        let element = match node {
+            Entry::Array(array) => {
+                if scope != MacroScope::Fingerprints {
+                    add_dimension_macros(
+                        &array.size,
+                        names,
+                        output,
+                        &macro_path(block_prefix, path)?,
+                        format!("array '{}#{}'", owner, path.join(".")),
+                    )?;
+                }
+                &array.element
+            }
+            node => node,
+        };
+        let nested = match element {
+            Entry::Branch(branch) => Some((&branch.fields, scope)),
+            Entry::Struct(field) => field
+                .struct_type()
+                .zip(scope.nested_type())
+                .map(|(struct_type, scope)| (&struct_type.fields, scope)),
+            Entry::Leaf(_) | Entry::Array(_) => None,
+        };
+        match (element, nested) {
+            (_, Some((children, scope))) => {
                 collect_macros(
                     children,
-                    block_name,
+                    scope,
+                    owner,
                     block_prefix,
                     fingerprints,
                     names,
@@ -216,9 +563,10 @@ fn collect_macros(
                     abi,
                 )?;
             }
-            Entry::Leaf(leaf) => collect_leaf_macros(
+            (Entry::Leaf(leaf), None) => collect_leaf_macros(
                 leaf,
-                block_name,
+                scope,
+                owner,
                 block_prefix,
                 fingerprints,
                 names,
@@ -226,6 +574,7 @@ fn collect_macros(
                 output,
                 abi,
             )?,
+            _ => {}
         }
         path.pop();
     }
@@ -235,7 +584,8 @@ fn collect_macros(
 #[allow(clippy::too_many_arguments)]
 fn collect_leaf_macros(
     leaf: &LeafEntry,
-    block_name: &str,
+    scope: MacroScope,
+    owner: &str,
     block_prefix: &str,
     fingerprints: &IndexMap<String, u64>,
     names: &mut NameRegistry,
@@ -243,43 +593,38 @@ fn collect_leaf_macros(
     output: &mut Vec<MacroDefinition>,
     abi: Abi,
 ) -> Result<(), LayoutError> {
-    let size = leaf.size()?;
-
     let path_prefix = macro_path(block_prefix, path)?;
-    if let Some(size) = size {
-        match size {
-            SizeSource::OneD(length) => {
-                add_macro(
-                    names,
-                    output,
-                    format!("{path_prefix}_LEN"),
-                    format!("{length}u"),
-                    format!("array '{}#{}'", block_name, path.join(".")),
-                    true,
-                )?;
-            }
-            SizeSource::TwoD([rows, columns]) => {
-                let origin = format!("array '{}#{}'", block_name, path.join("."));
-                add_macro(
-                    names,
-                    output,
-                    format!("{path_prefix}_ROWS"),
-                    format!("{rows}u"),
-                    origin.clone(),
-                    true,
-                )?;
-                add_macro(
-                    names,
-                    output,
-                    format!("{path_prefix}_COLS"),
-                    format!("{columns}u"),
-                    origin,
-                    false,
-                )?;
-            }
-        }
+    if scope == MacroScope::Fingerprints {
+        return collect_fingerprint_macro(
+            leaf,
+            owner,
+            fingerprints,
+            names,
+            path,
+            output,
+            &path_prefix,
+        );
+    }
+
+    if let Some(size) = leaf.size()? {
+        add_dimension_macros(
+            &size,
+            names,
+            output,
+            &path_prefix,
+            format!("array '{}#{}'", owner, path.join(".")),
+        )?;
     }
 
+    add_limit_macros(
+        &leaf.constraints,
+        names,
+        output,
+        &path_prefix,
+        format!("limits of '{}#{}'", owner, path.join(".")),
+        true,
+    )?;
+
     if let EntrySource::Bitmap(fields) = &leaf.source {
         let width = abi.scalar(leaf.scalar_type)?.storage_size * 8;
         let mut shift = 0usize;
@@ -289,7 +634,7 @@ fn collect_leaf_macros(
                 let origin = format!(
                     "bitmap region '{}' in '{}#{}'",
                     data_name,
-                    block_name,
+                    owner,
                     path.join(".")
                 );
                 let prefix = format!("{path_prefix}_{region}");
@@ -306,14 +651,30 @@ fn collect_leaf_macros(
                     output,
                     format!("{prefix}_MASK"),
                     bitmap_mask(width, field.bits, shift),
-                    origin,
+                    origin.clone(),
                     false,
                 )?;
+                add_limit_macros(&field.constraints, names, output, &prefix, origin, false)?;
             }
             shift += field.bits;
         }
     }
 
+    if scope == MacroScope::Block {
+        collect_fingerprint_macro(leaf, owner, fingerprints, names, path, output, &path_prefix)?;
+    }
+    Ok(())
+}
+
+fn collect_fingerprint_macro(
+    leaf: &LeafEntry,
+    block_name: &str,
+    fingerprints: &IndexMap<String, u64>,
+    names: &mut NameRegistry,
+    path: &[String],
+    output: &mut Vec<MacroDefinition>,
+    path_prefix: &str,
+) -> Result<(), LayoutError> {
     if let EntrySource::Fingerprint(target) = &leaf.source {
         let target_name = target.block_name(block_name);
         let value = fingerprints.get(target_name).ok_or_else(|| {
@@ -335,6 +696,86 @@ fn collect_leaf_macros(
     Ok(())
 }
 
+/// Adds the `_MIN` and `_MAX` macros of a field's limits.
+fn add_limit_macros(
+    constraints: &Constraints,
+    names: &mut NameRegistry,
+    output: &mut Vec<MacroDefinition>,
+    path_prefix: &str,
+    origin: String,
+    group_start: bool,
+) -> Result<(), LayoutError> {
+    let mut group_start = group_start;
+    for (suffix, limit) in [("MIN", &constraints.min), ("MAX", &constraints.max)] {
+        if let Some(literal) = limit.as_ref().and_then(const_literal) {
+            add_macro(
+                names,
+                output,
+                format!("{path_prefix}_{suffix}"),
+                literal,
+                origin.clone(),
+                group_start,
+            )?;
+            group_start = false;
+        }
+    }
+    Ok(())
+}
+
+/// Adds the `_LEN`, or `_ROWS` and `_COLS`, macros of an array.
+fn add_dimension_macros(
+    size: &SizeSource,
+    names: &mut NameRegistry,
+    output: &mut Vec<MacroDefinition>,
+    path_prefix: &str,
+    origin: String,
+) -> Result<(), LayoutError> {
+    match size {
+        SizeSource::OneD(length) => add_macro(
+            names,
+            output,
+            format!("{path_prefix}_LEN"),
+            format!("{length}u"),
+            origin,
+            true,
+        ),
+        SizeSource::TwoD([rows, columns]) => {
+            add_macro(
+                names,
+                output,
+                format!("{path_prefix}_ROWS"),
+                format!("{rows}u"),
+                origin.clone(),
+                true,
+            )?;
+            add_macro(
+                names,
+                output,
+                format!("{path_prefix}_COLS"),
+                format!("{columns}u"),
+                origin,
+                false,
+            )
+        }
+    }
+}
+
+/// Returns the C array declarator of an array, such as `[BLOCK_TABLE_LEN]`.
+fn dimension_declarator(
+    size: Option<&SizeSource>,
+    block_prefix: &str,
+    path: &[String],
+) -> Result<String, LayoutError> {
+    Ok(match size {
+        None => String::new(),
+        Some(SizeSource::OneD(_)) => format!("[{}_LEN]", macro_path(block_prefix, path)?),
+        Some(SizeSource::TwoD(_)) => {
+            let prefix = macro_path(block_prefix, path)?;
+            format!("[{prefix}_ROWS][{prefix}_COLS]")
+        }
+    })
+}
+
 fn add_macro(
     names: &mut NameRegistry,
     output: &mut Vec<MacroDefinition>,
@@ -351,35 +792,89 @@ fn add_macro(
     Ok(())
 }
 
+/// Renders `fields` as struct members; `node` is their resolved branch, whose offsets place
+/// padding members in the gaps left by explicit field offsets.
 fn render_fields(
     fields: &IndexMap<String, Entry>,
+    node: &ResolvedNode<'_>,
     depth: usize,
     block_prefix: &str,
     path: &mut Vec<String>,
     output: &mut String,
     abi: Abi,
 ) -> Result<(), LayoutError> {
+    let ResolvedNode::Branch {
+        coordinates,
+        packed,
+        children,
+        ..
+    } = node
+    else {
+        return Err(header_error("resolved struct fields are not a branch"));
+    };
     let indent = "  ".repeat(depth);
-    for (name, node) in fields {
+    let mut end = coordinates.offset;
+    for ((name, entry), (_, child)) in fields.iter().zip(children) {
         path.push(name.clone());
-        match node {
-            Entry::Branch(children) => {
-                output.push_str(&format!("{indent}struct {{\n"));
-                render_fields(children, depth + 1, block_prefix, path, output, abi)?;
+        let child_coordinates = child.coordinates();
+        let alignment = if *packed {
+            coordinates.alignment
+        } else {
+            child_coordinates.alignment
+        };
+        // Gaps up to the next aligned offset are padding the compiler inserts itself.
+        if child_coordinates.offset - coordinates.offset
+            > (end - coordinates.offset).next_multiple_of(alignment)
+        {
+            output.push_str(&gap_member(
+                &indent,
+                end - coordinates.offset,
+                child_coordinates.offset - end,
+                name,
+                abi,
+            )?);
+        }
+        end = child_coordinates.offset + child_coordinates.size;
+        match entry {
+            Entry::Branch(branch) => {
+                output.push_str(&format!("{indent}{} {{\n", aggregate_keyword(entry)));
+                render_fields(branch, child, depth + 1, block_prefix, path, output, abi)?;
                 output.push_str(&format!("{indent}}} {name};\n"));
             }
-            Entry::Leaf(leaf) => {
-                let c_type = abi.scalar(leaf.scalar_type)?.c_type;
-                let dimensions = match leaf.size()? {
-                    None => String::new(),
-                    Some(SizeSource::OneD(_)) => {
-                        format!("[{}_LEN]", macro_path(block_prefix, path)?)
+            Entry::Struct(field) => {
+                output.push_str(&format!("{indent}{}_t {name};\n", field.type_name));
+            }
+            Entry::Array(array) => {
+                let dimensions = dimension_declarator(Some(&array.size), block_prefix, path)?;
+                match (array.element.as_ref(), child) {
+                    (Entry::Struct(field), _) => {
+                        output.push_str(&format!(
+                            "{indent}{}_t {name}{dimensions};\n",
+                            field.type_name
+                        ));
                     }
-                    Some(SizeSource::TwoD(_)) => {
-                        let prefix = macro_path(block_prefix, path)?;
-                        format!("[{prefix}_ROWS][{prefix}_COLS]")
+                    (element, ResolvedNode::Array { element: first, .. }) => {
+                        let keyword = aggregate_keyword(element);
+                        output.push_str(&format!("{indent}{keyword} {{\n"));
+                        if let Some(fields) = element.struct_fields() {
+                            render_fields(
+                                fields,
+                                first,
+                                depth + 1,
+                                block_prefix,
+                                path,
+                                output,
+                                abi,
+                            )?;
+                        }
+                        output.push_str(&format!("{indent}}} {name}{dimensions};\n"));
                     }
-                };
+                    _ => return Err(header_error("resolved array of structs is not an array")),
+                }
+            }
+            Entry::Leaf(leaf) => {
+                let c_type = abi.scalar(leaf.scalar_type)?.c_type;
+                let dimensions = dimension_declarator(leaf.size()?.as_ref(), block_prefix, path)?;
                 let comment = match &leaf.source {
                     EntrySource::Bitmap(_) => " /* bitmap storage */".to_owned(),
                     EntrySource::Ref(source) if source.is_list() => {
@@ -387,11 +882,24 @@ fn render_fields(
                     }
                     EntrySource::Ref(_) => " /* ref address */".to_owned(),
                     EntrySource::Fingerprint(_) => " /* fingerprint */".to_owned(),
-                    _ => leaf
-                        .scalar_type
-                        .fixed_point()
-                        .map(|fixed| format!(" /* {fixed} */"))
-                        .unwrap_or_default(),
+                    EntrySource::Digest(algorithm) => format!(" /* {} digest */", algorithm.name()),
+                    EntrySource::Signature(_) => " /* signature */".to_owned(),
+                    EntrySource::PublicKeyHash(_) => " /* public key SHA-256 */".to_owned(),
+                    EntrySource::Hmac(_) => " /* HMAC-SHA256 */".to_owned(),
+                    EntrySource::CrcCompensation(_) => " /* CRC compensation */".to_owned(),
+                    EntrySource::EncryptionNonce(_) => " /* encryption nonce */".to_owned(),
+                    EntrySource::EncryptionTag(_) => " /* encryption tag */".to_owned(),
+                    EntrySource::Reserved(_) => " /* reserved */".to_owned(),
+                    EntrySource::File(_) => " /* embedded file */".to_owned(),
+                    _ => match (&leaf.enum_name, &leaf.scale) {
+                        (Some(name), _) => format!(" /* enum {name} */"),
+                        (None, Some(scale)) => format!(" /* {} */", scale_formula(scale)),
+                        (None, None) => leaf
+                            .scalar_type
+                            .fixed_point()
+                            .map(|fixed| format!(" /* {fixed} */"))
+                            .unwrap_or_default(),
+                    },
                 };
                 output.push_str(&format!("{indent}{c_type} {name}{dimensions};{comment}\n"));
             }
@@ -401,6 +909,136 @@ fn render_fields(
     Ok(())
 }
 
+/// Describes a linear scale for a member comment, such as `physical = raw * 0.1 - 40`.
+fn scale_formula(scale: &LinearScale) -> String {
+    let mut formula = format!("physical = raw * {}", scale.factor);
+    if scale.offset > 0.0 {
+        formula.push_str(&format!(" + {}", scale.offset));
+    } else if scale.offset < 0.0 {
+        formula.push_str(&format!(" - {}", -scale.offset));
+    }
+    formula
+}
+
+/// Returns the keyword that opens a struct declaration, marked packed when needed.
+fn struct_keyword(packed: bool) -> &'static str {
+    if packed {
+        "struct MINT_PACKED"
+    } else {
+        "struct"
+    }
+}
+
+/// Returns the keyword that opens the declaration of an inline branch, which may be a union.
+fn aggregate_keyword(entry: &Entry) -> &'static str {
+    match entry {
+        Entry::Branch(branch) if branch.selector.is_some() => {
+            if branch.packed {
+                "union MINT_PACKED"
+            } else {
+                "union"
+            }
+        }
+        entry => struct_keyword(entry.is_packed()),
+    }
+}
+
+/// Packing of a typedef for compilers without the GNU attribute, where `MINT_PACKED` is empty.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PackPragma {
+    None,
+    /// Every inline struct and union is packed, so `#pragma pack(1)` packs exactly those.
+    Packed,
+    /// Inline structs and unions differ in packing, which the pragma cannot express.
+    Mixed,
+}
+
+impl PackPragma {
+    fn new(fields: &IndexMap<String, Entry>, packed: bool) -> Self {
+        if mixed_packing(fields, packed) {
+            Self::Mixed
+        } else if packed {
+            Self::Packed
+        } else {
+            Self::None
+        }
+    }
+
+    fn push(self, typedef_name: &str) -> String {
+        match self {
+            Self::None => String::new(),
+            Self::Packed => "#ifndef __GNUC__\n#pragma pack(push, 1)\n#endif\n".to_owned(),
+            Self::Mixed => format!(
+                "#ifndef __GNUC__\n#error \"{typedef_name} nests packed and unpacked structs, which needs __attribute__((packed))\"\n#endif\n"
+            ),
+        }
+    }
+
+    fn pop(self) -> &'static str {
+        match self {
+            Self::Packed => "#ifndef __GNUC__\n#pragma pack(pop)\n#endif\n",
+            Self::None | Self::Mixed => "",
+        }
+    }
+}
+
+/// Returns whether an inline struct or union within `fields` is packed differently from the
+/// enclosing typedef. Struct type fields are separate typedefs with their own packing.
+fn mixed_packing(fields: &IndexMap<String, Entry>, packed: bool) -> bool {
+    fields.values().any(|entry| {
+        let element = match entry {
+            Entry::Array(array) => array.element.as_ref(),
+            entry => entry,
+        };
+        match (element, element.struct_fields()) {
+            (Entry::Struct(_), _) | (_, None) => false,
+            (element, Some(fields)) => {
+                element.is_packed() != packed || mixed_packing(fields, packed)
+            }
+        }
+    })
+}
+
+/// Returns whether `node` or any branch inside it is packed.
+fn uses_packing(node: &ResolvedNode<'_>) -> bool {
+    match node {
+        ResolvedNode::Branch {
+            packed, children, ..
+        } => *packed || children.iter().any(|(_, child)| uses_packing(child)),
+        ResolvedNode::Array { element, .. } => uses_packing(element),
+        ResolvedNode::Leaf { .. } => false,
+    }
+}
+
+/// Renders the padding member that fills the `length` octets at `offset` in front of `field`,
+/// which sets an explicit offset.
+fn gap_member(
+    indent: &str,
+    offset: usize,
+    length: usize,
+    field: &str,
+    abi: Abi,
+) -> Result<String, LayoutError> {
+    // The smallest integer type is one addressable unit wide.
+    let unit = if abi.address_unit_octets() == 1 {
+        ScalarType::U8
+    } else {
+        ScalarType::U16
+    };
+    let unit = abi.scalar(unit)?;
+    if !length.is_multiple_of(unit.storage_size) {
+        return Err(header_error(format!(
+            "gap of {length} octets before '{field}' is not a whole number of {}",
+            unit.c_type
+        )));
+    }
+    Ok(format!(
+        "{indent}{} mint_gap_0x{offset:X}[{}u]; /* before '{field}' */\n",
+        unit.c_type,
+        length / unit.storage_size
+    ))
+}
+
 fn render_layout_assertions(
     block_name: &str,
     typedef_name: &str,
@@ -410,14 +1048,15 @@ fn render_layout_assertions(
     let mut path = Vec::new();
     render_node_assertions(block_name, typedef_name, root, &mut path, &mut output);
 
-    let root_size = match root {
-        ResolvedNode::Branch { coordinates, .. } | ResolvedNode::Leaf { coordinates, .. } => {
-            coordinates.size
-        }
-    };
+    let root_size = root.coordinates().size;
     output.push_str(&format!(
         "_Static_assert(sizeof({typedef_name}) * CHAR_BIT == {root_size}u * 8u, \"Mint ABI size mismatch for {typedef_name}\");\n"
     ));
+    if let ResolvedNode::Branch { packed: true, .. } = root {
+        output.push_str(&format!(
+            "_Static_assert(_Alignof({typedef_name}) == 1u, \"Mint ABI packing mismatch for {typedef_name}\");\n"
+        ));
+    }
     output
 }
 
@@ -434,16 +1073,33 @@ fn render_node_assertions(
 
     for (name, child) in children {
         path.push(name.clone());
-        let offset = match child {
-            ResolvedNode::Branch { coordinates, .. } | ResolvedNode::Leaf { coordinates, .. } => {
-                coordinates.offset
-            }
-        };
+        let offset = child.coordinates().offset;
         let member = path.join(".");
         output.push_str(&format!(
             "_Static_assert(offsetof({typedef_name}, {member}) * CHAR_BIT == {offset}u * 8u, \"Mint ABI offset mismatch for {block_name}.{member}\");\n"
         ));
-        render_node_assertions(block_name, typedef_name, child, path, output);
+        match child {
+            ResolvedNode::Array {
+                dimensions,
+                element,
+                ..
+            } => {
+                // Elements share a layout, so the first element and the stride cover them all.
+                let index = match dimensions {
+                    SizeSource::OneD(_) => "[0]",
+                    SizeSource::TwoD(_) => "[0][0]",
+                };
+                let first = format!("{member}{index}");
+                let stride = element.coordinates().size;
+                output.push_str(&format!(
+                    "_Static_assert(sizeof((({typedef_name} *)0)->{first}) * CHAR_BIT == {stride}u * 8u, \"Mint ABI stride mismatch for {block_name}.{member}\");\n"
+                ));
+                path.pop();
+                path.push(format!("{name}{index}"));
+                render_node_assertions(block_name, typedef_name, element, path, output);
+            }
+            _ => render_node_assertions(block_name, typedef_name, child, path, output),
+        }
         path.pop();
     }
 }
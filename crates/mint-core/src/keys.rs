@@ -0,0 +1,184 @@
+use crate::layout::error::LayoutError;
+use crate::layout::settings::{EncryptionConfig, HmacConfig, KeyEncoding, SignatureConfig};
+use crate::output::encryption::EncryptionKey;
+use crate::output::signature::SigningKey;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Location of key material read at build time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySource {
+    File(PathBuf),
+    /// Environment variable whose value is the key.
+    Env(String),
+}
+
+impl KeySource {
+    pub(crate) fn read(&self) -> Result<Vec<u8>, LayoutError> {
+        match self {
+            Self::File(path) => std::fs::read(path).map_err(|error| {
+                LayoutError::KeyError(format!(
+                    "failed to read key file '{}': {error}",
+                    path.display()
+                ))
+            }),
+            Self::Env(name) => std::env::var(name)
+                .map(String::into_bytes)
+                .map_err(|error| {
+                    LayoutError::KeyError(format!(
+                        "failed to read key from environment variable '{name}': {error}"
+                    ))
+                }),
+        }
+    }
+}
+
+/// Build-time key locations keyed by config name.
+///
+/// Entries override any location named in the layout, so the same layout can be signed with
+/// development and production keys.
+#[derive(Debug, Clone, Default)]
+pub struct KeyRing {
+    sources: HashMap<String, KeySource>,
+}
+
+impl KeyRing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the key for a config name, returning the previous source if one was set.
+    pub fn insert(&mut self, name: impl Into<String>, source: KeySource) -> Option<KeySource> {
+        self.sources.insert(name.into(), source)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&KeySource> {
+        self.sources.get(name)
+    }
+}
+
+/// Resolves key sources for one layout file.
+pub(crate) struct KeyContext<'a> {
+    pub(crate) ring: &'a KeyRing,
+    /// Directory that relative layout key paths resolve from.
+    pub(crate) layout_dir: &'a Path,
+}
+
+impl KeyContext<'_> {
+    /// Returns the build-time override for `name`, falling back to the layout's key location.
+    /// `layout_keys` names the layout keys that can supply one, for the missing-key error.
+    pub(crate) fn source(
+        &self,
+        kind: &str,
+        name: &str,
+        layout_source: Option<KeySource>,
+        layout_keys: &str,
+    ) -> Result<KeySource, LayoutError> {
+        if let Some(source) = self.ring.get(name) {
+            return Ok(source.clone());
+        }
+        match layout_source {
+            Some(KeySource::File(path)) => Ok(KeySource::File(self.layout_dir.join(path))),
+            Some(source) => Ok(source),
+            None => Err(LayoutError::KeyError(format!(
+                "no key for [mint.{kind}.{name}]; set {layout_keys} in the layout or pass --key {name}=PATH"
+            ))),
+        }
+    }
+
+    pub(crate) fn signing_key(
+        &self,
+        name: &str,
+        config: &SignatureConfig,
+    ) -> Result<SigningKey, LayoutError> {
+        let bytes = self
+            .source(
+                "signature",
+                name,
+                config.key_file.clone().map(KeySource::File),
+                "'key_file'",
+            )?
+            .read()?;
+        SigningKey::from_key_bytes(config.algorithm, &bytes)
+            .map_err(|error| LayoutError::KeyError(format!("[mint.signature.{name}]: {error}")))
+    }
+
+    /// Reads the secret key for `[mint.<kind>.<name>]` from its `key_file` or `key_env`. A key
+    /// file's final line break is not part of the key; `encoding` then decides how the rest is
+    /// read.
+    fn secret(
+        &self,
+        kind: &str,
+        name: &str,
+        key_file: Option<&PathBuf>,
+        key_env: Option<&String>,
+        encoding: KeyEncoding,
+    ) -> Result<Vec<u8>, LayoutError> {
+        let layout_source = match (key_file, key_env) {
+            (Some(path), _) => Some(KeySource::File(path.clone())),
+            (None, Some(var)) => Some(KeySource::Env(var.clone())),
+            (None, None) => None,
+        };
+        let source = self.source(kind, name, layout_source, "'key_file' or 'key_env'")?;
+        let mut key = source.read()?;
+        if matches!(source, KeySource::File(_)) && key.last() == Some(&b'\n') {
+            key.pop();
+            if key.last() == Some(&b'\r') {
+                key.pop();
+            }
+        }
+        match encoding {
+            KeyEncoding::Raw => Ok(key),
+            KeyEncoding::Hex => decode_hex(&key).ok_or_else(|| {
+                LayoutError::KeyError(format!(
+                    "[mint.{kind}.{name}]: key_encoding is 'hex' but the key is not an even number of hex digits"
+                ))
+            }),
+        }
+    }
+
+    /// Reads the secret key for `[mint.hmac.<name>]`.
+    pub(crate) fn hmac_key(&self, name: &str, config: &HmacConfig) -> Result<Vec<u8>, LayoutError> {
+        let key = self.secret(
+            "hmac",
+            name,
+            config.key_file.as_ref(),
+            config.key_env.as_ref(),
+            config.key_encoding,
+        )?;
+        if key.is_empty() {
+            return Err(LayoutError::KeyError(format!(
+                "[mint.hmac.{name}]: key is empty"
+            )));
+        }
+        Ok(key)
+    }
+
+    /// Reads the AES key for `[mint.encryption.<name>]`.
+    pub(crate) fn encryption_key(
+        &self,
+        name: &str,
+        config: &EncryptionConfig,
+    ) -> Result<EncryptionKey, LayoutError> {
+        let key = self.secret(
+            "encryption",
+            name,
+            config.key_file.as_ref(),
+            config.key_env.as_ref(),
+            config.key_encoding,
+        )?;
+        EncryptionKey::from_key_bytes(config.algorithm, &key)
+            .map_err(|error| LayoutError::KeyError(format!("[mint.encryption.{name}]: {error}")))
+    }
+}
+
+/// Decodes `bytes` as an even number of hex digits.
+fn decode_hex(bytes: &[u8]) -> Option<Vec<u8>> {
+    if !bytes.len().is_multiple_of(2) || !bytes.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    bytes
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
@@ -1,27 +1,33 @@
-use super::abi::{Abi, Endianness, ScalarAbi};
+use super::abi::{Abi, ScalarAbi};
+use super::curve;
 use super::entry::{EntrySource, LeafEntry, RefSource, SizeSource, append_array_element};
 use super::error::{LayoutError, in_field_path};
 use super::header::Header;
-use super::resolved::{ResolvedLayout, validate_static};
-use super::settings::MintConfig;
-use super::used_values::ValueSink;
+use super::resolved::PUBLIC_KEY_HASH_LEN;
+use super::resolved::{
+    DeferredField, EncryptionField, EncryptionFields, ResolvedLayout, check_input_words,
+    validate_static,
+};
+use super::settings::DigestAlgorithm;
+use super::settings::{ChecksumAlgorithm, MintConfig};
+pub use super::types::{EnumType, StructEntry, StructType};
+use super::used_values::{ValueSink, bytes_to_hex_json};
 use super::value::{DataValue, ValueSource};
-use crate::data::DataSource;
-use crate::output::checksum;
+use crate::data::{DataSource, PrefixedDataSource};
+use crate::keys::KeyContext;
+use crate::output::checksum::{self, ChecksumState};
+use crate::output::digest;
+use crate::output::encryption::EncryptionKey;
+use crate::output::mcuboot;
 
 use indexmap::IndexMap;
 use serde::de::{Error as _, MapAccess, Visitor};
 use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
-
-struct PendingChecksum {
-    leaf_index: usize,
-    buffer_position: usize,
-    scalar_abi: ScalarAbi,
-    config_name: String,
-    field_path: Vec<String>,
-}
+use std::ops::Range;
+use std::path::Path;
 
 struct PendingValueRecord {
     leaf_index: usize,
@@ -54,6 +60,9 @@ pub(crate) struct BuildConfig<'a> {
     pub(crate) padding: u8,
     pub(crate) strict: bool,
     pub(crate) consts: &'a HashMap<String, ValueSource>,
+    pub(crate) enums: &'a IndexMap<String, EnumType>,
+    /// Directory that relative `file` paths resolve from.
+    pub(crate) layout_dir: &'a Path,
 }
 
 pub(crate) struct BuildOutput {
@@ -67,6 +76,34 @@ pub struct Config {
     pub blocks: IndexMap<String, Block>,
 }
 
+impl Config {
+    /// Returns the blocks covered by `block_name`'s integrity fields, which must be other
+    /// blocks in this layout.
+    pub(crate) fn coverage_targets(&self, block_name: &str) -> Result<Vec<&str>, LayoutError> {
+        let Some(block) = self.blocks.get(block_name) else {
+            return Ok(Vec::new());
+        };
+        let mut targets = Vec::new();
+        for target in block.coverage_blocks() {
+            if target == block_name {
+                return Err(LayoutError::InvalidLayout(format!(
+                    "coverage block '{target}' is the containing block; use 'from'/'to' coverage instead"
+                )));
+            }
+            if !self.blocks.contains_key(target) {
+                return Err(LayoutError::BlockNotFound(format!(
+                    "coverage target '{target}' from block '{block_name}'. Available blocks: {}",
+                    self.blocks.keys().cloned().collect::<Vec<_>>().join(", ")
+                )));
+            }
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        Ok(targets)
+    }
+}
+
 impl<'de> Deserialize<'de> for Config {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -100,7 +137,11 @@ impl<'de> Deserialize<'de> for Config {
                     }
                 }
 
-                let mint = mint.ok_or_else(|| M::Error::missing_field("mint"))?;
+                let mint: MintConfig = mint.ok_or_else(|| M::Error::missing_field("mint"))?;
+                for (name, block) in &mut blocks {
+                    super::types::bind_block(block, &mint.types)
+                        .map_err(|error| M::Error::custom(format!("in block '{name}': {error}")))?;
+                }
                 Ok(Config { mint, blocks })
             }
         }
@@ -119,7 +160,83 @@ pub struct Block {
 #[derive(Debug)]
 pub enum Entry {
     Leaf(LeafEntry),
-    Branch(IndexMap<String, Entry>),
+    Branch(BranchEntry),
+    Array(ArrayEntry),
+    Struct(StructEntry),
+}
+
+impl Entry {
+    /// Returns the fields of a branch or of a bound struct type.
+    pub(crate) fn struct_fields(&self) -> Option<&IndexMap<String, Entry>> {
+        match self {
+            Entry::Branch(branch) => Some(&branch.fields),
+            Entry::Struct(field) => field.struct_type().map(|struct_type| &struct_type.fields),
+            Entry::Leaf(_) | Entry::Array(_) => None,
+        }
+    }
+
+    /// Returns whether the fields of a branch or bound struct type are packed.
+    pub(crate) fn is_packed(&self) -> bool {
+        match self {
+            Entry::Branch(branch) => branch.packed,
+            Entry::Struct(field) => field
+                .struct_type()
+                .is_some_and(|struct_type| struct_type.packed),
+            Entry::Leaf(_) | Entry::Array(_) => false,
+        }
+    }
+
+    /// Returns the explicit `offset` of the field within its enclosing struct, if set.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Entry::Leaf(leaf) => leaf.offset,
+            Entry::Branch(branch) => branch.offset,
+            Entry::Array(array) => array.offset,
+            Entry::Struct(field) => field.offset,
+        }
+    }
+}
+
+/// Table of named fields, emitted as a nested struct.
+#[derive(Debug, Default)]
+pub struct BranchEntry {
+    pub fields: IndexMap<String, Entry>,
+    /// Explicit offset within the enclosing struct.
+    pub offset: Option<usize>,
+    /// Lays the fields out without alignment padding, like a C packed struct.
+    pub packed: bool,
+    /// Makes the branch a union whose fields share its offset; the selector picks the one field
+    /// that a build populates.
+    pub selector: Option<UnionSelector>,
+}
+
+/// Source of the member name that selects the populated field of a union.
+#[derive(Debug, Clone)]
+pub enum UnionSelector {
+    Value(String),
+    Const(String),
+    Name(String),
+}
+
+impl std::ops::Deref for BranchEntry {
+    type Target = IndexMap<String, Entry>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.fields
+    }
+}
+
+/// Branch or struct type field with a `size`, repeated as an array of structs.
+#[derive(Debug)]
+pub struct ArrayEntry {
+    pub size: SizeSource,
+    /// Data-source name of the array; element leaves read `<name>[i].<leaf name>`. Defaults to
+    /// the field name.
+    pub name: Option<String>,
+    /// Explicit offset of the whole array within the enclosing struct.
+    pub offset: Option<usize>,
+    /// Element shape: an inline [`Entry::Branch`] or an [`Entry::Struct`] type reference.
+    pub element: Box<Entry>,
 }
 
 impl<'de> Deserialize<'de> for Entry {
@@ -127,7 +244,10 @@ impl<'de> Deserialize<'de> for Entry {
     where
         D: Deserializer<'de>,
     {
-        let table = toml::Table::deserialize(deserializer)?;
+        let mut table = toml::Table::deserialize(deserializer)?;
+        if curve::is_lookup_table(&table) {
+            return curve::parse_lookup_table(table).map_err(D::Error::custom);
+        }
         if matches!(table.get("type"), Some(toml::Value::String(_))) {
             return toml::Value::Table(table)
                 .try_into()
@@ -135,25 +255,265 @@ impl<'de> Deserialize<'de> for Entry {
                 .map_err(D::Error::custom);
         }
 
-        let mut branch = IndexMap::with_capacity(table.len());
-        for (name, value) in table {
-            super::validate_c_identifier(&name, "field").map_err(D::Error::custom)?;
-            let entry = value
-                .try_into()
-                .map_err(|error| D::Error::custom(format!("in data field '{name}': {error}")))?;
-            branch.insert(name, entry);
+        // `size`, `name` and `offset` are branch keys unless they hold a table, which makes them
+        // fields.
+        let mut branch_key = |key: &str| match table.get(key) {
+            Some(toml::Value::Table(_)) | None => None,
+            Some(_) => table.remove(key),
+        };
+        let size = branch_key("size");
+        let name = branch_key("name");
+        let offset = branch_key("offset");
+        let name = name
+            .map(|name| match name {
+                toml::Value::String(name) if !name.is_empty() => Ok(name),
+                _ => Err(D::Error::custom("branch 'name' must be a non-empty string")),
+            })
+            .transpose()?;
+        let offset = offset
+            .map(|offset| match offset {
+                toml::Value::Integer(offset) => usize::try_from(offset).ok(),
+                _ => None,
+            })
+            .map(|offset| {
+                offset.ok_or_else(|| {
+                    D::Error::custom("branch 'offset' must be a non-negative integer")
+                })
+            })
+            .transpose()?;
+
+        // `struct` is a C keyword, so it can never collide with a field name.
+        let element = if let Some(type_name) = table.remove("struct") {
+            let toml::Value::String(type_name) = type_name else {
+                return Err(D::Error::custom(
+                    "'struct' must name a type in [mint.types]",
+                ));
+            };
+            if let Some(key) = table.keys().next() {
+                return Err(D::Error::custom(format!(
+                    "struct field may only set 'struct', 'size', 'name' and 'offset'; found '{key}'"
+                )));
+            }
+            match size {
+                // A plain struct field reads its leaves under `name`, if given.
+                None => {
+                    let mut field = StructEntry::new(type_name, name);
+                    field.offset = offset;
+                    return Ok(Entry::Struct(field));
+                }
+                Some(_) => Entry::Struct(StructEntry::new(type_name, None)),
+            }
+        } else {
+            let packed = match table.get("packed") {
+                Some(toml::Value::Table(_)) | None => false,
+                Some(toml::Value::Boolean(packed)) => {
+                    let packed = *packed;
+                    table.remove("packed");
+                    packed
+                }
+                Some(_) => return Err(D::Error::custom("branch 'packed' must be true or false")),
+            };
+            // `union` is a C keyword as well, so it always holds the selector.
+            let selector = table
+                .remove("union")
+                .map(parse_union_selector)
+                .transpose()?;
+            Entry::Branch(BranchEntry {
+                fields: parse_fields(table)?,
+                offset: None,
+                packed,
+                selector,
+            })
+        };
+        let Some(size) = size else {
+            if name.is_some() {
+                return Err(D::Error::custom(
+                    "branch 'name' requires a 'size'; only arrays of structs read data by name",
+                ));
+            }
+            return Ok(match element {
+                Entry::Branch(branch) => Entry::Branch(BranchEntry { offset, ..branch }),
+                element => element,
+            });
+        };
+        let size = size
+            .try_into()
+            .map_err(|error| D::Error::custom(format!("invalid branch 'size': {error}")))?;
+        Ok(Entry::Array(ArrayEntry {
+            size,
+            name,
+            offset,
+            element: Box::new(element),
+        }))
+    }
+}
+
+/// Parses a `union` key: a member name, or a table naming a const or data-source value that
+/// holds one.
+fn parse_union_selector<E: serde::de::Error>(value: toml::Value) -> Result<UnionSelector, E> {
+    let selector = match value {
+        toml::Value::String(member) => Some(UnionSelector::Value(member)),
+        toml::Value::Table(table) if table.len() == 1 => {
+            table
+                .into_iter()
+                .next()
+                .and_then(|(key, value)| match (key.as_str(), value) {
+                    ("value", toml::Value::String(member)) => Some(UnionSelector::Value(member)),
+                    ("const", toml::Value::String(name)) => Some(UnionSelector::Const(name)),
+                    ("name", toml::Value::String(name)) => Some(UnionSelector::Name(name)),
+                    _ => None,
+                })
+        }
+        _ => None,
+    };
+    match selector {
+        Some(
+            UnionSelector::Value(text) | UnionSelector::Const(text) | UnionSelector::Name(text),
+        ) if text.is_empty() => Err(E::custom("union selector must not be empty")),
+        Some(selector) => Ok(selector),
+        None => Err(E::custom(
+            "'union' must be a member name or a table with one string 'value', 'const' or 'name'",
+        )),
+    }
+}
+
+fn parse_fields<E: serde::de::Error>(table: toml::Table) -> Result<IndexMap<String, Entry>, E> {
+    let mut branch = IndexMap::with_capacity(table.len());
+    for (name, value) in table {
+        super::validate_c_identifier(&name, "field").map_err(E::custom)?;
+        let entry = value
+            .try_into()
+            .map_err(|error| E::custom(format!("in data field '{name}': {error}")))?;
+        branch.insert(name, entry);
+    }
+    curve::bind_shared_axes(&mut branch).map_err(E::custom)?;
+    Ok(branch)
+}
+
+/// Emitted image of another block in the same layout, covered by `block` integrity fields.
+pub(crate) struct BlockImage {
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) length: usize,
+    pub(crate) padding: u8,
+}
+
+impl BlockImage {
+    fn covered(&self, to_length: bool) -> Cow<'_, [u8]> {
+        if to_length && self.bytes.len() < self.length {
+            let mut padded = self.bytes.clone();
+            padded.resize(self.length, self.padding);
+            Cow::Owned(padded)
+        } else {
+            Cow::Borrowed(&self.bytes)
+        }
+    }
+}
+
+/// Running checksum states by config name and coverage, so a checksum whose coverage extends
+/// an earlier one with the same config only processes the new bytes. Coverage bytes are final
+/// once their field resolves, so the saved states stay valid for the whole block.
+#[derive(Default)]
+struct ChecksumPrefixes<'a> {
+    states: Vec<(&'a str, Vec<Range<usize>>, ChecksumState)>,
+}
+
+impl<'a> ChecksumPrefixes<'a> {
+    fn checksum(
+        &mut self,
+        name: &'a str,
+        config: &ChecksumAlgorithm,
+        swapped_word: Option<usize>,
+        coverage: &[Range<usize>],
+        buffer: &[u8],
+    ) -> u32 {
+        let (mut state, remaining) = self
+            .states
+            .iter()
+            .filter(|(state_name, _, _)| *state_name == name)
+            .filter_map(|(_, done, state)| {
+                remaining_after(done, coverage).map(|remaining| (state, remaining))
+            })
+            .min_by_key(|(_, remaining)| {
+                remaining.iter().map(ExactSizeIterator::len).sum::<usize>()
+            })
+            .map(|(state, remaining)| (state.clone(), remaining))
+            .unwrap_or_else(|| (ChecksumState::new(config), coverage.to_vec()));
+        for range in remaining {
+            feed(&mut state, &buffer[range], swapped_word);
         }
-        Ok(Entry::Branch(branch))
+        let value = state.finish();
+        self.states.push((name, coverage.to_vec(), state));
+        value
+    }
+}
+
+fn feed(state: &mut ChecksumState, bytes: &[u8], swapped_word: Option<usize>) {
+    match swapped_word {
+        Some(word) => state.update_swapped(bytes, word),
+        None => state.update(bytes),
+    }
+}
+
+fn gather(buffer: &[u8], ranges: &[Range<usize>]) -> Vec<u8> {
+    ranges
+        .iter()
+        .flat_map(|range| &buffer[range.clone()])
+        .copied()
+        .collect()
+}
+
+/// Writes `data` back over `ranges` of `buffer`, the inverse of [`gather`].
+fn scatter(buffer: &mut [u8], ranges: &[Range<usize>], data: &[u8]) {
+    let mut position = 0;
+    for range in ranges {
+        buffer[range.clone()].copy_from_slice(&data[position..position + range.len()]);
+        position += range.len();
+    }
+}
+
+/// Returns the ranges still to process when the byte stream of `done` is a prefix of that of
+/// `coverage`.
+fn remaining_after(done: &[Range<usize>], coverage: &[Range<usize>]) -> Option<Vec<Range<usize>>> {
+    let (last, full) = done.split_last()?;
+    let current = coverage.get(full.len())?;
+    if coverage[..full.len()] != *full || current.start != last.start || current.end < last.end {
+        return None;
     }
+    Some(
+        std::iter::once(last.end..current.end)
+            .chain(coverage[done.len()..].iter().cloned())
+            .collect(),
+    )
 }
 
 impl Block {
+    /// Names of the blocks whose images this block's integrity fields cover, in field order.
+    pub(crate) fn coverage_blocks(&self) -> Vec<&str> {
+        fn collect<'a>(entry: &'a Entry, out: &mut Vec<&'a str>) {
+            match entry {
+                Entry::Leaf(leaf) => out.extend(leaf.coverage().block.as_deref()),
+                Entry::Branch(_) | Entry::Struct(_) => {
+                    for child in entry.struct_fields().into_iter().flat_map(IndexMap::values) {
+                        collect(child, out);
+                    }
+                }
+                Entry::Array(array) => collect(&array.element, out),
+            }
+        }
+        let mut out = Vec::new();
+        collect(&self.data, &mut out);
+        out
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn emit(
         &self,
         block_name: &str,
         fingerprints: &HashMap<String, u64>,
+        block_images: &HashMap<String, BlockImage>,
         data_source: Option<&dyn DataSource>,
         settings: &MintConfig,
+        keys: &KeyContext<'_>,
         strict: bool,
         value_sink: &mut dyn ValueSink,
     ) -> Result<BuildOutput, LayoutError> {
@@ -164,6 +524,8 @@ impl Block {
             padding: self.header.padding,
             strict,
             consts: &settings.consts,
+            enums: &settings.enums,
+            layout_dir: keys.layout_dir,
         };
         let mut buffer = Vec::new();
         buffer.try_reserve_exact(total_size).map_err(|error| {
@@ -172,17 +534,29 @@ impl Block {
             ))
         })?;
         buffer.resize(total_size, self.header.padding);
-        let mut pending_checksums = Vec::new();
         let mut pending_values = Vec::new();
+        let selected = resolved.select_union_members(data_source, &settings.consts)?;
 
         for (leaf_index, (path, coordinates, scalar_abi, leaf)) in
             resolved.emission_leaves().enumerate()
         {
+            // Union members that are not selected keep the padding byte.
+            if !resolved.is_populated(leaf_index, &selected) {
+                continue;
+            }
             let field_path = path.split('.').map(str::to_owned).collect::<Vec<_>>();
             let mut staging_sink = StagingValueSink {
                 leaf_index,
                 records: &mut pending_values,
             };
+            let prefixed;
+            let leaf_source = match (data_source, resolved.data_prefix(leaf_index)) {
+                (Some(source), prefix) if !prefix.is_empty() => {
+                    prefixed = PrefixedDataSource { source, prefix };
+                    Some(&prefixed as &dyn DataSource)
+                }
+                _ => data_source,
+            };
             let bytes = (|| -> Result<Vec<u8>, LayoutError> {
                 match &leaf.source {
                     EntrySource::Ref(_) => Self::emit_ref(
@@ -195,15 +569,24 @@ impl Block {
                         &field_path,
                     ),
                     EntrySource::Checksum(config_name) => {
-                        settings.checksum_config(config_name)?;
-                        pending_checksums.push(PendingChecksum {
-                            leaf_index,
-                            buffer_position: coordinates.offset,
-                            scalar_abi,
-                            config_name: config_name.clone(),
-                            field_path: field_path.clone(),
-                        });
-                        Ok(vec![0; scalar_abi.storage_size])
+                        let checksum_config = settings.checksum_config(config_name)?;
+                        leaf.validate_checksum_width(config_name, &checksum_config.algorithm)?;
+                        Ok(vec![0; coordinates.size])
+                    }
+                    EntrySource::Digest(_)
+                    | EntrySource::Signature(_)
+                    | EntrySource::Hmac(_)
+                    | EntrySource::CrcCompensation(_)
+                    | EntrySource::EncryptionNonce(_)
+                    | EntrySource::EncryptionTag(_) => Ok(vec![0; coordinates.size]),
+                    EntrySource::Reserved(_) => Ok(vec![config.padding; coordinates.size]),
+                    EntrySource::PublicKeyHash(name) => {
+                        let key = keys.signing_key(name, settings.signature_config(name)?)?;
+                        let hash =
+                            digest::calculate_digest(&key.public_key(), DigestAlgorithm::Sha256);
+                        debug_assert_eq!(hash.len(), PUBLIC_KEY_HASH_LEN);
+                        staging_sink.record_value(&field_path, bytes_to_hex_json(&hash))?;
+                        Ok(hash)
                     }
                     EntrySource::Fingerprint(target) => {
                         let target_name = target.block_name(block_name);
@@ -225,7 +608,7 @@ impl Block {
                         Ok(bytes)
                     }
                     _ => leaf.emit_bytes(
-                        data_source,
+                        leaf_source,
                         &config,
                         &mut staging_sink,
                         &field_path,
@@ -267,14 +650,55 @@ impl Block {
             slot.copy_from_slice(&bytes);
         }
 
+        let deferred = resolved.deferred_fields(self.header.length as usize)?;
+        if let Some(end) = deferred
+            .iter()
+            .filter_map(|field| field.coverage.last())
+            .map(|range| range.end)
+            .max()
+            .filter(|end| *end > buffer.len())
+        {
+            // `to_length` coverage includes tail padding, so emit it for the image to match.
+            buffer.resize(end, self.header.padding);
+        }
+
         let checksum_values = Self::resolve_checksums(
             &mut buffer,
-            &pending_checksums,
+            deferred,
+            block_images,
             settings,
+            keys,
             &config,
             &mut pending_values,
         )?;
 
+        if let Some(name) = &self.header.encryption {
+            let key = keys.encryption_key(name, settings.encryption_config(name)?)?;
+            let data_len = buffer.len();
+            Self::encrypt(
+                &mut buffer,
+                data_len,
+                &resolved.encryption_fields(),
+                &key,
+                &mut pending_values,
+            );
+        }
+
+        // Encrypt-then-sign: the signature covers the ciphertext and the nonce and tag.
+        if let Some(name) = &self.header.signature {
+            let key = keys.signing_key(name, settings.signature_config(name)?)?;
+            let signature = key.sign(&buffer);
+            buffer.extend_from_slice(&signature);
+        }
+
+        if let Some(image) = &self.header.mcuboot {
+            let key = match &image.signature {
+                Some(name) => Some(keys.signing_key(name, settings.signature_config(name)?)?),
+                None => None,
+            };
+            buffer = mcuboot::wrap_image(image, &buffer, settings.abi.endianness(), key.as_ref());
+        }
+
         pending_values.sort_by_key(|record| record.leaf_index);
         for record in pending_values {
             value_sink.record_value(&record.path, record.value)?;
@@ -286,6 +710,103 @@ impl Block {
         })
     }
 
+    /// Encrypts the first `data_len` bytes of `buffer` except the nonce and tag fields, then
+    /// fills those fields or appends the nonce and tag, in that order, as a trailer.
+    ///
+    /// Integrity fields are computed over the plaintext with the nonce and tag fields zeroed.
+    /// The header signature is computed afterwards, over the encrypted image.
+    fn encrypt(
+        buffer: &mut Vec<u8>,
+        data_len: usize,
+        fields: &EncryptionFields<'_>,
+        key: &EncryptionKey,
+        pending_values: &mut Vec<PendingValueRecord>,
+    ) {
+        let nonce = key.derive_nonce(buffer);
+        let ranges = fields.encrypted_ranges(data_len);
+        let mut data = gather(buffer, &ranges);
+        let tag = key.encrypt(&nonce, &mut data);
+        scatter(buffer, &ranges, &data);
+
+        let mut trailer = Vec::new();
+        for (field, bytes) in [(fields.nonce.first(), &nonce), (fields.tag.first(), &tag)] {
+            match field {
+                Some(field) => {
+                    buffer[field.span()].copy_from_slice(bytes);
+                    pending_values.push(PendingValueRecord {
+                        leaf_index: field.leaf_index,
+                        path: field.path.split('.').map(str::to_owned).collect(),
+                        value: bytes_to_hex_json(bytes),
+                    });
+                }
+                None => trailer.extend_from_slice(bytes),
+            }
+        }
+        buffer.extend_from_slice(&trailer);
+    }
+
+    /// Reverses [`Self::emit`]'s encryption of an emitted `image`, returning the plaintext data
+    /// with the nonce and tag fields zeroed, as they were when integrity fields were computed.
+    /// The encryption and signature trailers, and bytes past them such as padding up to
+    /// `header.length`, are dropped. GCM blocks fail if the tag does not match.
+    pub(crate) fn decrypt(
+        &self,
+        image: &[u8],
+        settings: &MintConfig,
+        keys: &KeyContext<'_>,
+    ) -> Result<Vec<u8>, LayoutError> {
+        let Some(name) = &self.header.encryption else {
+            return Err(LayoutError::InvalidLayout(
+                "block header has no 'encryption' config to decrypt with".to_owned(),
+            ));
+        };
+        let resolved = validate_static(self, settings)?;
+        let config = settings.encryption_config(name)?;
+        let key = keys.encryption_key(name, config)?;
+        let fields = resolved.encryption_fields();
+        let data_len = resolved.emitted_size(self.header.length as usize)?;
+        let trailer_end = data_len + fields.trailer_len(config.algorithm);
+        let image_len = trailer_end
+            + match &self.header.signature {
+                Some(signature) => settings
+                    .signature_config(signature)?
+                    .algorithm
+                    .signature_len(),
+                None => 0,
+            };
+        if image.len() < image_len {
+            return Err(LayoutError::InvalidLayout(format!(
+                "encrypted image is {} octets, but the block emits {image_len}",
+                image.len()
+            )));
+        }
+
+        let mut trailer = &image[data_len..trailer_end];
+        let mut take = |field: Option<&EncryptionField<'_>>, length: usize| -> Vec<u8> {
+            match field {
+                Some(field) => image[field.span()].to_vec(),
+                None => {
+                    let (bytes, rest) = trailer.split_at(length);
+                    trailer = rest;
+                    bytes.to_vec()
+                }
+            }
+        };
+        let nonce = take(fields.nonce.first(), config.algorithm.nonce_len());
+        let tag = take(fields.tag.first(), config.algorithm.tag_len());
+
+        let mut buffer = image[..data_len].to_vec();
+        let ranges = fields.encrypted_ranges(data_len);
+        let mut data = gather(&buffer, &ranges);
+        key.decrypt(&nonce, &mut data, &tag)
+            .map_err(|error| LayoutError::KeyError(format!("[mint.encryption.{name}]: {error}")))?;
+        scatter(&mut buffer, &ranges, &data);
+        for field in fields.nonce.iter().chain(&fields.tag) {
+            buffer[field.span()].fill(0);
+        }
+        Ok(buffer)
+    }
+
     fn emit_ref(
         leaf: &LeafEntry,
         resolved: &ResolvedLayout<'_>,
@@ -300,7 +821,7 @@ impl Block {
         };
         let mut addresses = Vec::with_capacity(source.targets().len());
         for target in source.targets() {
-            addresses.push(resolved.ref_address(target, header.start_address)?);
+            addresses.push(resolved.ref_address(target, header.data_address())?);
         }
 
         match source {
@@ -361,32 +882,141 @@ impl Block {
         }
     }
 
+    /// Fills checksum, digest, signature, HMAC and CRC compensation fields once every other field
+    /// is in the buffer, in dependency order. Returns checksum values in field order.
     fn resolve_checksums(
         buffer: &mut [u8],
-        pending_checksums: &[PendingChecksum],
+        deferred: Vec<DeferredField<'_>>,
+        block_images: &HashMap<String, BlockImage>,
         settings: &MintConfig,
+        keys: &KeyContext<'_>,
         config: &BuildConfig<'_>,
         pending_values: &mut Vec<PendingValueRecord>,
     ) -> Result<Vec<u32>, LayoutError> {
-        let mut checksum_values = Vec::with_capacity(pending_checksums.len());
-        for pending in pending_checksums {
-            let crc_config = settings.checksum_config(&pending.config_name)?;
-            let crc_val = checksum::calculate_crc(&buffer[..pending.buffer_position], crc_config);
-            let crc_bytes = match config.abi.endianness() {
-                Endianness::Big => crc_val.to_be_bytes(),
-                Endianness::Little => crc_val.to_le_bytes(),
+        let mut checksum_values = Vec::new();
+        let mut prefixes = ChecksumPrefixes::default();
+        for field in deferred {
+            let image = match field.block {
+                Some(name) => Some(block_images.get(name).ok_or_else(|| {
+                    in_field_path(
+                        field.path,
+                        LayoutError::BlockNotFound(format!(
+                            "coverage block '{name}' was not built before this block"
+                        )),
+                    )
+                })?),
+                None => None,
+            };
+            let covered = match image {
+                Some(image) => image.covered(field.leaf.coverage().to_length),
+                None if matches!(field.leaf.source, EntrySource::Checksum(_)) => {
+                    Cow::Borrowed(&[][..])
+                }
+                None => field.covered(buffer),
+            };
+            let covered = covered.as_ref();
+            let (bytes, value) = match &field.leaf.source {
+                EntrySource::Checksum(config_name) => {
+                    let checksum_config = settings.checksum_config(config_name)?;
+                    let swapped_word = checksum_config.swapped_word_octets(config.abi);
+                    let crc_val = match image {
+                        Some(_) => {
+                            check_input_words(
+                                config_name,
+                                checksum_config,
+                                std::slice::from_ref(&(0..covered.len())),
+                            )
+                            .map_err(|error| in_field_path(field.path, error))?;
+                            let mut state = ChecksumState::new(&checksum_config.algorithm);
+                            feed(&mut state, covered, swapped_word);
+                            state.finish()
+                        }
+                        None => prefixes.checksum(
+                            config_name,
+                            &checksum_config.algorithm,
+                            swapped_word,
+                            &field.coverage,
+                            buffer,
+                        ),
+                    };
+                    checksum_values.push((field.leaf_index, crc_val));
+                    let bytes = DataValue::U64(u64::from(crc_val)).to_bytes(
+                        field.leaf.scalar_type,
+                        checksum_config.stored_byte_order(config.abi),
+                        true,
+                    )?;
+                    (bytes, serde_json::Value::Number(crc_val.into()))
+                }
+                EntrySource::Digest(algorithm) => {
+                    let digest = digest::calculate_digest(covered, *algorithm);
+                    let value = bytes_to_hex_json(&digest);
+                    (digest, value)
+                }
+                EntrySource::Signature(name) => {
+                    let key = keys.signing_key(name, settings.signature_config(name)?)?;
+                    let signature = key.sign(covered);
+                    let value = bytes_to_hex_json(&signature);
+                    (signature, value)
+                }
+                EntrySource::Hmac(name) => {
+                    let key = keys.hmac_key(name, settings.hmac_config(name)?)?;
+                    let tag = digest::calculate_hmac_sha256(&key, covered);
+                    let value = bytes_to_hex_json(&tag);
+                    (tag, value)
+                }
+                EntrySource::CrcCompensation(compensation) => {
+                    let crc_config = compensation.crc_config(settings)?;
+                    let checksum_config = settings.checksum_config(&compensation.checksum)?;
+                    // Solve over the octets in the order the device feeds them, then put the
+                    // solved words back in memory order.
+                    let swapped_word = checksum_config.swapped_word_octets(config.abi);
+                    let stream = match swapped_word {
+                        Some(word) => Cow::Owned(checksum::swap_words(covered, word)),
+                        None => Cow::Borrowed(covered),
+                    };
+                    let bytes = field
+                        .covered_position(field.coordinates.offset)
+                        .and_then(|position| {
+                            checksum::crc_compensation(
+                                &stream,
+                                position,
+                                crc_config,
+                                compensation.target,
+                            )
+                        })
+                        .map(|bytes| match swapped_word {
+                            Some(word) => checksum::swap_words(&bytes, word),
+                            None => bytes,
+                        })
+                        .ok_or_else(|| {
+                            in_field_path(
+                                field.path,
+                                LayoutError::InvalidLayout(format!(
+                                    "no compensation bytes reach CRC 0x{:X} with [mint.checksum.{}]; the polynomial needs an x^0 term",
+                                    compensation.target, compensation.checksum
+                                )),
+                            )
+                        })?;
+                    let value = bytes_to_hex_json(&bytes);
+                    (bytes, value)
+                }
+                _ => unreachable!(
+                    "deferred fields are checksums, digests, signatures, HMACs or CRC compensation"
+                ),
             };
-            let size = pending.scalar_abi.storage_size;
-            buffer[pending.buffer_position..pending.buffer_position + size]
-                .copy_from_slice(&crc_bytes[..size]);
+            buffer[field.coordinates.offset..field.coordinates.offset + bytes.len()]
+                .copy_from_slice(&bytes);
             pending_values.push(PendingValueRecord {
-                leaf_index: pending.leaf_index,
-                path: pending.field_path.clone(),
-                value: serde_json::Value::Number(serde_json::Number::from(crc_val as u64)),
+                leaf_index: field.leaf_index,
+                path: field.path.split('.').map(str::to_owned).collect(),
+                value,
             });
-            checksum_values.push(crc_val);
         }
-        Ok(checksum_values)
+        checksum_values.sort_by_key(|(leaf_index, _)| *leaf_index);
+        Ok(checksum_values
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect())
     }
 }
 
@@ -440,7 +1070,19 @@ checksum_two = { checksum = "crc32", type = "u32" }
         let mut sink = RecordingSink::default();
 
         let output = config.blocks["block"]
-            .emit("block", &fingerprints, None, &config.mint, false, &mut sink)
+            .emit(
+                "block",
+                &fingerprints,
+                &HashMap::new(),
+                None,
+                &config.mint,
+                &KeyContext {
+                    ring: &crate::keys::KeyRing::default(),
+                    layout_dir: std::path::Path::new(""),
+                },
+                false,
+                &mut sink,
+            )
             .expect("block emits");
 
         assert_eq!(
@@ -481,8 +1123,13 @@ word = { value = 1, type = "u32" }
             .emit(
                 "block",
                 &HashMap::new(),
+                &HashMap::new(),
                 None,
                 &config.mint,
+                &KeyContext {
+                    ring: &crate::keys::KeyRing::default(),
+                    layout_dir: std::path::Path::new(""),
+                },
                 false,
                 &mut sink,
             )
@@ -0,0 +1,128 @@
+//! `min`, `max` and `allowed` limits on leaf and bitmap field values.
+
+use super::conversions::data_value_display;
+use super::value::DataValue;
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+/// Limits checked against a field's layout or data-source value before it is converted to the
+/// storage type. For `enum` fields, `min` and `max` compare the enum value and `allowed` may
+/// list symbols or values.
+#[derive(Debug, Default, Deserialize)]
+pub struct Constraints {
+    pub min: Option<DataValue>,
+    pub max: Option<DataValue>,
+    pub allowed: Option<Vec<DataValue>>,
+}
+
+#[derive(Clone, Copy)]
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+impl Constraints {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.min.is_none() && self.max.is_none() && self.allowed.is_none()
+    }
+
+    /// Checks that `min` and `max` are numbers in order and that `allowed` is not empty.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        for (key, limit) in [("min", &self.min), ("max", &self.max)] {
+            if let Some(limit) = limit
+                && !matches!(
+                    limit,
+                    DataValue::U64(_) | DataValue::I64(_) | DataValue::F64(_)
+                )
+            {
+                return Err(format!(
+                    "'{key}' must be a number, got {}",
+                    data_value_display(limit)
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (&self.min, &self.max)
+            && compare(min, max) == Some(Ordering::Greater)
+        {
+            return Err(format!(
+                "'min' {} is greater than 'max' {}",
+                data_value_display(min),
+                data_value_display(max)
+            ));
+        }
+        if self.allowed.as_ref().is_some_and(Vec::is_empty) {
+            return Err("'allowed' must list at least one value".to_owned());
+        }
+        Ok(())
+    }
+
+    /// Returns how `value` breaks the limits, or `None` if it satisfies them. `alias` is another
+    /// form of the same value that `allowed` also accepts, such as an enum symbol.
+    pub(crate) fn violation(&self, value: &DataValue, alias: Option<&DataValue>) -> Option<String> {
+        if let Some(allowed) = &self.allowed
+            && !allowed.iter().any(|entry| {
+                equals(entry, value) || alias.is_some_and(|alias| equals(entry, alias))
+            })
+        {
+            return Some(format!(
+                "is not one of the allowed values [{}]",
+                allowed
+                    .iter()
+                    .map(data_value_display)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        let comparable = match number(value) {
+            Some(Number::Float(value)) => !value.is_nan(),
+            Some(Number::Int(_)) => true,
+            None => false,
+        };
+        if (self.min.is_some() || self.max.is_some()) && !comparable {
+            return Some("is not a number, so it cannot be checked against 'min' or 'max'".into());
+        }
+        if let Some(min) = &self.min
+            && compare(value, min) == Some(Ordering::Less)
+        {
+            return Some(format!("is below 'min' {}", data_value_display(min)));
+        }
+        if let Some(max) = &self.max
+            && compare(value, max) == Some(Ordering::Greater)
+        {
+            return Some(format!("is above 'max' {}", data_value_display(max)));
+        }
+        None
+    }
+}
+
+fn number(value: &DataValue) -> Option<Number> {
+    match *value {
+        DataValue::Bool(value) => Some(Number::Int(i128::from(value))),
+        DataValue::U64(value) => Some(Number::Int(i128::from(value))),
+        DataValue::I64(value) => Some(Number::Int(i128::from(value))),
+        DataValue::F64(value) => Some(Number::Float(value)),
+        DataValue::Str(_) => None,
+    }
+}
+
+/// Orders two numbers exactly when both are integers; `None` for strings and NaN.
+pub(crate) fn compare(left: &DataValue, right: &DataValue) -> Option<Ordering> {
+    match (number(left)?, number(right)?) {
+        (Number::Int(left), Number::Int(right)) => Some(left.cmp(&right)),
+        (left, right) => as_float(left).partial_cmp(&as_float(right)),
+    }
+}
+
+fn as_float(number: Number) -> f64 {
+    match number {
+        Number::Int(value) => value as f64,
+        Number::Float(value) => value,
+    }
+}
+
+fn equals(left: &DataValue, right: &DataValue) -> bool {
+    match (left, right) {
+        (DataValue::Str(left), DataValue::Str(right)) => left == right,
+        (left, right) => compare(left, right) == Some(Ordering::Equal),
+    }
+}
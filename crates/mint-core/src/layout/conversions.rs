@@ -295,7 +295,7 @@ pub fn clamp_bitfield_value(
     Ok(raw.clamp(min, max))
 }
 
-fn data_value_display(value: &DataValue) -> String {
+pub(crate) fn data_value_display(value: &DataValue) -> String {
     match value {
         DataValue::Bool(value) => value.to_string(),
         DataValue::U64(value) => value.to_string(),
@@ -0,0 +1,441 @@
+//! `curve` and `map` fields: lookup-table values bound to their axis breakpoints.
+
+use super::abi::Endianness;
+use super::block::{BranchEntry, Entry};
+use super::constraint::{Constraints, compare};
+use super::conversions::data_value_display;
+use super::entry::{EntrySource, LeafEntry, SizeSource};
+use super::error::LayoutError;
+use super::scalar_type::{LinearScale, ScalarType};
+use super::value::{DataValue, LookupTable, TableKind, ValueSource};
+use crate::data::DataSource;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+/// Member of a curve or map struct that a leaf emits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TablePart {
+    X,
+    Y,
+    Values,
+}
+
+/// Source of one member of a curve or map, read from table `name` in the data source.
+#[derive(Debug, Clone)]
+pub struct TableSource {
+    pub name: String,
+    pub kind: TableKind,
+    pub part: TablePart,
+    /// `[rows, columns]` of the values; a curve has one row.
+    pub dimensions: [usize; 2],
+    /// Axes stored in `axis` fields rather than in the curve or map struct, `x` first.
+    pub shared: [Option<SharedAxis>; 2],
+}
+
+/// `axis` field holding the breakpoints of a curve or map axis.
+#[derive(Debug, Clone)]
+pub struct SharedAxis {
+    /// Path of the `axis` field, relative to the table that holds the curve or map.
+    pub field: String,
+    /// Data-source name of the axis, bound when the enclosing table is parsed.
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawLookupTable {
+    curve: Option<String>,
+    map: Option<String>,
+    #[serde(rename = "type")]
+    scalar_type: ScalarType,
+    size: SizeSource,
+    x: toml::Value,
+    y: Option<toml::Value>,
+    offset: Option<usize>,
+    min: Option<DataValue>,
+    max: Option<DataValue>,
+    allowed: Option<Vec<DataValue>>,
+    scale: Option<LinearScale>,
+}
+
+/// Storage of an axis kept inside the curve or map struct.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InlineAxis {
+    #[serde(rename = "type")]
+    scalar_type: ScalarType,
+    scale: Option<LinearScale>,
+}
+
+/// Returns whether a field table declares a curve or map.
+pub(crate) fn is_lookup_table(table: &toml::Table) -> bool {
+    ["curve", "map"]
+        .iter()
+        .any(|key| matches!(table.get(*key), Some(toml::Value::String(_))))
+}
+
+/// Parses a curve or map into a branch holding its inline axes, `x` then `y`, and its `values`.
+pub(crate) fn parse_lookup_table(table: toml::Table) -> Result<Entry, String> {
+    let raw: RawLookupTable = toml::Value::Table(table)
+        .try_into()
+        .map_err(|error| error.to_string())?;
+    let (kind, name) = match (raw.curve, raw.map) {
+        (Some(name), None) => (TableKind::Curve, name),
+        (None, Some(name)) => (TableKind::Map, name),
+        _ => return Err("a field may contain only one of 'curve' and 'map'".to_owned()),
+    };
+    if name.is_empty() {
+        return Err(format!("'{}' must name a data-source table", kind.name()));
+    }
+    let dimensions = match (kind, &raw.size) {
+        (TableKind::Curve, SizeSource::OneD(columns)) => [1, *columns],
+        (TableKind::Map, SizeSource::TwoD(extents)) => *extents,
+        (TableKind::Curve, SizeSource::TwoD(_)) => {
+            return Err("curve 'size' must be the number of x breakpoints".to_owned());
+        }
+        (TableKind::Map, SizeSource::OneD(_)) => {
+            return Err(
+                "map 'size' must be [rows, columns], the number of y and x breakpoints".to_owned(),
+            );
+        }
+    };
+    let y = match (kind, raw.y) {
+        (TableKind::Curve, Some(_)) => return Err("'y' is only valid for a map".to_owned()),
+        (TableKind::Map, None) => return Err("map requires a 'y' axis".to_owned()),
+        (_, y) => y,
+    };
+    let constraints = Constraints {
+        min: raw.min,
+        max: raw.max,
+        allowed: raw.allowed,
+    };
+    constraints.validate()?;
+    if let Some(scale) = &raw.scale {
+        scale.validate(raw.scalar_type)?;
+    }
+
+    let mut inline = Vec::new();
+    let mut shared = [None, None];
+    for (index, (label, axis)) in [("x", Some(raw.x)), ("y", y)].into_iter().enumerate() {
+        let Some(axis) = axis else {
+            continue;
+        };
+        match axis {
+            toml::Value::String(field) if !field.is_empty() => {
+                shared[index] = Some(SharedAxis { field, name: None });
+            }
+            toml::Value::Table(axis) => {
+                let axis: InlineAxis = toml::Value::Table(axis)
+                    .try_into()
+                    .map_err(|error| format!("invalid '{label}' axis: {error}"))?;
+                if let Some(scale) = &axis.scale {
+                    scale.validate(axis.scalar_type)?;
+                }
+                let (part, length) = match index {
+                    0 => (TablePart::X, dimensions[1]),
+                    _ => (TablePart::Y, dimensions[0]),
+                };
+                inline.push((label, part, axis, length));
+            }
+            _ => {
+                return Err(format!(
+                    "'{label}' must be an axis table such as {{ type = \"u16\" }} or the path of an 'axis' field"
+                ));
+            }
+        }
+    }
+
+    let source = |part| TableSource {
+        name: name.clone(),
+        kind,
+        part,
+        dimensions,
+        shared: shared.clone(),
+    };
+    let mut fields = IndexMap::with_capacity(inline.len() + 1);
+    for (label, part, axis, length) in inline {
+        let leaf = LeafEntry::table_part(
+            axis.scalar_type,
+            SizeSource::OneD(length),
+            Constraints::default(),
+            axis.scale,
+            source(part),
+        );
+        fields.insert(label.to_owned(), Entry::Leaf(leaf));
+    }
+    let values = LeafEntry::table_part(
+        raw.scalar_type,
+        raw.size,
+        constraints,
+        raw.scale,
+        source(TablePart::Values),
+    );
+    fields.insert("values".to_owned(), Entry::Leaf(values));
+    Ok(Entry::Branch(BranchEntry {
+        fields,
+        offset: raw.offset,
+        ..BranchEntry::default()
+    }))
+}
+
+/// Binds the shared axes of the curves and maps among `fields` to the `axis` fields they name,
+/// checking that each axis has one breakpoint per row or column.
+pub(crate) fn bind_shared_axes(fields: &mut IndexMap<String, Entry>) -> Result<(), String> {
+    let mut bindings = Vec::new();
+    for (field_name, entry) in fields.iter() {
+        let Some(table) = table_source(entry) else {
+            continue;
+        };
+        for (index, axis) in table.shared.iter().enumerate() {
+            let Some(axis) = axis.as_ref().filter(|axis| axis.name.is_none()) else {
+                continue;
+            };
+            let (label, length) = match index {
+                0 => ("x", table.dimensions[1]),
+                _ => ("y", table.dimensions[0]),
+            };
+            let axis_name = shared_axis_name(fields, &axis.field, length).map_err(|error| {
+                format!(
+                    "in data field '{field_name}': {label} axis '{}' {error}",
+                    axis.field
+                )
+            })?;
+            bindings.push((field_name.clone(), index, axis_name));
+        }
+    }
+
+    for (field_name, index, axis_name) in bindings {
+        let Some(Entry::Branch(branch)) = fields.get_mut(&field_name) else {
+            continue;
+        };
+        for entry in branch.fields.values_mut() {
+            if let Entry::Leaf(leaf) = entry
+                && let EntrySource::Table(table) = &mut leaf.source
+                && let Some(axis) = &mut table.shared[index]
+            {
+                axis.name = Some(axis_name.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the table source of a parsed curve or map branch.
+fn table_source(entry: &Entry) -> Option<&TableSource> {
+    let Entry::Branch(branch) = entry else {
+        return None;
+    };
+    branch.fields.values().find_map(|entry| match entry {
+        Entry::Leaf(LeafEntry {
+            source: EntrySource::Table(table),
+            ..
+        }) => Some(table.as_ref()),
+        _ => None,
+    })
+}
+
+fn shared_axis_name(
+    fields: &IndexMap<String, Entry>,
+    path: &str,
+    length: usize,
+) -> Result<String, String> {
+    let not_axis = || "must name an 'axis' field in the same table".to_owned();
+    let mut segments = path.split('.');
+    let mut entry = segments.next().and_then(|segment| fields.get(segment));
+    for segment in segments {
+        entry = match entry {
+            Some(Entry::Branch(branch)) => branch.fields.get(segment),
+            _ => None,
+        };
+    }
+    let Some(Entry::Leaf(leaf)) = entry else {
+        return Err(not_axis());
+    };
+    let EntrySource::Axis(name) = &leaf.source else {
+        return Err(not_axis());
+    };
+    match leaf.size().map_err(|error| error.to_string())? {
+        Some(SizeSource::OneD(size)) if size == length => Ok(name.clone()),
+        Some(SizeSource::OneD(size)) => Err(format!(
+            "has {size} breakpoints, but the table needs {length}"
+        )),
+        _ => Err("requires a one-dimensional size".to_owned()),
+    }
+}
+
+impl TableSource {
+    /// Reads the table and checks its dimensions and axes, filling shared axes from their
+    /// `axis` fields when the data source does not hold them.
+    pub(crate) fn load(&self, data_source: &dyn DataSource) -> Result<LookupTable, LayoutError> {
+        let mut table = data_source.retrieve_table(&self.name, self.kind)?;
+        let kind = self.kind.name();
+        let name = &self.name;
+        let [rows, columns] = self.dimensions;
+        if table.values.len() != rows {
+            return Err(export_error(format!(
+                "{kind} '{name}' has {} value rows, expected {rows}",
+                table.values.len()
+            )));
+        }
+        for (index, row) in table.values.iter().enumerate() {
+            if row.len() != columns {
+                let location = match self.kind {
+                    TableKind::Curve => String::new(),
+                    TableKind::Map => format!(" row {index}"),
+                };
+                return Err(export_error(format!(
+                    "{kind} '{name}'{location} has {} values, expected {columns}",
+                    row.len()
+                )));
+            }
+        }
+
+        let axes = match self.kind {
+            TableKind::Curve => 1,
+            TableKind::Map => 2,
+        };
+        let [x, y] = &self.shared;
+        for (label, breakpoints, length, shared) in [
+            ("x", &mut table.x, columns, x),
+            ("y", &mut table.y, rows, y),
+        ]
+        .into_iter()
+        .take(axes)
+        {
+            match shared {
+                Some(shared) => {
+                    let Some(axis_name) = &shared.name else {
+                        unreachable!("shared axes are bound when the layout is parsed")
+                    };
+                    let expected = read_axis(data_source, axis_name, length)?;
+                    if !breakpoints.is_empty()
+                        && let Some(index) = (0..expected.len().max(breakpoints.len()))
+                            .find(|&index| !same_value(breakpoints.get(index), expected.get(index)))
+                    {
+                        let found = breakpoints
+                            .get(index)
+                            .map_or("nothing".to_owned(), data_value_display);
+                        let wanted = expected
+                            .get(index)
+                            .map_or("nothing".to_owned(), data_value_display);
+                        return Err(export_error(format!(
+                            "{label} axis of {kind} '{name}' does not match shared axis '{}': {found} at index {index}, expected {wanted}",
+                            shared.field
+                        )));
+                    }
+                    *breakpoints = expected;
+                }
+                None if breakpoints.is_empty() => {
+                    return Err(export_error(format!("{kind} '{name}' has no {label} axis")));
+                }
+                None => check_breakpoints(breakpoints, length).map_err(|error| {
+                    export_error(format!("{label} axis of {kind} '{name}' {error}"))
+                })?,
+            }
+        }
+        Ok(table)
+    }
+}
+
+/// Reads the breakpoints of an `axis` field and checks that there are `length` of them in
+/// strictly increasing order.
+pub(crate) fn read_axis(
+    data_source: &dyn DataSource,
+    name: &str,
+    length: usize,
+) -> Result<Vec<DataValue>, LayoutError> {
+    let ValueSource::Array(breakpoints) = data_source.retrieve_1d_array_or_string(name)? else {
+        return Err(export_error(format!(
+            "axis '{name}' must be an array of breakpoints, not a string"
+        )));
+    };
+    check_breakpoints(&breakpoints, length)
+        .map_err(|error| export_error(format!("axis '{name}' {error}")))?;
+    Ok(breakpoints)
+}
+
+fn check_breakpoints(breakpoints: &[DataValue], length: usize) -> Result<(), String> {
+    if breakpoints.len() != length {
+        return Err(format!(
+            "has {} breakpoints, expected {length}",
+            breakpoints.len()
+        ));
+    }
+    for (index, breakpoint) in breakpoints.iter().enumerate() {
+        if compare(breakpoint, breakpoint).is_none() {
+            return Err(format!(
+                "has non-numeric breakpoint {} at index {index}",
+                data_value_display(breakpoint)
+            ));
+        }
+        if let Some(previous) = index.checked_sub(1).and_then(|i| breakpoints.get(i))
+            && compare(previous, breakpoint) != Some(Ordering::Less)
+        {
+            return Err(format!(
+                "is not strictly increasing: {} at index {index} follows {}",
+                data_value_display(breakpoint),
+                data_value_display(previous)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that breakpoints stay strictly increasing once encoded as `scalar_type`, which
+/// rounding or a `scale` can break: `[1.2, 1.4]` both store as 1 in a `u8` axis.
+pub(crate) fn check_stored_breakpoints(
+    breakpoints: &[DataValue],
+    encoded: &[Vec<u8>],
+    scalar_type: ScalarType,
+    endianness: Endianness,
+) -> Result<(), String> {
+    let stored = encoded
+        .iter()
+        .map(|bytes| stored_value(bytes, scalar_type, endianness))
+        .collect::<Vec<_>>();
+    for (index, pair) in stored.windows(2).enumerate() {
+        if compare(&pair[0], &pair[1]) != Some(Ordering::Less) {
+            return Err(format!(
+                "is not strictly increasing once stored as {}: {} at index {} is stored as {}, after {}",
+                scalar_type.name(),
+                data_value_display(&breakpoints[index + 1]),
+                index + 1,
+                data_value_display(&pair[1]),
+                data_value_display(&pair[0])
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Value held by the encoded bytes of one element: the float, or the raw integer of integer and
+/// fixed-point types.
+fn stored_value(bytes: &[u8], scalar_type: ScalarType, endianness: Endianness) -> DataValue {
+    let fold = |bits: u64, byte: &u8| (bits << 8) | u64::from(*byte);
+    let bits = match endianness {
+        Endianness::Little => bytes.iter().rev().fold(0, fold),
+        Endianness::Big => bytes.iter().fold(0, fold),
+    };
+    let signed = scalar_type.is_signed() || scalar_type.fixed_point().is_some_and(|f| f.signed);
+    match scalar_type {
+        ScalarType::F32 => DataValue::F64(f64::from(f32::from_bits(bits as u32))),
+        ScalarType::F64 => DataValue::F64(f64::from_bits(bits)),
+        _ if signed => {
+            let unused = 64 - bytes.len() * 8;
+            DataValue::I64(((bits << unused) as i64) >> unused)
+        }
+        _ => DataValue::U64(bits),
+    }
+}
+
+fn same_value(left: Option<&DataValue>, right: Option<&DataValue>) -> bool {
+    match (left, right) {
+        (Some(left), Some(right)) => compare(left, right) == Some(Ordering::Equal),
+        _ => false,
+    }
+}
+
+fn export_error(message: String) -> LayoutError {
+    LayoutError::DataValueExportFailed(message)
+}
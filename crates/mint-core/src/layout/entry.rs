@@ -1,15 +1,22 @@
-use super::abi::{Endianness, ScalarAbi};
+use super::abi::{EndianBytes, Endianness, ScalarAbi};
 use super::block::BuildConfig;
-use super::conversions::clamp_bitfield_value;
+use super::constraint::Constraints;
+use super::conversions::{clamp_bitfield_value, data_value_display};
+use super::curve::{self, TablePart, TableSource};
 use super::error::LayoutError;
-use super::scalar_type::{ScalarType, fixed_point_unsupported_error};
+use super::file::FileSource;
+use super::scalar_type::{LinearScale, ScalarType, fixed_point_unsupported_error};
+use super::settings::{ChecksumAlgorithm, CrcConfig, DigestAlgorithm, MintConfig};
+use super::types::{EnumType, enum_type};
 use super::used_values::{
-    ValueSink, array_2d_to_json, array_to_json, data_value_to_json, i128_to_json,
+    ValueSink, array_2d_to_json, array_to_json, bytes_to_hex_json, data_value_to_json, i128_to_json,
 };
-use super::value::{DataValue, ValueSource};
+use super::value::{DataValue, TableKind, ValueSource};
 use crate::data::DataSource;
+use indexmap::IndexMap;
 use serde::de::Error as _;
 use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 const LEAF_SOURCE_KEYS: &[&str] = &[
@@ -20,11 +27,32 @@ const LEAF_SOURCE_KEYS: &[&str] = &[
     "checksum",
     "const",
     "fingerprint",
+    "digest",
+    "signature",
+    "public_key_hash",
+    "hmac",
+    "crc_compensation",
+    "encryption_nonce",
+    "encryption_tag",
+    "reserved",
+    "axis",
+    "file",
 ];
 const LEAF_KEYS: &[&str] = &[
     "type",
     "size",
     "SIZE",
+    "offset",
+    "enum",
+    "from",
+    "to",
+    "to_length",
+    "exclude",
+    "block",
+    "min",
+    "max",
+    "allowed",
+    "scale",
     "name",
     "value",
     "bitmap",
@@ -32,15 +60,34 @@ const LEAF_KEYS: &[&str] = &[
     "checksum",
     "const",
     "fingerprint",
+    "digest",
+    "signature",
+    "public_key_hash",
+    "hmac",
+    "crc_compensation",
+    "encryption_nonce",
+    "encryption_tag",
+    "reserved",
+    "axis",
+    "file",
 ];
 const BITMAP_SOURCE_KEYS: &[&str] = &["name", "value"];
-const BITMAP_KEYS: &[&str] = &["bits", "name", "value"];
+const BITMAP_KEYS: &[&str] = &["bits", "min", "max", "allowed", "name", "value"];
 
 /// Leaf entry representing an item to add to the flash block.
 #[derive(Debug)]
 pub struct LeafEntry {
     pub scalar_type: ScalarType,
+    /// `[mint.enum]` entry whose symbols the leaf's values may use.
+    pub enum_name: Option<String>,
+    /// Explicit offset within the enclosing struct.
+    pub offset: Option<usize>,
     size_keys: SizeKeys,
+    coverage: Coverage,
+    /// Limits on the value before conversion.
+    pub constraints: Box<Constraints>,
+    /// Conversion from the physical value to the stored raw integer.
+    pub scale: Option<LinearScale>,
     pub source: EntrySource,
 }
 
@@ -48,8 +95,18 @@ pub struct LeafEntry {
 struct RawLeafEntry {
     #[serde(rename = "type")]
     scalar_type: ScalarType,
+    #[serde(rename = "enum", default)]
+    enum_name: Option<String>,
+    #[serde(default)]
+    offset: Option<usize>,
     #[serde(flatten, default)]
     size_keys: SizeKeys,
+    #[serde(flatten, default)]
+    coverage: Coverage,
+    #[serde(flatten, default)]
+    constraints: Box<Constraints>,
+    #[serde(default)]
+    scale: Option<LinearScale>,
     #[serde(flatten)]
     source: EntrySource,
 }
@@ -71,9 +128,80 @@ impl<'de> Deserialize<'de> for LeafEntry {
         let raw: RawLeafEntry = toml::Value::Table(table)
             .try_into()
             .map_err(D::Error::custom)?;
+        if !raw.coverage.is_default()
+            && !matches!(
+                raw.source,
+                EntrySource::Checksum(_)
+                    | EntrySource::Digest(_)
+                    | EntrySource::Signature(_)
+                    | EntrySource::Hmac(_)
+                    | EntrySource::CrcCompensation(_)
+            )
+        {
+            return Err(D::Error::custom(
+                "coverage keys ('from', 'to', 'to_length', 'exclude', 'block') are only valid with a 'checksum', 'digest', 'signature', 'hmac' or 'crc_compensation' source",
+            ));
+        }
+        if raw.coverage.block.is_some() && matches!(raw.source, EntrySource::CrcCompensation(_)) {
+            return Err(D::Error::custom(
+                "'block' coverage is not valid with 'crc_compensation'; the covered range must contain the field",
+            ));
+        }
+        if raw.coverage.block.is_some()
+            && (raw.coverage.from.is_some()
+                || raw.coverage.to.is_some()
+                || !raw.coverage.exclude.is_empty())
+        {
+            return Err(D::Error::custom(
+                "'block' coverage cannot be combined with 'from', 'to' or 'exclude'",
+            ));
+        }
+        if raw.coverage.to.is_some() && raw.coverage.to_length {
+            return Err(D::Error::custom(
+                "leaf may contain only one coverage end; found 'to' and 'to_length'",
+            ));
+        }
+        if !raw.constraints.is_empty()
+            && !matches!(
+                raw.source,
+                EntrySource::Name(_) | EntrySource::Value(_) | EntrySource::Const(_)
+            )
+        {
+            return Err(D::Error::custom(
+                "'min', 'max' and 'allowed' are only valid with a 'name', 'value' or 'const' source",
+            ));
+        }
+        raw.constraints.validate().map_err(D::Error::custom)?;
+        if let Some(scale) = &raw.scale {
+            if !matches!(
+                raw.source,
+                EntrySource::Name(_)
+                    | EntrySource::Value(_)
+                    | EntrySource::Const(_)
+                    | EntrySource::Axis(_)
+            ) {
+                return Err(D::Error::custom(
+                    "'scale' is only valid with a 'name', 'value', 'const' or 'axis' source",
+                ));
+            }
+            if raw.enum_name.is_some() {
+                return Err(D::Error::custom("'scale' cannot be combined with 'enum'"));
+            }
+            scale.validate(raw.scalar_type).map_err(D::Error::custom)?;
+        }
+        if matches!(raw.source, EntrySource::Reserved(false)) {
+            return Err(D::Error::custom(
+                "'reserved' must be true; remove it to declare a regular field",
+            ));
+        }
         Ok(Self {
             scalar_type: raw.scalar_type,
+            enum_name: raw.enum_name,
+            offset: raw.offset,
             size_keys: raw.size_keys,
+            coverage: raw.coverage,
+            constraints: raw.constraints,
+            scale: raw.scale,
             source: raw.source,
         })
     }
@@ -109,6 +237,34 @@ impl SizeKeys {
     }
 }
 
+/// Field paths bounding the bytes covered by a deferred integrity field.
+///
+/// `from` starts at the named field's first byte and `to` ends after the named field's last
+/// byte; `to_length` instead ends at the configured `header.length`, covering tail padding.
+/// Fields named in `exclude` are skipped. Without them, coverage runs from the block start up to
+/// the field itself. `block` instead covers another block's emitted image, padded to its
+/// `header.length` with `to_length`.
+#[derive(Debug, Default, Deserialize)]
+pub struct Coverage {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    #[serde(default)]
+    pub to_length: bool,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    pub block: Option<String>,
+}
+
+impl Coverage {
+    pub(crate) fn is_default(&self) -> bool {
+        self.from.is_none()
+            && self.to.is_none()
+            && !self.to_length
+            && self.exclude.is_empty()
+            && self.block.is_none()
+    }
+}
+
 /// Mutually exclusive source enum.
 #[derive(Debug, Deserialize)]
 pub enum EntrySource {
@@ -126,6 +282,64 @@ pub enum EntrySource {
     Const(String),
     #[serde(rename = "fingerprint")]
     Fingerprint(FingerprintTarget),
+    #[serde(rename = "digest")]
+    Digest(DigestAlgorithm),
+    #[serde(rename = "signature")]
+    Signature(String),
+    #[serde(rename = "public_key_hash")]
+    PublicKeyHash(String),
+    #[serde(rename = "hmac")]
+    Hmac(String),
+    #[serde(rename = "crc_compensation")]
+    CrcCompensation(CrcCompensation),
+    #[serde(rename = "encryption_nonce")]
+    EncryptionNonce(String),
+    #[serde(rename = "encryption_tag")]
+    EncryptionTag(String),
+    /// Space kept for future use, filled with the block's padding byte.
+    #[serde(rename = "reserved")]
+    Reserved(bool),
+    /// Strictly increasing breakpoints shared by the curves and maps that name the field.
+    #[serde(rename = "axis")]
+    Axis(String),
+    /// Bytes of an external file, one per `u8` or `u16` element.
+    #[serde(rename = "file")]
+    File(FileSource),
+    /// Axis or values of a `curve` or `map` field.
+    #[serde(skip)]
+    Table(Box<TableSource>),
+}
+
+/// Bytes solved so that the CRC over the field's coverage, including the field, equals `target`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CrcCompensation {
+    /// CRC config in `[mint.checksum]`.
+    pub checksum: String,
+    pub target: u32,
+}
+
+impl CrcCompensation {
+    /// Returns the referenced CRC parameters, checking that `target` fits the CRC width.
+    pub(crate) fn crc_config<'c>(
+        &self,
+        settings: &'c MintConfig,
+    ) -> Result<&'c CrcConfig, LayoutError> {
+        let ChecksumAlgorithm::Crc(config) = &settings.checksum_config(&self.checksum)?.algorithm
+        else {
+            return Err(LayoutError::InvalidLayout(format!(
+                "CRC compensation requires a CRC; [mint.checksum.{}] is not a CRC config.",
+                self.checksum
+            )));
+        };
+        if self.target & !(u32::MAX >> (32 - u32::from(config.width))) != 0 {
+            return Err(LayoutError::InvalidLayout(format!(
+                "CRC compensation target 0x{:X} does not fit the {}-bit CRC width.",
+                self.target, config.width
+            )));
+        }
+        Ok(config)
+    }
 }
 
 /// One address source within a scalar ref or reflist.
@@ -249,12 +463,16 @@ impl<'de> Deserialize<'de> for FingerprintTarget {
 #[derive(Debug)]
 pub struct BitmapField {
     pub bits: usize,
+    /// Limits on the value before it is clamped to `bits`.
+    pub constraints: Constraints,
     pub source: BitmapFieldSource,
 }
 
 #[derive(Deserialize)]
 struct RawBitmapField {
     bits: usize,
+    #[serde(flatten, default)]
+    constraints: Constraints,
     #[serde(flatten)]
     source: BitmapFieldSource,
 }
@@ -269,8 +487,10 @@ impl<'de> Deserialize<'de> for BitmapField {
         let raw: RawBitmapField = toml::Value::Table(table)
             .try_into()
             .map_err(D::Error::custom)?;
+        raw.constraints.validate().map_err(D::Error::custom)?;
         Ok(Self {
             bits: raw.bits,
+            constraints: raw.constraints,
             source: raw.source,
         })
     }
@@ -356,10 +576,37 @@ impl BitmapField {
 }
 
 impl LeafEntry {
+    /// Builds an axis or the values of a `curve` or `map` field.
+    pub(crate) fn table_part(
+        scalar_type: ScalarType,
+        size: SizeSource,
+        constraints: Constraints,
+        scale: Option<LinearScale>,
+        source: TableSource,
+    ) -> Self {
+        Self {
+            scalar_type,
+            enum_name: None,
+            offset: None,
+            size_keys: SizeKeys {
+                size: Some(size),
+                strict_size: None,
+            },
+            coverage: Coverage::default(),
+            constraints: Box::new(constraints),
+            scale,
+            source: EntrySource::Table(Box::new(source)),
+        }
+    }
+
     pub(crate) fn size(&self) -> Result<Option<SizeSource>, LayoutError> {
         self.size_keys.resolve().map(|(size, _)| size)
     }
 
+    pub(crate) fn coverage(&self) -> &Coverage {
+        &self.coverage
+    }
+
     /// Returns the alignment of the leaf entry.
     pub fn get_alignment(&self, scalar_abi: ScalarAbi) -> usize {
         scalar_abi.alignment
@@ -373,6 +620,19 @@ impl LeafEntry {
         field_path: &[String],
         scalar_abi: ScalarAbi,
     ) -> Result<Vec<u8>, LayoutError> {
+        if let EntrySource::Table(table) = &self.source {
+            return self.emit_table_part(
+                table,
+                data_source,
+                config,
+                value_sink,
+                field_path,
+                scalar_abi,
+            );
+        }
+        if let EntrySource::File(file) = &self.source {
+            return self.emit_file(file, config, value_sink, field_path, scalar_abi);
+        }
         if let EntrySource::Bitmap(fields) = &self.source {
             return self.emit_bitmap(
                 fields,
@@ -522,9 +782,12 @@ impl LeafEntry {
                 "size/SIZE keys are forbidden with checksum.".into(),
             ));
         }
-        if !matches!(self.scalar_type, ScalarType::U32) {
+        if !matches!(
+            self.scalar_type,
+            ScalarType::U8 | ScalarType::U16 | ScalarType::U32
+        ) {
             return Err(LayoutError::InvalidLayout(format!(
-                "Checksum type must be u32 (4 bytes), got {} ({} bytes).",
+                "Checksum type must be u8, u16 or u32, got {} ({} bytes).",
                 self.scalar_type.name(),
                 self.scalar_type.size_bytes()
             )));
@@ -532,6 +795,74 @@ impl LeafEntry {
         Ok(())
     }
 
+    /// Validates that checksum storage matches the referenced algorithm's width. A custom
+    /// algorithm must be bound by the build request first.
+    pub(crate) fn validate_checksum_width(
+        &self,
+        name: &str,
+        config: &ChecksumAlgorithm,
+    ) -> Result<(), LayoutError> {
+        let Some(width) = config.width() else {
+            let custom = match config {
+                ChecksumAlgorithm::Custom(custom) => custom.name.as_str(),
+                _ => unreachable!("only custom algorithms are unbound"),
+            };
+            return Err(LayoutError::InvalidLayout(format!(
+                "[mint.checksum.{name}] uses custom algorithm '{custom}', which is not registered on the build request"
+            )));
+        };
+        let storage_bits = self.scalar_type.size_bytes() * 8;
+        if storage_bits != usize::from(width) {
+            return Err(LayoutError::InvalidLayout(format!(
+                "Checksum type {} ({storage_bits} bits) does not match the {width}-bit checksum width.",
+                self.scalar_type.name(),
+            )));
+        }
+        Ok(())
+    }
+
+    /// Validates CRC compensation storage: a `u8`, `u16` or `u32` scalar, or a `u8` array.
+    pub(crate) fn validate_compensation_storage(&self) -> Result<(), LayoutError> {
+        let array = self.size_keys.size.is_some() || self.size_keys.strict_size.is_some();
+        let valid = if array {
+            self.scalar_type == ScalarType::U8
+        } else {
+            matches!(
+                self.scalar_type,
+                ScalarType::U8 | ScalarType::U16 | ScalarType::U32
+            )
+        };
+        if !valid {
+            return Err(LayoutError::InvalidLayout(format!(
+                "CRC compensation type must be u8, u16, u32 or a u8 array, got {}.",
+                self.scalar_type.name()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Validates that digest, signature or key-hash storage is a `u8` array of exactly
+    /// `length` bytes.
+    pub(crate) fn validate_byte_array_storage(
+        &self,
+        kind: &str,
+        producer: &str,
+        length: usize,
+    ) -> Result<(), LayoutError> {
+        if self.scalar_type != ScalarType::U8 {
+            return Err(LayoutError::InvalidLayout(format!(
+                "{kind} type must be u8, got {}.",
+                self.scalar_type.name()
+            )));
+        }
+        match self.size_keys.resolve()?.0 {
+            Some(SizeSource::OneD(size)) if size == length => Ok(()),
+            _ => Err(LayoutError::InvalidLayout(format!(
+                "{producer} produces {length} bytes; size must be {length}."
+            ))),
+        }
+    }
+
     pub fn validate_fingerprint(&self) -> Result<(), LayoutError> {
         if self.size_keys.size.is_some() || self.size_keys.strict_size.is_some() {
             return Err(LayoutError::InvalidLayout(
@@ -548,6 +879,116 @@ impl LeafEntry {
         Ok(())
     }
 
+    /// Validates that an `axis` leaf is a one-dimensional array.
+    pub(crate) fn validate_axis(&self) -> Result<(), LayoutError> {
+        match self.size_keys.resolve()?.0 {
+            Some(SizeSource::OneD(_)) => Ok(()),
+            _ => Err(LayoutError::InvalidLayout(
+                "Axis requires a one-dimensional size, the number of breakpoints.".into(),
+            )),
+        }
+    }
+
+    /// Validates that a `file` leaf is a one-dimensional `u8` or `u16` array.
+    pub(crate) fn validate_file(&self) -> Result<(), LayoutError> {
+        if !matches!(self.scalar_type, ScalarType::U8 | ScalarType::U16) {
+            return Err(LayoutError::InvalidLayout(format!(
+                "File type must be u8 or u16, got {}.",
+                self.scalar_type.name()
+            )));
+        }
+        match self.size_keys.resolve()?.0 {
+            Some(SizeSource::OneD(_)) => Ok(()),
+            _ => Err(LayoutError::InvalidLayout(
+                "File requires a one-dimensional size or SIZE.".into(),
+            )),
+        }
+    }
+
+    /// Validates that an `enum` leaf stores data values in an integer type that holds every
+    /// value of the enum.
+    pub(crate) fn validate_enum(
+        &self,
+        name: &str,
+        enums: &IndexMap<String, EnumType>,
+    ) -> Result<(), LayoutError> {
+        if !matches!(
+            self.source,
+            EntrySource::Name(_) | EntrySource::Value(_) | EntrySource::Const(_)
+        ) {
+            return Err(LayoutError::InvalidLayout(
+                "'enum' is only valid with a 'name', 'value' or 'const' source.".into(),
+            ));
+        }
+        if !self.scalar_type.is_integer() {
+            return Err(LayoutError::InvalidLayout(format!(
+                "Enum requires integer storage type, got {}.",
+                self.scalar_type.name()
+            )));
+        }
+        let enum_type = enum_type(enums, name)?;
+        for (symbol, value) in &enum_type.symbols {
+            if DataValue::I64(*value)
+                .to_bytes(self.scalar_type, Endianness::Little, true)
+                .is_err()
+            {
+                return Err(LayoutError::InvalidLayout(format!(
+                    "Enum '{name}' value {symbol} = {value} does not fit type {}.",
+                    self.scalar_type.name()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Maps enum symbols to their integers, returning the value to encode and the symbol to
+    /// report. Values pass through unchanged for leaves without an `enum`.
+    fn enum_value<'v>(
+        &self,
+        value: &'v DataValue,
+        config: &BuildConfig,
+    ) -> Result<(Cow<'v, DataValue>, Cow<'v, DataValue>), LayoutError> {
+        let Some(name) = &self.enum_name else {
+            return Ok((Cow::Borrowed(value), Cow::Borrowed(value)));
+        };
+        let (symbol, number) = enum_type(config.enums, name)?.resolve(value)?;
+        Ok((
+            Cow::Owned(DataValue::I64(number)),
+            Cow::Owned(DataValue::Str(symbol.to_owned())),
+        ))
+    }
+
+    /// Checks a value against the leaf's `min`, `max` and `allowed` limits. `reported` is the
+    /// value as the used-values report shows it, the symbol for enum fields, which `allowed`
+    /// also accepts. `location` places an array element, such as `" at index 2"`.
+    fn check_constraints(
+        &self,
+        value: &DataValue,
+        reported: &DataValue,
+        location: &str,
+        data_source: Option<&dyn DataSource>,
+    ) -> Result<(), LayoutError> {
+        let Some(violation) = self.constraints.violation(value, Some(reported)) else {
+            return Ok(());
+        };
+        let origin = match &self.source {
+            EntrySource::Name(name) => data_origin(name, data_source),
+            EntrySource::Table(table) => data_origin(&table.name, data_source),
+            EntrySource::Const(name) => format!(" from const '{name}'"),
+            _ => String::new(),
+        };
+        Err(constraint_error(reported, location, &origin, &violation))
+    }
+
+    /// Encodes one value in the leaf's storage type, applying its linear scale.
+    fn encode(&self, value: &DataValue, config: &BuildConfig) -> Result<Vec<u8>, LayoutError> {
+        let raw = match &self.scale {
+            Some(scale) => Cow::Owned(scale.raw_value(value, self.scalar_type, config.strict)?),
+            None => Cow::Borrowed(value),
+        };
+        raw.to_bytes(self.scalar_type, config.abi.endianness(), config.strict)
+    }
+
     /// Validates bitmap entry rules.
     pub(crate) fn validate_bitmap(
         &self,
@@ -611,6 +1052,13 @@ impl LeafEntry {
         let mut offset: usize = 0;
         for field in fields {
             let value = field.resolve_value(data_source)?;
+            if let Some(violation) = field.constraints.violation(&value, None) {
+                let origin = match &field.source {
+                    BitmapFieldSource::Name(name) => data_origin(name, data_source),
+                    BitmapFieldSource::Value(_) => String::new(),
+                };
+                return Err(constraint_error(&value, "", &origin, &violation));
+            }
             let clamped = clamp_bitfield_value(&value, field.bits, signed, config.strict)?;
 
             let mask = (1u128 << field.bits) - 1;
@@ -632,6 +1080,132 @@ impl LeafEntry {
         )
     }
 
+    /// Emits the bytes of a file like a string, one byte per element, and reports the file's
+    /// SHA-256 digest rather than its contents.
+    fn emit_file(
+        &self,
+        file: &FileSource,
+        config: &BuildConfig,
+        value_sink: &mut dyn ValueSink,
+        field_path: &[String],
+        scalar_abi: ScalarAbi,
+    ) -> Result<Vec<u8>, LayoutError> {
+        let (size, strict_len) = self.size_keys.resolve()?;
+        let Some(SizeSource::OneD(size)) = size else {
+            unreachable!("file size validated before block emission")
+        };
+        let total_bytes =
+            size.checked_mul(scalar_abi.array_stride)
+                .ok_or(LayoutError::DataValueExportFailed(
+                    "Array size overflow".into(),
+                ))?;
+        let (bytes, hash) = file.read(config.layout_dir)?;
+        if bytes.len() > size {
+            return Err(LayoutError::DataValueExportFailed(format!(
+                "File contents ({} bytes) are larger than defined size ({size}).",
+                bytes.len()
+            )));
+        }
+        if strict_len && bytes.len() < size {
+            return Err(LayoutError::DataValueExportFailed(format!(
+                "File contents ({} bytes) are smaller than defined size ({size}, strict SIZE).",
+                bytes.len()
+            )));
+        }
+
+        let encoded = match self.scalar_type {
+            ScalarType::U16 => bytes
+                .iter()
+                .flat_map(|byte| u16::from(*byte).to_endian_bytes(config.abi.endianness()))
+                .collect(),
+            _ => bytes.clone(),
+        };
+        let mut out = Vec::with_capacity(total_bytes);
+        append_string(&mut out, encoded, scalar_abi, config.padding);
+        out.resize(total_bytes, config.padding);
+
+        let mut reported = serde_json::Map::new();
+        reported.insert(
+            "file".to_owned(),
+            serde_json::Value::String(file.path.to_string_lossy().into_owned()),
+        );
+        reported.insert("sha256".to_owned(), bytes_to_hex_json(&hash));
+        reported.insert("offset".to_owned(), file.offset.into());
+        reported.insert("length".to_owned(), bytes.len().into());
+        value_sink.record_value(field_path, serde_json::Value::Object(reported))?;
+        Ok(out)
+    }
+
+    /// Emits an axis or the values of a curve or map, checking the whole table first.
+    fn emit_table_part(
+        &self,
+        table: &TableSource,
+        data_source: Option<&dyn DataSource>,
+        config: &BuildConfig,
+        value_sink: &mut dyn ValueSink,
+        field_path: &[String],
+        scalar_abi: ScalarAbi,
+    ) -> Result<Vec<u8>, LayoutError> {
+        let Some(ds) = data_source else {
+            return Err(LayoutError::MissingDataSheet(format!(
+                "{} '{}' requires a value from a data source, but none was provided.",
+                table.kind.name(),
+                table.name
+            )));
+        };
+        let lookup = table.load(ds)?;
+        let mut out = Vec::new();
+        let mut append = |value: &DataValue, location: String| {
+            self.check_constraints(value, value, &location, data_source)?;
+            let encoded = self.encode(value, config)?;
+            append_array_element(&mut out, &encoded, scalar_abi, config.padding);
+            Ok::<_, LayoutError>(encoded)
+        };
+        let reported = match table.part {
+            TablePart::X | TablePart::Y => {
+                let (label, axis) = match table.part {
+                    TablePart::X => ("x", &lookup.x),
+                    _ => ("y", &lookup.y),
+                };
+                let mut encoded = Vec::with_capacity(axis.len());
+                for (index, value) in axis.iter().enumerate() {
+                    encoded.push(append(value, format!(" at index {index}"))?);
+                }
+                curve::check_stored_breakpoints(
+                    axis,
+                    &encoded,
+                    self.scalar_type,
+                    config.abi.endianness(),
+                )
+                .map_err(|error| {
+                    LayoutError::DataValueExportFailed(format!(
+                        "{label} axis of {} '{}' {error}",
+                        table.kind.name(),
+                        table.name
+                    ))
+                })?;
+                array_to_json(axis)?
+            }
+            TablePart::Values => {
+                for (row_index, row) in lookup.values.iter().enumerate() {
+                    for (column, value) in row.iter().enumerate() {
+                        let location = match table.kind {
+                            TableKind::Curve => format!(" at index {column}"),
+                            TableKind::Map => format!(" at [{row_index}][{column}]"),
+                        };
+                        append(value, location)?;
+                    }
+                }
+                match (table.kind, lookup.values.first()) {
+                    (TableKind::Curve, Some(row)) => array_to_json(row)?,
+                    _ => array_2d_to_json(&lookup.values)?,
+                }
+            }
+        };
+        value_sink.record_value(field_path, reported)?;
+        Ok(out)
+    }
+
     fn emit_bytes_single(
         &self,
         data_source: Option<&dyn DataSource>,
@@ -647,26 +1221,21 @@ impl LeafEntry {
                         name
                     )));
                 };
-                let value = ds.retrieve_single_value(name)?;
-                let bytes =
-                    value.to_bytes(self.scalar_type, config.abi.endianness(), config.strict)?;
-                value_sink.record_value(field_path, data_value_to_json(&value)?)?;
-                Ok(bytes)
+                let value = match self.enum_name {
+                    Some(_) => ds.retrieve_symbol(name)?,
+                    None => ds.retrieve_single_value(name)?,
+                };
+                self.emit_scalar(&value, data_source, config, value_sink, field_path)
             }
             EntrySource::Value(ValueSource::Single(v)) => {
-                let bytes = v.to_bytes(self.scalar_type, config.abi.endianness(), config.strict)?;
-                value_sink.record_value(field_path, data_value_to_json(v)?)?;
-                Ok(bytes)
+                self.emit_scalar(v, data_source, config, value_sink, field_path)
             }
             EntrySource::Value(_) => Err(LayoutError::DataValueExportFailed(
                 "Single value expected for scalar type.".to_owned(),
             )),
             EntrySource::Const(name) => match self.validate_const(name, config.consts, None)? {
                 ValueSource::Single(v) => {
-                    let bytes =
-                        v.to_bytes(self.scalar_type, config.abi.endianness(), config.strict)?;
-                    value_sink.record_value(field_path, data_value_to_json(v)?)?;
-                    Ok(bytes)
+                    self.emit_scalar(v, data_source, config, value_sink, field_path)
                 }
                 ValueSource::Array(_) => Err(LayoutError::DataValueExportFailed(
                     "Single value expected for scalar type.".to_owned(),
@@ -674,13 +1243,42 @@ impl LeafEntry {
             },
             EntrySource::Bitmap(_) => unreachable!("bitmap handled in emit_bytes"),
             EntrySource::Ref(_) => unreachable!("ref handled by block emitter"),
-            EntrySource::Checksum(_) => unreachable!("checksum handled by block emitter"),
+            EntrySource::Checksum(_)
+            | EntrySource::Digest(_)
+            | EntrySource::Signature(_)
+            | EntrySource::PublicKeyHash(_)
+            | EntrySource::Hmac(_)
+            | EntrySource::CrcCompensation(_) => {
+                unreachable!("integrity fields handled by block emitter")
+            }
+            EntrySource::EncryptionNonce(_) | EntrySource::EncryptionTag(_) => {
+                unreachable!("encryption fields handled by block emitter")
+            }
             EntrySource::Fingerprint(_) => {
                 unreachable!("fingerprint handled by block emitter")
             }
+            EntrySource::Reserved(_) => unreachable!("reserved handled by block emitter"),
+            EntrySource::Axis(_) => unreachable!("axis size validated before block emission"),
+            EntrySource::Table(_) => unreachable!("tables handled in emit_bytes"),
+            EntrySource::File(_) => unreachable!("files handled in emit_bytes"),
         }
     }
 
+    fn emit_scalar(
+        &self,
+        value: &DataValue,
+        data_source: Option<&dyn DataSource>,
+        config: &BuildConfig,
+        value_sink: &mut dyn ValueSink,
+        field_path: &[String],
+    ) -> Result<Vec<u8>, LayoutError> {
+        let (encoded, reported) = self.enum_value(value, config)?;
+        self.check_constraints(&encoded, &reported, "", data_source)?;
+        let bytes = self.encode(&encoded, config)?;
+        value_sink.record_value(field_path, data_value_to_json(&reported)?)?;
+        Ok(bytes)
+    }
+
     fn emit_bytes_1d(
         &self,
         data_source: Option<&dyn DataSource>,
@@ -722,27 +1320,71 @@ impl LeafEntry {
             }
             EntrySource::Bitmap(_) => unreachable!("bitmap handled in emit_bytes"),
             EntrySource::Ref(_) => unreachable!("ref handled by block emitter"),
-            EntrySource::Checksum(_) => unreachable!("checksum handled by block emitter"),
+            EntrySource::Checksum(_)
+            | EntrySource::Digest(_)
+            | EntrySource::Signature(_)
+            | EntrySource::PublicKeyHash(_)
+            | EntrySource::Hmac(_)
+            | EntrySource::CrcCompensation(_) => {
+                unreachable!("integrity fields handled by block emitter")
+            }
+            EntrySource::EncryptionNonce(_) | EntrySource::EncryptionTag(_) => {
+                unreachable!("encryption fields handled by block emitter")
+            }
             EntrySource::Fingerprint(_) => {
                 unreachable!("fingerprint handled by block emitter")
             }
+            EntrySource::Reserved(_) => unreachable!("reserved handled by block emitter"),
+            EntrySource::Axis(name) => {
+                let Some(ds) = data_source else {
+                    return Err(LayoutError::MissingDataSheet(format!(
+                        "Axis '{}' requires breakpoints from a data source, but none was provided.",
+                        name
+                    )));
+                };
+                retrieved = ValueSource::Array(curve::read_axis(ds, name, size)?);
+                &retrieved
+            }
+            EntrySource::Table(_) => unreachable!("tables handled in emit_bytes"),
+            EntrySource::File(_) => unreachable!("files handled in emit_bytes"),
         };
 
         match value {
             ValueSource::Array(values) => {
-                for value in values {
-                    append_array_element(
-                        &mut out,
-                        &value.to_bytes(
-                            self.scalar_type,
-                            config.abi.endianness(),
-                            config.strict,
-                        )?,
-                        scalar_abi,
-                        config.padding,
-                    );
+                let mut reported = Vec::with_capacity(values.len());
+                let mut stored = Vec::new();
+                for (index, value) in values.iter().enumerate() {
+                    let (encoded, symbol) = self.enum_value(value, config)?;
+                    self.check_constraints(
+                        &encoded,
+                        &symbol,
+                        &format!(" at index {index}"),
+                        data_source,
+                    )?;
+                    let bytes = self.encode(&encoded, config)?;
+                    append_array_element(&mut out, &bytes, scalar_abi, config.padding);
+                    reported.push(symbol.into_owned());
+                    if let EntrySource::Axis(_) = self.source {
+                        stored.push(bytes);
+                    }
                 }
-                value_sink.record_value(field_path, array_to_json(values)?)?;
+                if let EntrySource::Axis(name) = &self.source {
+                    curve::check_stored_breakpoints(
+                        values,
+                        &stored,
+                        self.scalar_type,
+                        config.abi.endianness(),
+                    )
+                    .map_err(|error| {
+                        LayoutError::DataValueExportFailed(format!("axis '{name}' {error}"))
+                    })?;
+                }
+                value_sink.record_value(field_path, array_to_json(&reported)?)?;
+            }
+            ValueSource::Single(_) if self.enum_name.is_some() => {
+                return Err(LayoutError::DataValueExportFailed(
+                    "Enum arrays require an array of values, not a string.".to_owned(),
+                ));
             }
             ValueSource::Single(value) => {
                 if !matches!(self.scalar_type, ScalarType::U8 | ScalarType::U16) {
@@ -750,6 +1392,12 @@ impl LeafEntry {
                         "Strings should have type u8 or u16.".to_owned(),
                     ));
                 }
+                if self.scale.is_some() {
+                    return Err(LayoutError::DataValueExportFailed(
+                        "Scaled arrays require an array of values, not a string.".to_owned(),
+                    ));
+                }
+                self.check_constraints(value, value, "", data_source)?;
                 append_string(
                     &mut out,
                     value.string_to_bytes(self.scalar_type, config.abi.endianness())?,
@@ -837,17 +1485,28 @@ impl LeafEntry {
                 "failed to allocate {total_bytes}-byte field buffer: {error}"
             ))
         })?;
-        for row in &data {
-            for v in row {
+        let mut reported = Vec::with_capacity(data.len());
+        for (row_index, row) in data.iter().enumerate() {
+            let mut reported_row = Vec::with_capacity(row.len());
+            for (column, v) in row.iter().enumerate() {
+                let (encoded, symbol) = self.enum_value(v, config)?;
+                self.check_constraints(
+                    &encoded,
+                    &symbol,
+                    &format!(" at [{row_index}][{column}]"),
+                    data_source,
+                )?;
                 append_array_element(
                     &mut out,
-                    &v.to_bytes(self.scalar_type, config.abi.endianness(), config.strict)?,
+                    &self.encode(&encoded, config)?,
                     scalar_abi,
                     config.padding,
                 );
+                reported_row.push(symbol.into_owned());
             }
+            reported.push(reported_row);
         }
-        value_sink.record_value(field_path, array_2d_to_json(&data)?)?;
+        value_sink.record_value(field_path, array_2d_to_json(&reported)?)?;
 
         while out.len() < total_bytes {
             out.push(config.padding);
@@ -906,6 +1565,26 @@ fn encode_bitmap_storage(
     Ok(bytes)
 }
 
+/// Describes where a data-source value came from, naming its variant when the source knows it.
+fn data_origin(name: &str, data_source: Option<&dyn DataSource>) -> String {
+    match data_source.and_then(|source| source.variant_of(name)) {
+        Some(variant) => format!(" from '{name}' in variant '{variant}'"),
+        None => format!(" from '{name}'"),
+    }
+}
+
+fn constraint_error(
+    value: &DataValue,
+    location: &str,
+    origin: &str,
+    violation: &str,
+) -> LayoutError {
+    LayoutError::DataValueExportFailed(format!(
+        "value {}{location}{origin} {violation}",
+        data_value_display(value)
+    ))
+}
+
 fn bitmap_field_key(field: &BitmapField, offset: usize) -> String {
     match &field.source {
         BitmapFieldSource::Name(name) => name.clone(),
@@ -24,6 +24,9 @@ pub enum LayoutError {
     #[error("no blocks provided")]
     NoBlocksProvided,
 
+    #[error("key error: {0}")]
+    KeyError(String),
+
     #[error("missing datasheet: {0}")]
     MissingDataSheet(String),
 
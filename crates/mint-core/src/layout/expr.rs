@@ -0,0 +1,631 @@
+//! Integer and float expressions over consts in `[mint.const]` strings and `{ expr = "..." }`
+//! tables and in `size` and `value` strings.
+//!
+//! Expressions are evaluated on the parsed TOML table before it is deserialized, so the rest of
+//! the layout only ever sees literals.
+
+use super::error::LayoutError;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+impl Number {
+    fn to_toml(self) -> toml::Value {
+        match self {
+            Self::Int(value) => toml::Value::Integer(value),
+            Self::Float(value) => toml::Value::Float(value),
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(value) => write!(formatter, "{value}"),
+            Self::Float(value) => write!(formatter, "{value:?}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum ExprError {
+    /// The text is not an expression over numeric consts.
+    NotExpression(String),
+    /// The expression is well formed but cannot be evaluated, such as on overflow.
+    Invalid(String),
+}
+
+impl ExprError {
+    fn message(self) -> String {
+        match self {
+            Self::NotExpression(message) | Self::Invalid(message) => message,
+        }
+    }
+}
+
+type Lookup<'l> = dyn FnMut(&str) -> Result<Option<Number>, ExprError> + 'l;
+
+/// Evaluates the strings that hold expressions in the `[mint.const]`, block data and struct type
+/// tables of a layout, replacing them with numbers. Returns whether any string was replaced.
+pub(crate) fn evaluate_layout(table: &mut toml::Table) -> Result<bool, LayoutError> {
+    let mut promoted = HashMap::new();
+    for (block_name, block) in table.iter().filter(|(key, _)| key.as_str() != "mint") {
+        let Some(header) = block.get("header").and_then(toml::Value::as_table) else {
+            continue;
+        };
+        for key in ["start_address", "length"] {
+            if let Some(value) = header.get(key).and_then(toml::Value::as_integer) {
+                promoted.insert(format!("{block_name}.{key}"), Number::Int(value));
+            }
+        }
+    }
+
+    let declared = table
+        .get("mint")
+        .and_then(|mint| mint.get("const"))
+        .and_then(toml::Value::as_table)
+        .cloned()
+        .unwrap_or_default();
+    let mut consts = Consts {
+        declared: &declared,
+        promoted,
+        values: HashMap::new(),
+        stack: Vec::new(),
+    };
+    let mut changed = false;
+
+    let mut evaluated = Vec::new();
+    for (name, value) in &declared {
+        let expression = match value {
+            toml::Value::Table(table) => table.contains_key("expr"),
+            toml::Value::String(_) => true,
+            _ => false,
+        };
+        if expression
+            && let Some(number) = consts.number(name).map_err(|error| {
+                LayoutError::InvalidLayout(format!(
+                    "[mint.const] '{name}' expression is invalid: {}",
+                    error.message()
+                ))
+            })?
+        {
+            evaluated.push((name.clone(), number));
+        }
+    }
+    if let Some(declared) = table
+        .get_mut("mint")
+        .and_then(|mint| mint.get_mut("const"))
+        .and_then(toml::Value::as_table_mut)
+    {
+        for (name, number) in evaluated {
+            declared.insert(name, number.to_toml());
+            changed = true;
+        }
+    }
+
+    let types = table
+        .get_mut("mint")
+        .and_then(|mint| mint.get_mut("types"))
+        .and_then(toml::Value::as_table_mut);
+    for (type_name, fields) in types.into_iter().flatten() {
+        if let toml::Value::Table(fields) = fields {
+            changed |= evaluate_fields(fields, &mut consts, &format!("mint.types.{type_name}"))?;
+        }
+    }
+    for (block_name, block) in table.iter_mut().filter(|(key, _)| key.as_str() != "mint") {
+        if let Some(toml::Value::Table(data)) = block.get_mut("data") {
+            changed |= evaluate_fields(data, &mut consts, &format!("{block_name}.data"))?;
+        }
+    }
+    Ok(changed)
+}
+
+/// Evaluates the `size`, `SIZE`, `value` and limit expressions of the leaf or branch table at
+/// `path`.
+fn evaluate_fields(
+    table: &mut toml::Table,
+    consts: &mut Consts<'_>,
+    path: &str,
+) -> Result<bool, LayoutError> {
+    let leaf = matches!(table.get("type"), Some(toml::Value::String(_)));
+    let mut changed = false;
+    let size_keys: &[&str] = if leaf { &["size", "SIZE"] } else { &["size"] };
+    for key in size_keys {
+        let Some(value) = table.get_mut(*key) else {
+            continue;
+        };
+        let extents = match value {
+            toml::Value::Array(extents) => extents.iter_mut().collect(),
+            value => vec![value],
+        };
+        for extent in extents {
+            let toml::Value::String(text) = extent else {
+                continue;
+            };
+            let size = match consts.evaluate(text) {
+                Ok(Number::Int(size)) if size >= 0 => size,
+                Ok(number) => Err(expression_error(
+                    path,
+                    key,
+                    text,
+                    format!("a size must be a non-negative integer, not {number}"),
+                ))?,
+                Err(error) => Err(expression_error(path, key, text, error.message()))?,
+            };
+            *extent = toml::Value::Integer(size);
+            changed = true;
+        }
+    }
+
+    if leaf {
+        // Arrays and enums read strings as text and symbols.
+        let text_value = ["size", "SIZE", "enum"]
+            .iter()
+            .any(|key| table.contains_key(*key));
+        let symbols = table.contains_key("enum");
+        changed |= evaluate_numbers(table, consts, path, text_value, symbols)?;
+        changed |= evaluate_scale(table, consts, path)?;
+        // Inline axes of a curve or map carry their own scale.
+        for axis in ["x", "y"] {
+            if let Some(toml::Value::Table(axis_table)) = table.get_mut(axis) {
+                changed |= evaluate_scale(axis_table, consts, &format!("{path}.{axis}"))?;
+            }
+        }
+        if let Some(toml::Value::Table(slice)) = table.get_mut("file") {
+            for key in ["offset", "length"] {
+                let Some(toml::Value::String(text)) = slice.get(key) else {
+                    continue;
+                };
+                let file_path = format!("{path}.file");
+                let number = match consts.evaluate(text) {
+                    Ok(Number::Int(number)) if number >= 0 => number,
+                    Ok(number) => Err(expression_error(
+                        &file_path,
+                        key,
+                        text,
+                        format!("a file {key} must be a non-negative integer, not {number}"),
+                    ))?,
+                    Err(error) => Err(expression_error(&file_path, key, text, error.message()))?,
+                };
+                slice.insert(key.to_owned(), toml::Value::Integer(number));
+                changed = true;
+            }
+        }
+        if let Some(toml::Value::Array(fields)) = table.get_mut("bitmap") {
+            for field in fields {
+                if let toml::Value::Table(field) = field {
+                    changed |=
+                        evaluate_numbers(field, consts, &format!("{path}.bitmap"), false, false)?;
+                }
+            }
+        }
+        return Ok(changed);
+    }
+
+    for (name, child) in table.iter_mut() {
+        // A table-valued `union` selects a member rather than declaring a field.
+        if let toml::Value::Table(child) = child
+            && name != "union"
+        {
+            changed |= evaluate_fields(child, consts, &format!("{path}.{name}"))?;
+        }
+    }
+    Ok(changed)
+}
+
+/// Evaluates the `factor` and `offset` expressions of a `scale` table.
+fn evaluate_scale(
+    table: &mut toml::Table,
+    consts: &mut Consts<'_>,
+    path: &str,
+) -> Result<bool, LayoutError> {
+    let mut changed = false;
+    if let Some(toml::Value::Table(scale)) = table.get_mut("scale") {
+        for key in ["factor", "offset"] {
+            if let Some(toml::Value::String(text)) = scale.get(key) {
+                let number = consts.evaluate(text).map_err(|error| {
+                    expression_error(&format!("{path}.scale"), key, text, error.message())
+                })?;
+                scale.insert(key.to_owned(), number.to_toml());
+                changed = true;
+            }
+        }
+    }
+    Ok(changed)
+}
+
+/// Evaluates the `value`, `min`, `max` and `allowed` expressions of a leaf or bitmap field.
+/// `text_value` leaves scalar `value` and `allowed` strings as text, and `symbols` also leaves
+/// array elements as enum symbols.
+fn evaluate_numbers(
+    table: &mut toml::Table,
+    consts: &mut Consts<'_>,
+    path: &str,
+    text_value: bool,
+    symbols: bool,
+) -> Result<bool, LayoutError> {
+    let mut changed = false;
+    for key in ["value", "min", "max", "allowed"] {
+        let values = match table.get_mut(key) {
+            Some(toml::Value::Array(values)) if key == "value" && !symbols => {
+                values.iter_mut().collect()
+            }
+            Some(toml::Value::Array(values)) if key == "allowed" && !text_value => {
+                values.iter_mut().collect()
+            }
+            Some(value) if matches!(key, "min" | "max") || (key == "value" && !text_value) => {
+                vec![value]
+            }
+            _ => Vec::new(),
+        };
+        for value in values {
+            let toml::Value::String(text) = value else {
+                continue;
+            };
+            let number = consts
+                .evaluate(text)
+                .map_err(|error| expression_error(path, key, text, error.message()))?;
+            *value = number.to_toml();
+            changed = true;
+        }
+    }
+    Ok(changed)
+}
+
+fn expression_error(path: &str, key: &str, text: &str, message: String) -> LayoutError {
+    LayoutError::InvalidLayout(format!(
+        "'{key}' expression \"{text}\" in '{path}' is invalid: {message}"
+    ))
+}
+
+/// Numeric consts: declared `[mint.const]` values, which may themselves be expressions, and the
+/// auto-promoted block header consts.
+struct Consts<'a> {
+    declared: &'a toml::Table,
+    promoted: HashMap<String, Number>,
+    values: HashMap<String, Option<Number>>,
+    /// Consts being evaluated, to report cycles.
+    stack: Vec<String>,
+}
+
+impl Consts<'_> {
+    /// Evaluates `text` in a context that needs a number.
+    fn evaluate(&mut self, text: &str) -> Result<Number, ExprError> {
+        evaluate(text, &mut |name| self.number(name))
+    }
+
+    /// Returns the value of const `name`, or `None` if it is not numeric.
+    fn number(&mut self, name: &str) -> Result<Option<Number>, ExprError> {
+        if let Some(value) = self.promoted.get(name) {
+            return Ok(Some(*value));
+        }
+        if let Some(value) = self.values.get(name) {
+            return Ok(*value);
+        }
+        let declared = self.declared;
+        let value = match declared.get(name) {
+            Some(toml::Value::Integer(value)) => Some(Number::Int(*value)),
+            Some(toml::Value::Float(value)) => Some(Number::Float(*value)),
+            Some(toml::Value::Table(table)) if table.contains_key("expr") => {
+                Some(self.evaluate_const(name, expression_text(table)?)?.0)
+            }
+            // A plain string is an expression only if it parses and uses a numeric const, so
+            // text such as "0042" or "2024-01-01" stays text.
+            Some(toml::Value::String(text)) => match self.evaluate_const(name, text) {
+                Ok((number, true)) => Some(number),
+                Ok((_, false)) | Err(ExprError::NotExpression(_)) => None,
+                Err(error) => return Err(error),
+            },
+            _ => None,
+        };
+        self.values.insert(name.to_owned(), value);
+        Ok(value)
+    }
+
+    /// Evaluates the expression of const `name`, also returning whether it used another const.
+    fn evaluate_const(&mut self, name: &str, text: &str) -> Result<(Number, bool), ExprError> {
+        if self.stack.iter().any(|entry| entry == name) {
+            return Err(ExprError::Invalid(format!(
+                "const expression cycle: {} -> {name}",
+                self.stack.join(" -> ")
+            )));
+        }
+        self.stack.push(name.to_owned());
+        let mut named = false;
+        let result = evaluate(text, &mut |name| {
+            named = true;
+            self.number(name)
+        });
+        self.stack.pop();
+        Ok((result?, named))
+    }
+}
+
+/// Returns the expression of a `{ expr = "..." }` const, which must evaluate, unlike a plain
+/// const string.
+fn expression_text(table: &toml::Table) -> Result<&str, ExprError> {
+    match (table.get("expr"), table.len()) {
+        (Some(toml::Value::String(text)), 1) => Ok(text),
+        _ => Err(ExprError::Invalid(
+            "a const table must be { expr = \"...\" }".to_owned(),
+        )),
+    }
+}
+
+/// Evaluates `text`; `lookup` returns the value of a name, or `None` for a name that is not a
+/// numeric const.
+///
+/// Integers are 64-bit signed and every operation checks for overflow. An operation with a
+/// float operand yields a float; bitwise operators and shifts take integers only. Integer
+/// division truncates toward zero.
+fn evaluate(text: &str, lookup: &mut Lookup<'_>) -> Result<Number, ExprError> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        position: 0,
+        lookup,
+    };
+    let value = parser.binary(0)?;
+    match parser.tokens.get(parser.position) {
+        None => Ok(value),
+        Some(token) => Err(ExprError::NotExpression(format!("unexpected '{token}'"))),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(Number),
+    Name(String),
+    Operator(&'static str),
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(number) => write!(formatter, "{number}"),
+            Self::Name(name) => formatter.write_str(name),
+            Self::Operator(operator) => formatter.write_str(operator),
+            Self::Open => formatter.write_str("("),
+            Self::Close => formatter.write_str(")"),
+        }
+    }
+}
+
+/// Binary operators from loosest to tightest binding, as in C.
+const PRECEDENCE: &[&[&str]] = &[
+    &["|"],
+    &["^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, ExprError> {
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while let Some(character) = rest.chars().next() {
+        let length = if character.is_ascii_digit() || character == '.' {
+            let length = number_length(rest);
+            tokens.push(Token::Number(parse_number(&rest[..length])?));
+            length
+        } else if character.is_ascii_alphabetic() || character == '_' {
+            let length = rest
+                .find(|next: char| !(next.is_ascii_alphanumeric() || next == '_' || next == '.'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Name(rest[..length].to_owned()));
+            length
+        } else if character == '(' {
+            tokens.push(Token::Open);
+            1
+        } else if character == ')' {
+            tokens.push(Token::Close);
+            1
+        } else if let Some(operator) = ["<<", ">>", "+", "-", "*", "/", "%", "&", "|", "^", "~"]
+            .into_iter()
+            .find(|operator| rest.starts_with(operator))
+        {
+            tokens.push(Token::Operator(operator));
+            operator.len()
+        } else {
+            return Err(ExprError::NotExpression(format!(
+                "unexpected character '{character}'"
+            )));
+        };
+        rest = rest[length..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Returns the length of the number literal at the start of `text`, including the sign of a
+/// decimal exponent such as `1e-3`.
+fn number_length(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let decimal = !matches!(bytes.get(1), Some(b'x' | b'X' | b'o' | b'b'));
+    let mut length = 0;
+    while let Some(byte) = bytes.get(length) {
+        let exponent_sign = decimal
+            && matches!(byte, b'+' | b'-')
+            && matches!(bytes.get(length.wrapping_sub(1)), Some(b'e' | b'E'));
+        if !(byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'.') || exponent_sign) {
+            break;
+        }
+        length += 1;
+    }
+    length
+}
+
+fn parse_number(text: &str) -> Result<Number, ExprError> {
+    let digits = text.replace('_', "");
+    let radix = [("0x", 16), ("0o", 8), ("0b", 2)]
+        .into_iter()
+        .find_map(|(prefix, radix)| digits.strip_prefix(prefix).map(|digits| (digits, radix)));
+    let number = match radix {
+        Some((digits, radix)) => i64::from_str_radix(digits, radix).ok().map(Number::Int),
+        None if digits.contains(['.', 'e', 'E']) => digits
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .map(Number::Float),
+        None => digits.parse::<i64>().ok().map(Number::Int),
+    };
+    number.ok_or_else(|| ExprError::NotExpression(format!("invalid number '{text}'")))
+}
+
+struct Parser<'t, 'l, 'r> {
+    tokens: &'t [Token],
+    position: usize,
+    lookup: &'r mut Lookup<'l>,
+}
+
+impl Parser<'_, '_, '_> {
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Number, ExprError> {
+        let Some(operators) = PRECEDENCE.get(level) else {
+            return self.unary();
+        };
+        let mut left = self.binary(level + 1)?;
+        while let Some(Token::Operator(operator)) = self.tokens.get(self.position)
+            && operators.contains(operator)
+        {
+            self.position += 1;
+            let right = self.binary(level + 1)?;
+            left = apply(operator, left, right)?;
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Number, ExprError> {
+        match self.next().cloned() {
+            Some(Token::Operator("-")) => match self.unary()? {
+                Number::Int(value) => value.checked_neg().map(Number::Int).ok_or_else(overflow),
+                Number::Float(value) => Ok(Number::Float(-value)),
+            },
+            Some(Token::Operator("+")) => self.unary(),
+            Some(Token::Operator("~")) => match self.unary()? {
+                Number::Int(value) => Ok(Number::Int(!value)),
+                Number::Float(_) => Err(integer_only("~")),
+            },
+            Some(Token::Open) => {
+                let value = self.binary(0)?;
+                match self.next() {
+                    Some(Token::Close) => Ok(value),
+                    _ => Err(ExprError::NotExpression("unclosed '('".to_owned())),
+                }
+            }
+            Some(Token::Number(number)) => Ok(number),
+            Some(Token::Name(name)) => (self.lookup)(&name)?.ok_or_else(|| {
+                ExprError::NotExpression(format!("'{name}' is not a numeric const"))
+            }),
+            Some(token) => Err(ExprError::NotExpression(format!("unexpected '{token}'"))),
+            None => Err(ExprError::NotExpression("expression ends early".to_owned())),
+        }
+    }
+}
+
+fn apply(operator: &str, left: Number, right: Number) -> Result<Number, ExprError> {
+    let (left, right) = match (left, right) {
+        (Number::Int(left), Number::Int(right)) => (left, right),
+        (left, right) => {
+            let (left, right) = (as_float(left), as_float(right));
+            let value = match operator {
+                "+" => left + right,
+                "-" => left - right,
+                "*" => left * right,
+                "/" => left / right,
+                "%" => left % right,
+                operator => return Err(integer_only(operator)),
+            };
+            if !value.is_finite() {
+                return Err(ExprError::Invalid(format!(
+                    "'{operator}' yields the non-finite value {value}"
+                )));
+            }
+            return Ok(Number::Float(value));
+        }
+    };
+    if matches!(operator, "/" | "%") && right == 0 {
+        return Err(ExprError::Invalid("division by zero".to_owned()));
+    }
+    let shift = |right: i64| u32::try_from(right).ok().filter(|shift| *shift < 64);
+    let value = match operator {
+        "+" => left.checked_add(right),
+        "-" => left.checked_sub(right),
+        "*" => left.checked_mul(right),
+        "/" => left.checked_div(right),
+        "%" => left.checked_rem(right),
+        "&" => Some(left & right),
+        "|" => Some(left | right),
+        "^" => Some(left ^ right),
+        "<<" => shift(right).and_then(|shift| {
+            Some(left.checked_shl(shift)?).filter(|value| value >> shift == left)
+        }),
+        ">>" => shift(right).map(|shift| left >> shift),
+        _ => unreachable!("tokenizer only produces known operators"),
+    };
+    value.map(Number::Int).ok_or_else(overflow)
+}
+
+fn as_float(number: Number) -> f64 {
+    match number {
+        Number::Int(value) => value as f64,
+        Number::Float(value) => value,
+    }
+}
+
+fn overflow() -> ExprError {
+    ExprError::Invalid("result overflows a 64-bit signed integer".to_owned())
+}
+
+fn integer_only(operator: &str) -> ExprError {
+    ExprError::Invalid(format!("'{operator}' needs integer operands"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(text: &str) -> Result<Number, ExprError> {
+        let consts = HashMap::from([("N", Number::Int(8)), ("app.length", Number::Int(0x100))]);
+        evaluate(text, &mut |name| Ok(consts.get(name).copied()))
+    }
+
+    #[test]
+    fn operators_follow_c_precedence() {
+        assert_eq!(eval("N * 4 + 1").ok(), Some(Number::Int(33)));
+        assert_eq!(eval("1 + N * 4").ok(), Some(Number::Int(33)));
+        assert_eq!(eval("(1 + N) * 4").ok(), Some(Number::Int(36)));
+        assert_eq!(eval("1 << 4 | 0x0F & 3").ok(), Some(Number::Int(19)));
+        assert_eq!(eval("-7 / 2").ok(), Some(Number::Int(-3)));
+        assert_eq!(eval("~0").ok(), Some(Number::Int(-1)));
+        assert_eq!(eval("app.length / 2").ok(), Some(Number::Int(0x80)));
+        assert_eq!(eval("N / 2.0 + 1e-1").ok(), Some(Number::Float(4.1)));
+        assert_eq!(eval("0b1010_0000 >> 4").ok(), Some(Number::Int(10)));
+    }
+
+    #[test]
+    fn errors_distinguish_text_from_invalid_expressions() {
+        assert!(matches!(eval("hello"), Err(ExprError::NotExpression(_))));
+        assert!(matches!(eval("Mint v1"), Err(ExprError::NotExpression(_))));
+        assert!(matches!(eval("(N"), Err(ExprError::NotExpression(_))));
+        assert!(matches!(eval("N / 0"), Err(ExprError::Invalid(_))));
+        assert!(matches!(
+            eval("0x7FFF_FFFF_FFFF_FFFF + 1"),
+            Err(ExprError::Invalid(_))
+        ));
+        assert!(matches!(eval("1 << 64"), Err(ExprError::Invalid(_))));
+        assert!(matches!(eval("N & 1.5"), Err(ExprError::Invalid(_))));
+    }
+}
@@ -0,0 +1,130 @@
+//! `file` fields: bytes of an external file embedded in a `u8` or `u16` array.
+
+use super::error::LayoutError;
+use super::settings::DigestAlgorithm;
+use crate::output::digest;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::path::{Component, Path, PathBuf};
+
+/// File embedded by a `file` field, or the slice of it starting at `offset`. Relative paths are
+/// relative to the layout file, and are rewritten that way when an included file declares the
+/// field.
+#[derive(Debug, Clone)]
+pub struct FileSource {
+    pub path: PathBuf,
+    pub offset: usize,
+    /// Number of bytes to embed; the rest of the file when unset.
+    pub length: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFileSlice {
+    path: String,
+    #[serde(default)]
+    offset: usize,
+    length: Option<usize>,
+}
+
+impl<'de> Deserialize<'de> for FileSource {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (path, offset, length) = match toml::Value::deserialize(deserializer)? {
+            toml::Value::String(path) => (path, 0, None),
+            toml::Value::Table(table) => {
+                let slice: RawFileSlice = toml::Value::Table(table)
+                    .try_into()
+                    .map_err(|error| D::Error::custom(format!("invalid 'file' slice: {error}")))?;
+                (slice.path, slice.offset, slice.length)
+            }
+            value => {
+                return Err(D::Error::custom(format!(
+                    "'file' must be a path or a {{ path, offset, length }} table; got {}",
+                    value.type_str()
+                )));
+            }
+        };
+        if path.is_empty() {
+            return Err(D::Error::custom("'file' path must not be empty"));
+        }
+        Ok(Self {
+            path: PathBuf::from(path),
+            offset,
+            length,
+        })
+    }
+}
+
+impl FileSource {
+    /// Reads the embedded bytes from `layout_dir`, the directory of the layout file, returning
+    /// them with the SHA-256 digest of the whole file.
+    pub(crate) fn read(&self, layout_dir: &Path) -> Result<(Vec<u8>, Vec<u8>), LayoutError> {
+        let path = layout_dir.join(&self.path);
+        let contents = std::fs::read(&path).map_err(|error| {
+            LayoutError::FileError(format!("failed to read file {}: {error}", path.display()))
+        })?;
+        let hash = digest::calculate_digest(&contents, DigestAlgorithm::Sha256);
+        let end = match self.length {
+            Some(length) => self.offset.checked_add(length),
+            None => Some(contents.len()),
+        };
+        let Some(bytes) = end.and_then(|end| contents.get(self.offset..end)) else {
+            return Err(LayoutError::DataValueExportFailed(format!(
+                "file {} has {} bytes; the slice at offset {}{} runs past its end.",
+                path.display(),
+                contents.len(),
+                self.offset,
+                self.length
+                    .map(|length| format!(" with length {length}"))
+                    .unwrap_or_default()
+            )));
+        };
+        Ok((bytes.to_vec(), hash))
+    }
+}
+
+/// Rewrites the relative `file` paths of the fields in an included layout table against
+/// `directory`, the directory of the included file relative to the root layout file.
+pub(crate) fn resolve_paths(table: &mut toml::Table, directory: &Path) {
+    if directory.as_os_str().is_empty() {
+        return;
+    }
+    let leaf = matches!(table.get("type"), Some(toml::Value::String(_)));
+    if leaf {
+        let path = match table.get_mut("file") {
+            Some(toml::Value::String(path)) => path,
+            Some(toml::Value::Table(slice)) => match slice.get_mut("path") {
+                Some(toml::Value::String(path)) => path,
+                _ => return,
+            },
+            _ => return,
+        };
+        if !path.is_empty() && !Path::new(path.as_str()).is_absolute() {
+            *path = directory.join(path.as_str()).to_string_lossy().into_owned();
+        }
+        return;
+    }
+
+    for (_, child) in table.iter_mut() {
+        if let toml::Value::Table(child) = child {
+            resolve_paths(child, directory);
+        }
+    }
+}
+
+/// Returns `path` relative to `base`, both absolute, climbing out of `base` with `..` where
+/// needed.
+pub(crate) fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let path = path.components().collect::<Vec<_>>();
+    let base = base.components().collect::<Vec<_>>();
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return path.iter().collect();
+    }
+    std::iter::repeat_n(Component::ParentDir, base.len() - common)
+        .chain(path[common..].iter().copied())
+        .collect()
+}
@@ -89,17 +89,42 @@ fn hash_node(
     match node {
         ResolvedNode::Branch {
             coordinates,
+            packed,
+            selector,
             children,
+            ..
         } => {
             hasher.update(&[0]);
             hash_usize(coordinates.offset, hasher)?;
             hash_usize(coordinates.size, hasher)?;
             hash_usize(coordinates.alignment, hasher)?;
+            // Only packed branches and unions add a marker, so struct fingerprints keep their
+            // values. The selected member changes the data, not the ABI.
+            if *packed {
+                hasher.update(&[3]);
+            }
+            if selector.is_some() {
+                hasher.update(&[4]);
+            }
             hash_usize(children.len(), hasher)?;
             for (_, child) in children {
                 hash_node(child, resolved, hasher)?;
             }
         }
+        ResolvedNode::Array {
+            coordinates,
+            dimensions,
+            element,
+            ..
+        } => {
+            hasher.update(&[2]);
+            hash_usize(coordinates.offset, hasher)?;
+            hash_usize(coordinates.size, hasher)?;
+            hash_usize(coordinates.alignment, hasher)?;
+            hash_dimensions(Some(dimensions), hasher)?;
+            // The other elements repeat the first at its size, which the array size covers.
+            hash_node(element, resolved, hasher)?;
+        }
         ResolvedNode::Leaf {
             coordinates,
             scalar_abi,
@@ -8,8 +8,93 @@ pub struct Header {
     pub length: u32,
     #[serde(default = "default_padding")]
     pub padding: u8,
+    /// `[mint.signature]` config whose signature over the whole block data is appended after
+    /// the resolved layout.
+    pub signature: Option<String>,
+    /// `[mint.encryption]` config that encrypts the block data after integrity fields and the
+    /// signature trailer are computed.
+    pub encryption: Option<String>,
+    /// Wraps the block data in an MCUboot image header and TLV trailer.
+    pub mcuboot: Option<McubootImage>,
+}
+
+impl Header {
+    /// Address of the first data byte, after the MCUboot image header when there is one.
+    pub fn data_address(&self) -> u64 {
+        u64::from(self.start_address)
+            + self
+                .mcuboot
+                .as_ref()
+                .map_or(0, |image| u64::from(image.header_size))
+    }
 }
 
 fn default_padding() -> u8 {
     0xFF
 }
+
+/// MCUboot image settings. The block's `start_address` is the slot start, where the image header
+/// goes; the data follows at `start_address + header_size`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct McubootImage {
+    pub version: ImageVersion,
+    /// Octets reserved for the image header; the 32-octet header is zero-padded to this size.
+    #[serde(default = "default_mcuboot_header_size")]
+    pub header_size: u16,
+    /// RAM load address, which also sets `IMAGE_F_RAM_LOAD`.
+    pub load_address: Option<u32>,
+    /// `[mint.signature]` config that signs the image in the TLV trailer.
+    pub signature: Option<String>,
+}
+
+fn default_mcuboot_header_size() -> u16 {
+    0x20
+}
+
+/// MCUboot image version, written as `MAJOR.MINOR.REVISION+BUILD` like `imgtool --version`.
+/// Omitted components are zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct ImageVersion {
+    pub major: u8,
+    pub minor: u8,
+    pub revision: u16,
+    pub build: u32,
+}
+
+impl TryFrom<String> for ImageVersion {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        let invalid =
+            || format!("invalid MCUboot version '{text}'; expected MAJOR.MINOR.REVISION+BUILD");
+        let (numbers, build) = match text.split_once('+') {
+            Some((numbers, build)) => (numbers, Some(build)),
+            None => (text.as_str(), None),
+        };
+        let mut parts = numbers.split('.');
+        let mut next = || parts.next().map(str::parse::<u64>).transpose();
+        let (major, minor, revision) = (
+            next().map_err(|_| invalid())?,
+            next().map_err(|_| invalid())?,
+            next().map_err(|_| invalid())?,
+        );
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        let build = build
+            .map(str::parse::<u32>)
+            .transpose()
+            .map_err(|_| invalid())?;
+        Ok(Self {
+            major: major
+                .ok_or_else(invalid)?
+                .try_into()
+                .map_err(|_| invalid())?,
+            minor: minor.unwrap_or(0).try_into().map_err(|_| invalid())?,
+            revision: revision.unwrap_or(0).try_into().map_err(|_| invalid())?,
+            build: build.unwrap_or(0),
+        })
+    }
+}
@@ -0,0 +1,177 @@
+use super::error::LayoutError;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// `[mint]` sections whose entries are merged one by one across included files.
+const MERGED_MINT_SECTIONS: &[&str] = &[
+    "checksum",
+    "signature",
+    "hmac",
+    "encryption",
+    "const",
+    "types",
+    "enum",
+];
+
+/// Layout table assembled from a file and everything it includes, with the file that defined
+/// each key.
+#[derive(Default)]
+pub(super) struct IncludedLayout {
+    pub(super) table: toml::Table,
+    /// Directory of the root layout file, which `file` paths are rewritten relative to.
+    root: PathBuf,
+    origins: HashMap<String, PathBuf>,
+    included: Vec<PathBuf>,
+}
+
+impl IncludedLayout {
+    /// Reads `table` from `file`, first merging the files named by its `include` key.
+    pub(super) fn load(file: &Path, table: toml::Table) -> Result<Self, LayoutError> {
+        let file = canonical(file)?;
+        let mut layout = Self {
+            root: file.parent().unwrap_or(Path::new("")).to_owned(),
+            ..Self::default()
+        };
+        layout.merge_file(&file, table, &mut Vec::new())?;
+        Ok(layout)
+    }
+
+    fn merge_file(
+        &mut self,
+        file: &Path,
+        mut table: toml::Table,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<(), LayoutError> {
+        stack.push(file.to_owned());
+        let directory = file.parent().unwrap_or(Path::new(""));
+        for include in include_list(file, table.remove("include"))? {
+            let included = canonical(&directory.join(&include))?;
+            if stack.contains(&included) {
+                return Err(LayoutError::FileError(format!(
+                    "layout include cycle: {} -> {}",
+                    stack
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(" -> "),
+                    included.display()
+                )));
+            }
+            // A file reached through two includes contributes its keys once.
+            if self.included.contains(&included) {
+                continue;
+            }
+            self.included.push(included.clone());
+            let text = std::fs::read_to_string(&included).map_err(|_| {
+                LayoutError::FileError(format!(
+                    "failed to open file: {} (included from {})",
+                    included.display(),
+                    file.display()
+                ))
+            })?;
+            let included_table = text.parse::<toml::Table>().map_err(|error| {
+                LayoutError::FileError(format!(
+                    "failed to parse file {}: {error}",
+                    included.display()
+                ))
+            })?;
+            self.merge_file(&included, included_table, stack)?;
+        }
+        stack.pop();
+        super::file::resolve_paths(
+            &mut table,
+            &super::file::relative_path(directory, &self.root),
+        );
+        self.merge_table(file, table)
+    }
+
+    fn merge_table(&mut self, file: &Path, table: toml::Table) -> Result<(), LayoutError> {
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("mint", toml::Value::Table(mint)) => {
+                    for (section, value) in mint {
+                        let merged = match value {
+                            toml::Value::Table(entries)
+                                if MERGED_MINT_SECTIONS.contains(&section.as_str()) =>
+                            {
+                                entries
+                            }
+                            value => {
+                                self.insert(file, &["mint", &section], value)?;
+                                continue;
+                            }
+                        };
+                        for (name, value) in merged {
+                            self.insert(file, &["mint", &section, &name], value)?;
+                        }
+                    }
+                }
+                (_, value) => self.insert(file, &[&key], value)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, file: &Path, key: &[&str], value: toml::Value) -> Result<(), LayoutError> {
+        let dotted = key.join(".");
+        // A key also conflicts with keys nested under it and with the keys it nests under.
+        let existing = self.origins.iter().find(|(other, _)| {
+            **other == dotted
+                || other
+                    .strip_prefix(dotted.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+                || dotted
+                    .strip_prefix(other.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+        });
+        if let Some((_, existing)) = existing {
+            return Err(LayoutError::FileError(format!(
+                "duplicate layout key '{dotted}' in {} and {}",
+                existing.display(),
+                file.display()
+            )));
+        }
+        self.origins.insert(dotted, file.to_owned());
+
+        let Some((last, parents)) = key.split_last() else {
+            return Ok(());
+        };
+        let mut table = &mut self.table;
+        for parent in parents {
+            let entry = table
+                .entry(parent.to_owned())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            let toml::Value::Table(child) = entry else {
+                unreachable!("merged parents are tables");
+            };
+            table = child;
+        }
+        table.insert((*last).to_owned(), value);
+        Ok(())
+    }
+}
+
+fn include_list(file: &Path, value: Option<toml::Value>) -> Result<Vec<String>, LayoutError> {
+    let invalid = || {
+        LayoutError::FileError(format!(
+            "'include' in {} must be an array of layout file paths",
+            file.display()
+        ))
+    };
+    match value {
+        None => Ok(Vec::new()),
+        Some(toml::Value::Array(paths)) => paths
+            .into_iter()
+            .map(|path| match path {
+                toml::Value::String(path) => Ok(path),
+                _ => Err(invalid()),
+            })
+            .collect(),
+        Some(_) => Err(invalid()),
+    }
+}
+
+fn canonical(path: &Path) -> Result<PathBuf, LayoutError> {
+    path.canonicalize()
+        .map_err(|_| LayoutError::FileError(format!("failed to open file: {}", path.display())))
+}
@@ -1,13 +1,19 @@
 pub mod abi;
 pub mod block;
+pub(crate) mod constraint;
 mod conversions;
+mod curve;
 pub(crate) mod entry;
 pub mod error;
+mod expr;
+pub(crate) mod file;
 pub(crate) mod fingerprint;
 pub mod header;
+mod include;
 pub(crate) mod resolved;
 pub mod scalar_type;
 pub mod settings;
+mod types;
 pub(crate) mod used_values;
 pub mod value;
 
@@ -32,7 +38,18 @@ pub fn load_layout(filename: impl AsRef<Path>) -> Result<Config, LayoutError> {
         .unwrap_or_default();
 
     match ext.as_str() {
-        "toml" => parse_toml_layout_with_context(&text, &format!("file {}", filename.display())),
+        "toml" => {
+            let context = format!("file {}", filename.display());
+            let table = text.parse::<toml::Table>().map_err(|error| {
+                LayoutError::FileError(format!("failed to parse {context}: {error}"))
+            })?;
+            if !table.contains_key("include") {
+                return parse_toml_layout_with_context(&text, &context);
+            }
+            let mut included = include::IncludedLayout::load(filename, table)?;
+            expr::evaluate_layout(&mut included.table)?;
+            parse_table(included.table, &context)
+        }
         _ => Err(LayoutError::FileError(
             "Unsupported layout file format; use .toml".to_owned(),
         )),
@@ -40,16 +57,38 @@ pub fn load_layout(filename: impl AsRef<Path>) -> Result<Config, LayoutError> {
 }
 
 pub fn parse_toml_layout(text: &str) -> Result<Config, LayoutError> {
+    if let Ok(table) = text.parse::<toml::Table>()
+        && table.contains_key("include")
+    {
+        return Err(LayoutError::FileError(
+            "'include' is only supported in layout files loaded from disk".to_owned(),
+        ));
+    }
     parse_toml_layout_with_context(text, "TOML layout")
 }
 
 fn parse_toml_layout_with_context(text: &str, context: &str) -> Result<Config, LayoutError> {
+    // Parsing the text directly keeps error locations, so only layouts with expressions are
+    // deserialized from their evaluated table.
+    if let Ok(mut table) = text.parse::<toml::Table>()
+        && expr::evaluate_layout(&mut table)?
+    {
+        return parse_table(table, context);
+    }
     let mut cfg: Config = toml::from_str(text)
         .map_err(|e| LayoutError::FileError(format!("failed to parse {}: {}", context, e)))?;
     promote_block_header_consts(&mut cfg)?;
     Ok(cfg)
 }
 
+fn parse_table(table: toml::Table, context: &str) -> Result<Config, LayoutError> {
+    let mut cfg: Config = toml::Value::Table(table)
+        .try_into()
+        .map_err(|e| LayoutError::FileError(format!("failed to parse {context}: {e}")))?;
+    promote_block_header_consts(&mut cfg)?;
+    Ok(cfg)
+}
+
 pub(crate) fn validate_c_identifier(name: &str, kind: &str) -> Result<(), String> {
     if name.contains('.') {
         return Err(format!(
@@ -1,11 +1,17 @@
 use super::MAX_RESOLVED_BLOCK_SIZE;
 use super::abi::{Abi, ScalarAbi};
-use super::block::{Block, Entry};
+use super::block::{ArrayEntry, Block, Entry, UnionSelector};
 use super::entry::{EntrySource, LeafEntry, RefSource, RefTarget, SizeSource};
 use super::error::{LayoutError, in_field_path};
 use super::scalar_type::ScalarType;
-use super::settings::MintConfig;
+use super::settings::{ChecksumConfig, EncryptionAlgorithm, EncryptionConfig, MintConfig};
+use super::value::{DataValue, ValueSource};
+use crate::data::DataSource;
+use crate::output::{digest, mcuboot};
+use indexmap::IndexMap;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::ops::Range;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) struct ResolvedCoordinates {
@@ -18,6 +24,7 @@ pub(crate) struct ResolvedLayout<'a> {
     pub(crate) root: ResolvedNode<'a>,
     leaves: Vec<ResolvedLeafEntry<'a>>,
     nodes: HashMap<String, ResolvedTarget>,
+    unions: Vec<ResolvedUnion<'a>>,
     total_size: usize,
     abi: Abi,
 }
@@ -69,6 +76,13 @@ pub(crate) fn validate_static<'a>(
             output_end.saturating_sub(1)
         )));
     }
+    for union in &resolved.unions {
+        if !matches!(union.selector, UnionSelector::Name(_)) {
+            union
+                .member(None, &settings.consts)
+                .map_err(|error| in_field_path(&union.path, error))?;
+        }
+    }
     for (path, coordinates, _, leaf) in resolved.emission_leaves() {
         let size = leaf.size().map_err(|error| in_field_path(path, error))?;
         let result = match &leaf.source {
@@ -80,33 +94,357 @@ pub(crate) fn validate_static<'a>(
                     "2D arrays within the layout file are not supported.".to_owned(),
                 ))
             }
-            EntrySource::Checksum(_) if coordinates.offset == 0 => Err(LayoutError::InvalidLayout(
-                "Checksum must follow at least one data byte.".to_owned(),
-            )),
-            EntrySource::Checksum(name) => settings.checksum_config(name).map(|_| ()),
+            EntrySource::Checksum(_) if coordinates.offset == 0 && leaf.coverage().is_default() => {
+                Err(LayoutError::InvalidLayout(
+                    "Checksum must follow at least one data byte.".to_owned(),
+                ))
+            }
+            // Custom algorithms are bound, and their width checked, only when building.
+            EntrySource::Checksum(name) => {
+                settings
+                    .checksum_config(name)
+                    .and_then(|config| match config.algorithm.width() {
+                        Some(_) => leaf.validate_checksum_width(name, &config.algorithm),
+                        None => Ok(()),
+                    })
+            }
             EntrySource::Ref(source) => {
-                validate_ref_addresses(path, source, leaf, &resolved, block.header.start_address)
+                validate_ref_addresses(path, source, leaf, &resolved, block.header.data_address())
+            }
+            EntrySource::Signature(name) => settings.signature_config(name).and_then(|config| {
+                leaf.validate_byte_array_storage(
+                    "Signature",
+                    &format!("Signature '{}'", config.algorithm.name()),
+                    config.algorithm.signature_len(),
+                )
+            }),
+            EntrySource::Hmac(name) => settings.hmac_config(name).and_then(|_| {
+                leaf.validate_byte_array_storage("HMAC", "HMAC-SHA256", digest::HMAC_SHA256_LEN)
+            }),
+            EntrySource::CrcCompensation(compensation) => {
+                compensation.crc_config(settings).and_then(|config| {
+                    let length = usize::from(config.width / 8);
+                    if coordinates.size == length {
+                        Ok(())
+                    } else {
+                        Err(LayoutError::InvalidLayout(format!(
+                            "CRC compensation for a {}-bit CRC needs {length} bytes; field has {}.",
+                            config.width, coordinates.size
+                        )))
+                    }
+                })
+            }
+            EntrySource::PublicKeyHash(name) => settings.signature_config(name).and_then(|_| {
+                leaf.validate_byte_array_storage(
+                    "Public key hash",
+                    "Public key hash (SHA-256)",
+                    PUBLIC_KEY_HASH_LEN,
+                )
+            }),
+            EntrySource::EncryptionNonce(name) => {
+                block_encryption(block, settings, "encryption_nonce", name).and_then(|config| {
+                    leaf.validate_byte_array_storage(
+                        "Encryption nonce",
+                        &format!("Nonce for '{}'", config.algorithm.name()),
+                        config.algorithm.nonce_len(),
+                    )
+                })
+            }
+            EntrySource::EncryptionTag(name) => {
+                block_encryption(block, settings, "encryption_tag", name).and_then(|config| {
+                    if config.algorithm.tag_len() == 0 {
+                        return Err(LayoutError::InvalidLayout(format!(
+                            "'{}' is unauthenticated and produces no tag.",
+                            config.algorithm.name()
+                        )));
+                    }
+                    leaf.validate_byte_array_storage(
+                        "Encryption tag",
+                        &format!("Tag for '{}'", config.algorithm.name()),
+                        config.algorithm.tag_len(),
+                    )
+                })
             }
             _ => Ok(()),
         };
+        let result = result.and_then(|()| match &leaf.enum_name {
+            Some(name) => leaf.validate_enum(name, &settings.enums),
+            None => Ok(()),
+        });
         result.map_err(|error| in_field_path(path, error))?;
     }
+    let encryption_fields = resolved.encryption_fields();
+    for (source, fields) in [
+        ("encryption_nonce", &encryption_fields.nonce),
+        ("encryption_tag", &encryption_fields.tag),
+    ] {
+        if fields.len() > 1 {
+            return Err(LayoutError::InvalidLayout(format!(
+                "block may contain only one '{source}' field; found [{}]",
+                fields
+                    .iter()
+                    .map(|field| field.path)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+    }
+    for field in resolved.deferred_fields(block.header.length as usize)? {
+        let result = match &field.leaf.source {
+            EntrySource::Checksum(name) if field.block.is_none() => settings
+                .checksum_config(name)
+                .and_then(|config| check_input_words(name, config, &field.coverage)),
+            EntrySource::CrcCompensation(compensation) => settings
+                .checksum_config(&compensation.checksum)
+                .and_then(|config| {
+                    check_input_words(&compensation.checksum, config, &field.coverage)?;
+                    check_input_words(
+                        &compensation.checksum,
+                        config,
+                        std::slice::from_ref(&field.span()),
+                    )
+                }),
+            _ => Ok(()),
+        };
+        result.map_err(|error| in_field_path(field.path, error))?;
+    }
+    let emitted_size = resolved.emitted_size(block.header.length as usize)?;
+    let mut trailers = Vec::new();
+    if let Some(image) = &block.header.mcuboot {
+        if block.header.signature.is_some() || block.header.encryption.is_some() {
+            return Err(LayoutError::InvalidLayout(
+                "'mcuboot' cannot be combined with header 'signature' or 'encryption'; sign MCUboot images with 'mcuboot.signature'".to_owned(),
+            ));
+        }
+        if unit_octets != 1 {
+            return Err(LayoutError::InvalidLayout(format!(
+                "MCUboot images require an 8-bit addressable unit; ABI '{}' uses {} octets",
+                settings.abi.name(),
+                unit_octets
+            )));
+        }
+        if usize::from(image.header_size) < mcuboot::IMAGE_HEADER_LEN {
+            return Err(LayoutError::InvalidLayout(format!(
+                "MCUboot header_size 0x{:X} is smaller than the {}-octet image header",
+                image.header_size,
+                mcuboot::IMAGE_HEADER_LEN
+            )));
+        }
+        let signature = match &image.signature {
+            Some(name) => Some(settings.signature_config(name)?.algorithm),
+            None => None,
+        };
+        trailers.push((
+            "the MCUboot image header".to_owned(),
+            usize::from(image.header_size),
+        ));
+        trailers.push((
+            "the MCUboot TLV trailer".to_owned(),
+            mcuboot::tlv_area_len(signature),
+        ));
+    }
+    if let Some(name) = &block.header.signature {
+        let config = settings.signature_config(name)?;
+        trailers.push((
+            format!("the '{name}' signature trailer"),
+            config.algorithm.signature_len(),
+        ));
+    }
+    if let Some(name) = &block.header.encryption {
+        let length = encryption_fields.trailer_len(settings.encryption_config(name)?.algorithm);
+        if length > 0 {
+            trailers.push((format!("the '{name}' encryption trailer"), length));
+        }
+    }
+    let trailer_size = trailers.iter().map(|(_, length)| length).sum::<usize>();
+    if emitted_size + trailer_size > block.header.length as usize {
+        return Err(LayoutError::InvalidLayout(format!(
+            "resolved layout size ({emitted_size} octets) plus {} exceeds configured block length ({} octets)",
+            trailers
+                .iter()
+                .map(|(name, length)| format!("{name} ({length} octets)"))
+                .collect::<Vec<_>>()
+                .join(" and "),
+            block.header.length
+        )));
+    }
     Ok(resolved)
 }
 
+/// Checks that `ranges` hold whole input words when `config` reads memory in words.
+pub(crate) fn check_input_words(
+    name: &str,
+    config: &ChecksumConfig,
+    ranges: &[Range<usize>],
+) -> Result<(), LayoutError> {
+    let word = usize::from(config.input.word_bits / 8);
+    match ranges
+        .iter()
+        .find(|range| !range.start.is_multiple_of(word) || !range.end.is_multiple_of(word))
+    {
+        Some(range) => Err(LayoutError::InvalidLayout(format!(
+            "checksum '{name}' reads {}-bit words, but range 0x{:X}..0x{:X} does not hold whole words",
+            config.input.word_bits, range.start, range.end
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Fields that hold a block's encryption nonce and tag; validation allows at most one of each.
+#[derive(Default)]
+pub(crate) struct EncryptionFields<'a> {
+    pub(crate) nonce: Vec<EncryptionField<'a>>,
+    pub(crate) tag: Vec<EncryptionField<'a>>,
+}
+
+pub(crate) struct EncryptionField<'a> {
+    pub(crate) leaf_index: usize,
+    pub(crate) path: &'a str,
+    pub(crate) coordinates: ResolvedCoordinates,
+}
+
+impl EncryptionField<'_> {
+    pub(crate) fn span(&self) -> Range<usize> {
+        self.coordinates.offset..self.coordinates.offset + self.coordinates.size
+    }
+}
+
+impl EncryptionFields<'_> {
+    /// Length of the encryption trailer: the nonce and tag that have no field of their own.
+    pub(crate) fn trailer_len(&self, algorithm: EncryptionAlgorithm) -> usize {
+        let nonce = if self.nonce.is_empty() {
+            algorithm.nonce_len()
+        } else {
+            0
+        };
+        let tag = if self.tag.is_empty() {
+            algorithm.tag_len()
+        } else {
+            0
+        };
+        nonce + tag
+    }
+
+    /// Ranges of the first `data_len` bytes that are encrypted: everything but the fields.
+    pub(crate) fn encrypted_ranges(&self, data_len: usize) -> Vec<Range<usize>> {
+        let mut spans = self
+            .nonce
+            .iter()
+            .chain(&self.tag)
+            .map(EncryptionField::span)
+            .collect::<Vec<_>>();
+        spans.sort_by_key(|span| span.start);
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for span in spans {
+            if span.start > start {
+                ranges.push(start..span.start);
+            }
+            start = span.end;
+        }
+        if data_len > start {
+            ranges.push(start..data_len);
+        }
+        ranges
+    }
+}
+
+/// Returns the block's encryption config for an `encryption_nonce` or `encryption_tag` field,
+/// which must name the config in the block header.
+fn block_encryption<'s>(
+    block: &Block,
+    settings: &'s MintConfig,
+    source: &str,
+    name: &str,
+) -> Result<&'s EncryptionConfig, LayoutError> {
+    match &block.header.encryption {
+        Some(header) if header == name => settings.encryption_config(name),
+        Some(header) => Err(LayoutError::InvalidLayout(format!(
+            "'{source}' names '{name}', but the block header encrypts with '{header}'."
+        ))),
+        None => Err(LayoutError::InvalidLayout(format!(
+            "'{source}' requires header 'encryption = \"{name}\"'."
+        ))),
+    }
+}
+
+/// Public key hashes are SHA-256 of the encoded public key.
+pub(crate) const PUBLIC_KEY_HASH_LEN: usize = 32;
+
+/// Checksum, digest, signature, HMAC or CRC compensation field whose bytes are computed after
+/// every other field is emitted.
+pub(crate) struct DeferredField<'a> {
+    pub(crate) leaf_index: usize,
+    pub(crate) path: &'a str,
+    pub(crate) coordinates: ResolvedCoordinates,
+    pub(crate) leaf: &'a LeafEntry,
+    /// Covered byte ranges in ascending order, with excluded fields removed. Empty when the
+    /// field covers another block.
+    pub(crate) coverage: Vec<Range<usize>>,
+    /// Other block in the same layout whose emitted image the field covers.
+    pub(crate) block: Option<&'a str>,
+}
+
+impl DeferredField<'_> {
+    fn span(&self) -> Range<usize> {
+        self.coordinates.offset..self.coordinates.offset + self.coordinates.size
+    }
+
+    /// Returns the covered bytes of `buffer`, copying only when exclusions split the coverage.
+    pub(crate) fn covered<'b>(&self, buffer: &'b [u8]) -> std::borrow::Cow<'b, [u8]> {
+        match self.coverage.as_slice() {
+            [range] => std::borrow::Cow::Borrowed(&buffer[range.clone()]),
+            ranges => std::borrow::Cow::Owned(
+                ranges
+                    .iter()
+                    .flat_map(|range| &buffer[range.clone()])
+                    .copied()
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns where buffer `offset` lands within the bytes returned by [`Self::covered`].
+    pub(crate) fn covered_position(&self, offset: usize) -> Option<usize> {
+        let mut position = 0;
+        for range in &self.coverage {
+            if range.contains(&offset) {
+                return Some(position + offset - range.start);
+            }
+            position += range.len();
+        }
+        None
+    }
+}
+
+fn ranges_overlap(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
 impl<'a> ResolvedLayout<'a> {
     pub(crate) fn new(entry: &'a Entry, abi: Abi) -> Result<Self, LayoutError> {
+        if entry.offset().is_some() {
+            return Err(layout_size_error(
+                "block data cannot have an 'offset'; set offsets on its fields",
+            ));
+        }
+        if let Entry::Branch(branch) = entry
+            && branch.selector.is_some()
+        {
+            return Err(layout_size_error(
+                "block data cannot be a union; declare the union as a field",
+            ));
+        }
         let mut root = collect_entry(entry, abi, &mut Vec::new())?;
         let mut cursor = 0usize;
-        let mut leaves = Vec::new();
-        let mut nodes = HashMap::new();
-        layout_node(
-            &mut root,
-            &mut cursor,
-            &mut Vec::new(),
-            &mut leaves,
-            &mut nodes,
-        )?;
+        let mut sink = LayoutSink::default();
+        layout_node(&mut root, &mut cursor, &mut Vec::new(), "", &mut sink)?;
+        let LayoutSink {
+            leaves,
+            nodes,
+            unions,
+            ..
+        } = sink;
 
         for leaf in &leaves {
             if let EntrySource::Ref(source) = &leaf.leaf.source {
@@ -133,11 +471,42 @@ impl<'a> ResolvedLayout<'a> {
             root,
             leaves,
             nodes,
+            unions,
             total_size: cursor,
             abi,
         })
     }
 
+    /// Selects the populated member of every union, in layout order. Unions inside members
+    /// that are not populated select nothing.
+    pub(crate) fn select_union_members(
+        &self,
+        data_source: Option<&dyn DataSource>,
+        consts: &HashMap<String, ValueSource>,
+    ) -> Result<Vec<Option<usize>>, LayoutError> {
+        let mut selected = Vec::with_capacity(self.unions.len());
+        for union in &self.unions {
+            let member = if is_within(&union.within, &selected) {
+                Some(
+                    union
+                        .member(data_source, consts)
+                        .map_err(|error| in_field_path(&union.path, error))?,
+                )
+            } else {
+                None
+            };
+            selected.push(member);
+        }
+        Ok(selected)
+    }
+
+    /// Returns whether leaf `leaf_index` lies in the populated member of every union around it.
+    pub(crate) fn is_populated(&self, leaf_index: usize, selected: &[Option<usize>]) -> bool {
+        self.leaves
+            .get(leaf_index)
+            .is_some_and(|leaf| is_within(&leaf.within, selected))
+    }
+
     pub(crate) fn coordinates(&self, path: &str) -> Option<ResolvedCoordinates> {
         self.nodes.get(path).map(|target| target.coordinates)
     }
@@ -146,6 +515,42 @@ impl<'a> ResolvedLayout<'a> {
         self.total_size
     }
 
+    /// Size of the emitted image before any header trailer: the layout, extended by `to_length`
+    /// coverage into the tail padding.
+    pub(crate) fn emitted_size(&self, block_length: usize) -> Result<usize, LayoutError> {
+        Ok(self
+            .deferred_fields(block_length)?
+            .iter()
+            .filter_map(|field| field.coverage.last())
+            .fold(self.total_size, |size, range| size.max(range.end)))
+    }
+
+    /// Returns the `encryption_nonce` and `encryption_tag` fields.
+    pub(crate) fn encryption_fields(&self) -> EncryptionFields<'_> {
+        let mut fields = EncryptionFields::default();
+        for (leaf_index, leaf) in self.leaves.iter().enumerate() {
+            let list = match &leaf.leaf.source {
+                EntrySource::EncryptionNonce(_) => &mut fields.nonce,
+                EntrySource::EncryptionTag(_) => &mut fields.tag,
+                _ => continue,
+            };
+            list.push(EncryptionField {
+                leaf_index,
+                path: &leaf.path,
+                coordinates: leaf.coordinates,
+            });
+        }
+        fields
+    }
+
+    /// Prefix that leaf `leaf_index` adds to its data-source names; empty outside arrays of
+    /// structs.
+    pub(crate) fn data_prefix(&self, leaf_index: usize) -> &str {
+        self.leaves
+            .get(leaf_index)
+            .map_or("", |leaf| leaf.data_prefix.as_str())
+    }
+
     pub(crate) fn emission_leaves(
         &self,
     ) -> impl ExactSizeIterator<Item = (&str, ResolvedCoordinates, ScalarAbi, &LeafEntry)> {
@@ -159,6 +564,154 @@ impl<'a> ResolvedLayout<'a> {
         })
     }
 
+    /// Returns checksum, digest, signature, HMAC and CRC compensation fields in resolution order.
+    ///
+    /// A field is resolved only after every other deferred field inside its coverage, so a
+    /// checksum after a digest covers the final digest bytes and vice versa. Field order breaks
+    /// ties; coverage that depends on itself through other fields is rejected. `block_length`
+    /// bounds `to_length` coverage.
+    pub(crate) fn deferred_fields(
+        &self,
+        block_length: usize,
+    ) -> Result<Vec<DeferredField<'_>>, LayoutError> {
+        let mut pending = Vec::new();
+        for (leaf_index, leaf) in self.leaves.iter().enumerate() {
+            if !matches!(
+                leaf.leaf.source,
+                EntrySource::Checksum(_)
+                    | EntrySource::Digest(_)
+                    | EntrySource::Signature(_)
+                    | EntrySource::Hmac(_)
+                    | EntrySource::CrcCompensation(_)
+            ) {
+                continue;
+            }
+            let coverage = self
+                .coverage_ranges(leaf, block_length)
+                .map_err(|error| in_field_path(&leaf.path, error))?;
+            pending.push(DeferredField {
+                leaf_index,
+                path: &leaf.path,
+                coordinates: leaf.coordinates,
+                leaf: leaf.leaf,
+                coverage,
+                block: leaf.leaf.coverage().block.as_deref(),
+            });
+        }
+
+        let mut ordered = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            let ready = pending.iter().position(|field| {
+                pending.iter().all(|other| {
+                    std::ptr::eq(field, other)
+                        || !field
+                            .coverage
+                            .iter()
+                            .any(|range| ranges_overlap(range, &other.span()))
+                })
+            });
+            let Some(ready) = ready else {
+                return Err(LayoutError::InvalidLayout(format!(
+                    "integrity field coverage is circular between fields [{}]",
+                    pending
+                        .iter()
+                        .map(|field| field.path)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+            };
+            ordered.push(pending.remove(ready));
+        }
+        Ok(ordered)
+    }
+
+    fn coverage_ranges(
+        &self,
+        leaf: &ResolvedLeafEntry<'_>,
+        block_length: usize,
+    ) -> Result<Vec<Range<usize>>, LayoutError> {
+        let coverage = leaf.leaf.coverage();
+        if coverage.block.is_some() {
+            return Ok(Vec::new());
+        }
+        // Compensation bytes must sit inside the range they balance, so it defaults to the whole
+        // layout rather than stopping at the field.
+        let compensation = matches!(leaf.leaf.source, EntrySource::CrcCompensation(_));
+        let lookup = |path: &str, key: &str| {
+            self.coordinates(path).ok_or_else(|| {
+                LayoutError::InvalidLayout(format!(
+                    "coverage '{key}' path '{path}' not found in block. Available fields: [{}]",
+                    self.nodes.keys().cloned().collect::<Vec<_>>().join(", ")
+                ))
+            })
+        };
+        let start = match &coverage.from {
+            Some(path) => lookup(path, "from")?.offset,
+            None => 0,
+        };
+        let end = match &coverage.to {
+            Some(path) => {
+                let target = lookup(path, "to")?;
+                target.offset + target.size
+            }
+            None if coverage.to_length => block_length,
+            None if compensation || start > leaf.coordinates.offset => self.total_size,
+            None => leaf.coordinates.offset,
+        };
+        if start >= end {
+            return Err(LayoutError::InvalidLayout(format!(
+                "coverage range 0x{start:X}..0x{end:X} is empty"
+            )));
+        }
+
+        let mut excluded = Vec::with_capacity(coverage.exclude.len());
+        for path in &coverage.exclude {
+            let target = lookup(path, "exclude")?;
+            let range = target.offset..target.offset + target.size;
+            if !ranges_overlap(&range, &(start..end)) {
+                return Err(LayoutError::InvalidLayout(format!(
+                    "coverage 'exclude' path '{path}' is outside coverage range 0x{start:X}..0x{end:X}"
+                )));
+            }
+            excluded.push(range);
+        }
+        excluded.sort_by_key(|range| range.start);
+
+        let mut ranges = Vec::new();
+        let mut cursor = start;
+        for range in excluded {
+            if cursor < end && range.start > cursor {
+                ranges.push(cursor..range.start.min(end));
+            }
+            cursor = cursor.max(range.end);
+        }
+        if cursor < end {
+            ranges.push(cursor..end);
+        }
+        if ranges.is_empty() {
+            return Err(LayoutError::InvalidLayout(format!(
+                "coverage range 0x{start:X}..0x{end:X} is empty after exclusions"
+            )));
+        }
+
+        let own = leaf.coordinates.offset..leaf.coordinates.offset + leaf.coordinates.size;
+        if compensation {
+            if !ranges
+                .iter()
+                .any(|range| range.start <= own.start && own.end <= range.end)
+            {
+                return Err(LayoutError::InvalidLayout(format!(
+                    "coverage range 0x{start:X}..0x{end:X} must include the compensation field"
+                )));
+            }
+        } else if ranges.iter().any(|range| ranges_overlap(range, &own)) {
+            return Err(LayoutError::InvalidLayout(format!(
+                "coverage range 0x{start:X}..0x{end:X} includes the field itself; exclude it or narrow 'from'/'to'"
+            )));
+        }
+        Ok(ranges)
+    }
+
     pub(crate) fn target(&self, path: &str) -> Option<ResolvedTarget> {
         self.nodes.get(path).copied()
     }
@@ -170,7 +723,7 @@ impl<'a> ResolvedLayout<'a> {
     pub(crate) fn ref_address(
         &self,
         target: &RefTarget,
-        start_address: u32,
+        start_address: u64,
     ) -> Result<u64, LayoutError> {
         match target {
             RefTarget::Address(address) => Ok(*address),
@@ -181,23 +734,41 @@ impl<'a> ResolvedLayout<'a> {
                     ))
                 })?;
                 let target_offset = self.abi.offset_to_address_units(target.offset)?;
-                u64::from(start_address)
-                    .checked_add(target_offset)
-                    .ok_or_else(|| {
-                        LayoutError::InvalidLayout(format!(
-                            "address overflow resolving ref target '{path}'"
-                        ))
-                    })
+                start_address.checked_add(target_offset).ok_or_else(|| {
+                    LayoutError::InvalidLayout(format!(
+                        "address overflow resolving ref target '{path}'"
+                    ))
+                })
             }
         }
     }
 }
 
+#[derive(Clone)]
 pub(crate) enum ResolvedNode<'a> {
     Branch {
         coordinates: ResolvedCoordinates,
+        /// Explicit offset within the enclosing branch.
+        placement: Option<usize>,
+        /// Children are placed at the branch's own alignment of one addressable unit.
+        packed: bool,
+        /// Selector of a union branch, whose children all start at its offset.
+        selector: Option<&'a UnionSelector>,
+        /// Data-source name that prefixes the leaf names of a `struct` field with a `name`.
+        data_name: Option<String>,
         children: Vec<(String, ResolvedNode<'a>)>,
     },
+    /// Array of structs; every element is a branch with the same fields, in row-major order.
+    Array {
+        coordinates: ResolvedCoordinates,
+        /// Explicit offset within the enclosing branch.
+        placement: Option<usize>,
+        dimensions: SizeSource,
+        /// Data-source name that prefixes element leaf names.
+        data_name: String,
+        /// First element; the others repeat its layout at a stride of its size.
+        element: Box<ResolvedNode<'a>>,
+    },
     Leaf {
         coordinates: ResolvedCoordinates,
         scalar_abi: ScalarAbi,
@@ -207,9 +778,19 @@ pub(crate) enum ResolvedNode<'a> {
 }
 
 impl ResolvedNode<'_> {
-    fn coordinates(&self) -> ResolvedCoordinates {
+    pub(crate) fn coordinates(&self) -> ResolvedCoordinates {
+        match self {
+            Self::Branch { coordinates, .. }
+            | Self::Array { coordinates, .. }
+            | Self::Leaf { coordinates, .. } => *coordinates,
+        }
+    }
+
+    /// Returns the explicit offset of the node within its enclosing branch, if set.
+    pub(crate) fn placement(&self) -> Option<usize> {
         match self {
-            Self::Branch { coordinates, .. } | Self::Leaf { coordinates, .. } => *coordinates,
+            Self::Branch { placement, .. } | Self::Array { placement, .. } => *placement,
+            Self::Leaf { leaf, .. } => leaf.offset,
         }
     }
 
@@ -221,13 +802,23 @@ impl ResolvedNode<'_> {
                         .iter()
                         .any(|(_, child)| child.is_larger_than_one_octet())
             }
+            Self::Array {
+                dimensions,
+                element,
+                ..
+            } => {
+                let single = matches!(dimensions, SizeSource::OneD(1) | SizeSource::TwoD([1, 1]));
+                !single || element.is_larger_than_one_octet()
+            }
             Self::Leaf { coordinates, .. } => coordinates.size > 1,
         }
     }
 
     fn coordinates_mut(&mut self) -> &mut ResolvedCoordinates {
         match self {
-            Self::Branch { coordinates, .. } | Self::Leaf { coordinates, .. } => coordinates,
+            Self::Branch { coordinates, .. }
+            | Self::Array { coordinates, .. }
+            | Self::Leaf { coordinates, .. } => coordinates,
         }
     }
 
@@ -235,7 +826,7 @@ impl ResolvedNode<'_> {
         ResolvedTarget {
             coordinates: self.coordinates(),
             kind: match self {
-                Self::Branch { .. } => TargetKind::Branch,
+                Self::Branch { .. } | Self::Array { .. } => TargetKind::Branch,
                 Self::Leaf { .. } => TargetKind::Leaf,
             },
         }
@@ -244,9 +835,100 @@ impl ResolvedNode<'_> {
 
 struct ResolvedLeafEntry<'a> {
     path: String,
+    /// Prefix of the leaf's data-source names inside arrays of structs and named struct
+    /// fields, such as `ch[2].`.
+    data_prefix: String,
     coordinates: ResolvedCoordinates,
     scalar_abi: ScalarAbi,
     leaf: &'a LeafEntry,
+    /// Union members the leaf lies in, outermost first.
+    within: Vec<UnionMember>,
+}
+
+/// Union instance; every element of an array of unions is a separate instance.
+struct ResolvedUnion<'a> {
+    path: String,
+    data_prefix: String,
+    selector: &'a UnionSelector,
+    members: Vec<String>,
+    /// Members of enclosing unions, outermost first.
+    within: Vec<UnionMember>,
+}
+
+#[derive(Clone, Copy)]
+struct UnionMember {
+    union_index: usize,
+    member: usize,
+}
+
+impl ResolvedUnion<'_> {
+    /// Returns the index of the member that the selector names.
+    fn member(
+        &self,
+        data_source: Option<&dyn DataSource>,
+        consts: &HashMap<String, ValueSource>,
+    ) -> Result<usize, LayoutError> {
+        let name = match self.selector {
+            UnionSelector::Value(member) => Cow::Borrowed(member.as_str()),
+            UnionSelector::Const(name) => match consts.get(name) {
+                Some(ValueSource::Single(DataValue::Str(member))) => Cow::Borrowed(member.as_str()),
+                Some(_) => {
+                    return Err(LayoutError::InvalidLayout(format!(
+                        "union const '{name}' must be a string naming a member"
+                    )));
+                }
+                None => {
+                    return Err(LayoutError::InvalidLayout(format!(
+                        "Const '{name}' not found in [mint.const]. Available: [{}]",
+                        consts.keys().cloned().collect::<Vec<_>>().join(", ")
+                    )));
+                }
+            },
+            UnionSelector::Name(name) => {
+                let Some(source) = data_source else {
+                    return Err(LayoutError::MissingDataSheet(format!(
+                        "Union '{}' selects its member by data-source name '{name}', but no data source was provided.",
+                        self.path
+                    )));
+                };
+                match source.retrieve_symbol(&format!("{}{name}", self.data_prefix))? {
+                    DataValue::Str(member) => Cow::Owned(member),
+                    value => {
+                        return Err(LayoutError::DataValueExportFailed(format!(
+                            "union selector '{}{name}' must be a string naming a member; found {value:?}",
+                            self.data_prefix
+                        )));
+                    }
+                }
+            }
+        };
+        self.members
+            .iter()
+            .position(|member| *member == name)
+            .ok_or_else(|| {
+                LayoutError::DataValueExportFailed(format!(
+                    "union has no member '{name}'. Available members: [{}]",
+                    self.members.join(", ")
+                ))
+            })
+    }
+}
+
+/// Returns whether every union member in `within` is the selected member of its union.
+fn is_within(within: &[UnionMember], selected: &[Option<usize>]) -> bool {
+    within
+        .iter()
+        .all(|member| selected.get(member.union_index).copied().flatten() == Some(member.member))
+}
+
+/// Leaves, paths and unions gathered while laying out a tree of nodes.
+#[derive(Default)]
+struct LayoutSink<'a> {
+    leaves: Vec<ResolvedLeafEntry<'a>>,
+    nodes: HashMap<String, ResolvedTarget>,
+    unions: Vec<ResolvedUnion<'a>>,
+    /// Union members enclosing the node being laid out, outermost first.
+    within: Vec<UnionMember>,
 }
 
 #[derive(Clone, Copy)]
@@ -261,6 +943,23 @@ pub(crate) enum TargetKind {
     Leaf,
 }
 
+/// Resolves `fields` as a standalone struct starting at offset zero, such as a struct type.
+pub(crate) fn resolve_fields<'a>(
+    fields: &'a IndexMap<String, Entry>,
+    packed: bool,
+    abi: Abi,
+) -> Result<ResolvedNode<'a>, LayoutError> {
+    let mut node = collect_branch(fields, packed, None, abi, &mut Vec::new())?;
+    layout_node(
+        &mut node,
+        &mut 0,
+        &mut Vec::new(),
+        "",
+        &mut LayoutSink::default(),
+    )?;
+    Ok(node)
+}
+
 fn collect_entry<'a>(
     entry: &'a Entry,
     abi: Abi,
@@ -293,9 +992,25 @@ fn collect_entry<'a>(
                 EntrySource::Checksum(_) => {
                     leaf.validate_checksum_storage()?;
                 }
+                EntrySource::CrcCompensation(_) => {
+                    leaf.validate_compensation_storage()?;
+                }
+                EntrySource::Digest(algorithm) => {
+                    leaf.validate_byte_array_storage(
+                        "Digest",
+                        &format!("Digest '{}'", algorithm.name()),
+                        algorithm.output_len(),
+                    )?;
+                }
                 EntrySource::Fingerprint(_) => {
                     leaf.validate_fingerprint()?;
                 }
+                EntrySource::Axis(_) => {
+                    leaf.validate_axis()?;
+                }
+                EntrySource::File(_) => {
+                    leaf.validate_file()?;
+                }
                 _ => {}
             }
             Ok(ResolvedNode::Leaf {
@@ -309,46 +1024,165 @@ fn collect_entry<'a>(
                 dimensions,
             })
         }
-        Entry::Branch(entries) => {
-            if entries.is_empty() {
-                let name = if path.is_empty() {
-                    "<root>".to_owned()
-                } else {
-                    path.join(".")
-                };
-                return Err(layout_size_error(format!(
-                    "empty branch '{name}' is invalid"
-                )));
+        Entry::Branch(branch) => {
+            let mut node = collect_branch(
+                &branch.fields,
+                branch.packed,
+                branch.selector.as_ref(),
+                abi,
+                path,
+            )?;
+            if let ResolvedNode::Branch { placement, .. } = &mut node {
+                *placement = branch.offset;
             }
-
-            let mut children = Vec::with_capacity(entries.len());
-            for (name, child) in entries {
-                path.push(name.clone());
-                let child = collect_entry(child, abi, path)?;
-                path.pop();
-                children.push((name.clone(), child));
-            }
-            let mut alignment = children
-                .iter()
-                .map(|(_, child)| child.coordinates().alignment)
-                .max()
-                .unwrap_or(1);
-            if children.len() > 1
-                || children
-                    .iter()
-                    .any(|(_, child)| child.is_larger_than_one_octet())
+            Ok(node)
+        }
+        Entry::Array(array) => collect_array(array, abi, path),
+        Entry::Struct(field) => {
+            let mut node =
+                collect_branch(struct_fields(entry)?, entry.is_packed(), None, abi, path)?;
+            if let ResolvedNode::Branch {
+                placement,
+                data_name,
+                ..
+            } = &mut node
             {
-                alignment = alignment.max(abi.family().min_aggregate_alignment());
+                *placement = field.offset;
+                data_name.clone_from(&field.name);
             }
-            Ok(ResolvedNode::Branch {
+            Ok(node)
+        }
+    }
+}
+
+fn collect_branch<'a>(
+    entries: &'a IndexMap<String, Entry>,
+    packed: bool,
+    selector: Option<&'a UnionSelector>,
+    abi: Abi,
+    path: &mut Vec<String>,
+) -> Result<ResolvedNode<'a>, LayoutError> {
+    if entries.is_empty() {
+        let name = if path.is_empty() {
+            "<root>".to_owned()
+        } else {
+            path.join(".")
+        };
+        return Err(layout_size_error(format!(
+            "empty branch '{name}' is invalid"
+        )));
+    }
+
+    let mut children = Vec::with_capacity(entries.len());
+    for (name, child) in entries {
+        path.push(name.clone());
+        if selector.is_some() && child.offset().is_some() {
+            return Err(layout_size_error(format!(
+                "union member '{}' cannot set 'offset'; every member starts at the union's offset",
+                path.join(".")
+            )));
+        }
+        let child = collect_entry(child, abi, path)?;
+        path.pop();
+        children.push((name.clone(), child));
+    }
+    let mut alignment = children
+        .iter()
+        .map(|(_, child)| child.coordinates().alignment)
+        .max()
+        .unwrap_or(1);
+    if packed {
+        alignment = abi.address_unit_octets();
+    } else if children.len() > 1
+        || children
+            .iter()
+            .any(|(_, child)| child.is_larger_than_one_octet())
+    {
+        alignment = alignment.max(abi.family().min_aggregate_alignment());
+    }
+    Ok(ResolvedNode::Branch {
+        coordinates: ResolvedCoordinates {
+            offset: 0,
+            size: 0,
+            alignment,
+        },
+        placement: None,
+        packed,
+        selector,
+        data_name: None,
+        children,
+    })
+}
+
+fn collect_array<'a>(
+    array: &'a ArrayEntry,
+    abi: Abi,
+    path: &mut Vec<String>,
+) -> Result<ResolvedNode<'a>, LayoutError> {
+    let Some(key) = path.last() else {
+        return Err(layout_size_error(
+            "block data cannot have a 'size'; declare the array as a field",
+        ));
+    };
+    let data_name = array.name.clone().unwrap_or_else(|| key.clone());
+    let count = match array.size {
+        SizeSource::OneD(length) => Some(length),
+        SizeSource::TwoD([rows, columns]) => rows.checked_mul(columns),
+    };
+    match count {
+        Some(0) => Err(layout_size_error(format!(
+            "array '{}' has a zero extent",
+            path.join(".")
+        ))),
+        // Every element occupies at least one octet, so larger arrays cannot fit.
+        Some(count) if count <= MAX_RESOLVED_BLOCK_SIZE => {
+            let selector = match array.element.as_ref() {
+                Entry::Branch(branch) => branch.selector.as_ref(),
+                _ => None,
+            };
+            let element = collect_branch(
+                struct_fields(&array.element)?,
+                array.element.is_packed(),
+                selector,
+                abi,
+                path,
+            )?;
+            Ok(ResolvedNode::Array {
                 coordinates: ResolvedCoordinates {
                     offset: 0,
                     size: 0,
-                    alignment,
+                    alignment: element.coordinates().alignment,
                 },
-                children,
+                placement: array.offset,
+                dimensions: array.size.clone(),
+                data_name,
+                element: Box::new(element),
             })
         }
+        _ => Err(layout_size_error(format!(
+            "array '{}' has more elements than fit in Mint's materialized block limit ({MAX_RESOLVED_BLOCK_SIZE} octets)",
+            path.join(".")
+        ))),
+    }
+}
+
+/// Returns the fields of a branch or bound struct type.
+fn struct_fields(entry: &Entry) -> Result<&IndexMap<String, Entry>, LayoutError> {
+    match (entry.struct_fields(), entry) {
+        (Some(fields), _) => Ok(fields),
+        (None, Entry::Struct(field)) => Err(layout_size_error(format!(
+            "struct type '{}' is not bound; parse the layout with its [mint.types]",
+            field.type_name
+        ))),
+        (None, _) => unreachable!("array elements are branches or struct types"),
+    }
+}
+
+/// Index suffix of element `index` of an array of structs, such as `[3]` or `[1][2]`.
+fn element_suffix(dimensions: &SizeSource, index: usize) -> String {
+    match dimensions {
+        SizeSource::OneD(_) => format!("[{index}]"),
+        SizeSource::TwoD([_, columns]) => format!("[{}][{}]", index / columns, index % columns),
     }
 }
 
@@ -357,7 +1191,7 @@ fn validate_ref_addresses(
     source: &RefSource,
     leaf: &LeafEntry,
     resolved: &ResolvedLayout<'_>,
-    start_address: u32,
+    start_address: u64,
 ) -> Result<(), LayoutError> {
     let maximum = match leaf.scalar_type {
         ScalarType::U16 => u64::from(u16::MAX),
@@ -387,14 +1221,14 @@ fn validate_ref_addresses(
     Ok(())
 }
 
+/// Lays out `node` at `cursor`, which the caller has aligned within the enclosing struct.
 fn layout_node<'a>(
     node: &mut ResolvedNode<'a>,
     cursor: &mut usize,
     path: &mut Vec<String>,
-    leaves: &mut Vec<ResolvedLeafEntry<'a>>,
-    nodes: &mut HashMap<String, ResolvedTarget>,
+    data_prefix: &str,
+    sink: &mut LayoutSink<'a>,
 ) -> Result<(), LayoutError> {
-    *cursor = aligned_offset(*cursor, node.coordinates().alignment)?;
     let offset = *cursor;
     node.coordinates_mut().offset = offset;
 
@@ -405,33 +1239,173 @@ fn layout_node<'a>(
             leaf,
             ..
         } => {
+            if !sink.within.is_empty()
+                && matches!(
+                    leaf.source,
+                    EntrySource::Checksum(_)
+                        | EntrySource::Digest(_)
+                        | EntrySource::Signature(_)
+                        | EntrySource::Hmac(_)
+                        | EntrySource::CrcCompensation(_)
+                        | EntrySource::EncryptionNonce(_)
+                        | EntrySource::EncryptionTag(_)
+                )
+            {
+                return Err(layout_size_error(format!(
+                    "field '{}' lies in a union member, which cannot hold integrity or encryption fields",
+                    path.join(".")
+                )));
+            }
             *cursor = cursor
                 .checked_add(coordinates.size)
                 .ok_or_else(|| layout_size_error("leaf byte count overflow"))?;
-            leaves.push(ResolvedLeafEntry {
+            sink.leaves.push(ResolvedLeafEntry {
                 path: path.join("."),
+                data_prefix: data_prefix.to_owned(),
                 coordinates: *coordinates,
                 scalar_abi: *scalar_abi,
                 leaf,
+                within: sink.within.clone(),
             });
         }
         ResolvedNode::Branch {
             coordinates,
+            packed,
+            selector,
+            data_name,
             children,
+            ..
         } => {
-            for (name, child) in children {
+            let data_prefix = match data_name {
+                Some(data_name) => Cow::Owned(format!("{data_prefix}{data_name}.")),
+                None => Cow::Borrowed(data_prefix),
+            };
+            let union_index = selector.map(|selector| {
+                sink.unions.push(ResolvedUnion {
+                    path: path.join("."),
+                    data_prefix: data_prefix.to_string(),
+                    selector,
+                    members: children.iter().map(|(name, _)| name.clone()).collect(),
+                    within: sink.within.clone(),
+                });
+                sink.unions.len() - 1
+            });
+            let mut end = offset;
+            for (member, (name, child)) in children.iter_mut().enumerate() {
                 path.push(name.clone());
-                layout_node(child, cursor, path, leaves, nodes)?;
-                nodes.insert(path.join("."), child.target());
+                let alignment = if *packed {
+                    coordinates.alignment
+                } else {
+                    child.coordinates().alignment
+                };
+                // Union members overlay each other from the union's offset. Other fields align
+                // relative to the branch, which a packed parent may place at any addressable
+                // unit.
+                *cursor = match (union_index, child.placement()) {
+                    (Some(union_index), _) => {
+                        sink.within.push(UnionMember {
+                            union_index,
+                            member,
+                        });
+                        offset
+                    }
+                    (None, Some(placement)) => {
+                        placed_offset(placement, alignment, offset, *cursor, path)?
+                    }
+                    (None, None) => offset + aligned_offset(*cursor - offset, alignment)?,
+                };
+                layout_node(child, cursor, path, &data_prefix, sink)?;
+                if union_index.is_some() {
+                    sink.within.pop();
+                }
+                end = end.max(*cursor);
+                sink.nodes.insert(path.join("."), child.target());
                 path.pop();
             }
-            *cursor = aligned_offset(*cursor, coordinates.alignment)?;
+            *cursor = offset + aligned_offset(end - offset, coordinates.alignment)?;
+            coordinates.size = *cursor - offset;
+        }
+        ResolvedNode::Array {
+            coordinates,
+            dimensions,
+            data_name,
+            element,
+            ..
+        } => {
+            let Some(key) = path.pop() else {
+                unreachable!("arrays of structs are always named fields");
+            };
+            let count = match dimensions {
+                SizeSource::OneD(length) => *length,
+                SizeSource::TwoD([rows, columns]) => *rows * *columns,
+            };
+            let template = element.as_ref().clone();
+            let mut lay_out = |index: usize, node: &mut ResolvedNode<'a>| {
+                let suffix = element_suffix(dimensions, index);
+                path.push(format!("{key}{suffix}"));
+                let element_prefix = format!("{data_prefix}{data_name}{suffix}.");
+                // Element sizes are multiples of their alignment, so elements stay aligned.
+                layout_node(node, cursor, path, &element_prefix, sink)?;
+                sink.nodes.insert(path.join("."), node.target());
+                path.pop();
+                Ok::<_, LayoutError>(())
+            };
+            // Only the first element is kept. Check the whole array against the limit before
+            // laying out the others, which are only walked for their leaves and paths.
+            lay_out(0, element)?;
+            let stride = element.coordinates().size;
+            let fits = stride
+                .checked_mul(count)
+                .and_then(|size| size.checked_add(offset))
+                .is_some_and(|end| end <= MAX_RESOLVED_BLOCK_SIZE);
+            if !fits {
+                return Err(layout_size_error(format!(
+                    "array '{}' of {count} {stride}-octet elements exceeds Mint's materialized block limit ({MAX_RESOLVED_BLOCK_SIZE} octets)",
+                    path.iter()
+                        .chain([&key])
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(".")
+                )));
+            }
+            for index in 1..count {
+                lay_out(index, &mut template.clone())?;
+            }
+            path.push(key);
             coordinates.size = *cursor - offset;
         }
     }
     Ok(())
 }
 
+/// Returns the position of a child with an explicit `offset` in a branch that starts at
+/// `branch_offset`, rejecting offsets that are misaligned or overlap the fields before it.
+fn placed_offset(
+    placement: usize,
+    alignment: usize,
+    branch_offset: usize,
+    cursor: usize,
+    path: &[String],
+) -> Result<usize, LayoutError> {
+    if !placement.is_multiple_of(alignment) {
+        return Err(layout_size_error(format!(
+            "field '{}' offset 0x{placement:X} is not a multiple of its {alignment}-octet alignment",
+            path.join(".")
+        )));
+    }
+    let position = branch_offset
+        .checked_add(placement)
+        .ok_or_else(|| layout_size_error("field offset overflow"))?;
+    if position < cursor {
+        return Err(layout_size_error(format!(
+            "field '{}' offset 0x{placement:X} overlaps the preceding fields, which end at offset 0x{:X}",
+            path.join("."),
+            cursor - branch_offset
+        )));
+    }
+    Ok(position)
+}
+
 fn leaf_size(scalar_abi: ScalarAbi, dimensions: Option<&SizeSource>) -> Result<usize, LayoutError> {
     let elements = match dimensions {
         None => 1,
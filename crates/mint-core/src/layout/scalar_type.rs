@@ -4,7 +4,9 @@ use std::str::FromStr;
 use serde::Deserialize;
 use serde::Deserializer;
 
+use super::conversions::data_value_display;
 use super::error::LayoutError;
+use super::value::DataValue;
 
 /// Scalar type enum derived from 'type' string in leaf entries.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -80,6 +82,20 @@ impl ScalarType {
         )
     }
 
+    /// Returns the smallest and largest values of an integer type.
+    pub fn integer_bounds(&self) -> Option<(i128, i128)> {
+        let bits = self.size_bytes() * 8;
+        match self {
+            ScalarType::U8 | ScalarType::U16 | ScalarType::U32 | ScalarType::U64 => {
+                Some((0, (1i128 << bits) - 1))
+            }
+            ScalarType::I8 | ScalarType::I16 | ScalarType::I32 | ScalarType::I64 => {
+                Some((-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1))
+            }
+            ScalarType::F32 | ScalarType::F64 | ScalarType::Fixed(_) => None,
+        }
+    }
+
     /// Returns true if this is a signed type.
     pub fn is_signed(&self) -> bool {
         matches!(
@@ -213,6 +229,85 @@ fn parse_fixed_point_type(value: &str) -> Result<FixedPointType, String> {
     })
 }
 
+/// Linear scaling of an integer field, `physical = raw * factor + offset`, so layouts and data
+/// sources give physical values.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LinearScale {
+    pub factor: f64,
+    #[serde(default)]
+    pub offset: f64,
+}
+
+impl LinearScale {
+    pub(crate) fn validate(&self, scalar_type: ScalarType) -> Result<(), String> {
+        if !scalar_type.is_integer() {
+            return Err(format!(
+                "'scale' requires an integer type, got {scalar_type}"
+            ));
+        }
+        if !self.factor.is_finite() || self.factor == 0.0 {
+            return Err(format!(
+                "'scale' factor must be a finite, non-zero number, got {}",
+                self.factor
+            ));
+        }
+        if !self.offset.is_finite() {
+            return Err(format!(
+                "'scale' offset must be a finite number, got {}",
+                self.offset
+            ));
+        }
+        Ok(())
+    }
+
+    /// Converts a physical value to the raw integer `(value - offset) / factor`, rounded half to
+    /// even. A raw value outside the range of `scalar_type` is an error in strict mode and is
+    /// clamped otherwise.
+    pub(crate) fn raw_value(
+        &self,
+        value: &DataValue,
+        scalar_type: ScalarType,
+        strict: bool,
+    ) -> Result<DataValue, LayoutError> {
+        let physical = match *value {
+            DataValue::Bool(value) => f64::from(u8::from(value)),
+            DataValue::U64(value) => value as f64,
+            DataValue::I64(value) => value as f64,
+            DataValue::F64(value) => value,
+            DataValue::Str(_) => {
+                return Err(LayoutError::DataValueExportFailed(
+                    "Cannot convert string to scalar type.".to_owned(),
+                ));
+            }
+        };
+        let raw = ((physical - self.offset) / self.factor).round_ties_even();
+        if !raw.is_finite() {
+            return Err(LayoutError::DataValueExportFailed(format!(
+                "value {} does not scale to a finite raw value",
+                data_value_display(value)
+            )));
+        }
+        let Some((min, max)) = scalar_type.integer_bounds() else {
+            unreachable!("scale validated on integer types")
+        };
+        // `max as f64` rounds up to a power of two for 64-bit types, so compare against the
+        // exclusive upper bound, which is exact.
+        if strict && (raw < min as f64 || raw >= (max + 1) as f64) {
+            return Err(LayoutError::DataValueExportFailed(format!(
+                "value {} scales to raw value {raw}, outside the {scalar_type} range {min}..={max}",
+                data_value_display(value)
+            )));
+        }
+        let raw = raw.clamp(min as f64, max as f64);
+        Ok(if raw < 0.0 {
+            DataValue::I64(raw as i64)
+        } else {
+            DataValue::U64(raw as u64)
+        })
+    }
+}
+
 pub fn fixed_point_unsupported_error(kind: &str, scalar_type: ScalarType) -> LayoutError {
     LayoutError::InvalidLayout(format!(
         "{kind} does not support fixed-point storage type '{}'.",
@@ -1,8 +1,14 @@
-use super::abi::Abi;
+use super::abi::{Abi, Endianness};
 use super::error::LayoutError;
+use super::types::{EnumType, StructType};
 use super::value::ValueSource;
+use crate::extensions::IntegrityAlgorithm;
+use crate::output::checksum;
+use indexmap::IndexMap;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Top-level `[mint]` configuration section.
 #[derive(Debug, Deserialize)]
@@ -11,8 +17,24 @@ pub struct MintConfig {
     pub abi: Abi,
     #[serde(default)]
     pub checksum: HashMap<String, ChecksumConfig>,
+    #[serde(default)]
+    pub signature: HashMap<String, SignatureConfig>,
+    #[serde(default)]
+    pub hmac: HashMap<String, HmacConfig>,
+    #[serde(default)]
+    pub encryption: HashMap<String, EncryptionConfig>,
     #[serde(rename = "const", default)]
     pub consts: HashMap<String, ValueSource>,
+    /// Struct types referenced by `struct = "<name>"` fields, in declaration order.
+    #[serde(default, deserialize_with = "super::types::deserialize_types")]
+    pub types: IndexMap<String, Arc<StructType>>,
+    /// Symbolic values for leaves with `enum = "<name>"`.
+    #[serde(
+        rename = "enum",
+        default,
+        deserialize_with = "super::types::deserialize_enums"
+    )]
+    pub enums: IndexMap<String, EnumType>,
 }
 
 impl MintConfig {
@@ -29,16 +51,556 @@ impl MintConfig {
             ))
         })
     }
+
+    pub(crate) fn signature_config(&self, name: &str) -> Result<&SignatureConfig, LayoutError> {
+        self.signature.get(name).ok_or_else(|| {
+            let available = self
+                .signature
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ");
+            LayoutError::DataValueExportFailed(format!(
+                "Signature config '{name}' not found in [mint.signature]. Available: [{available}]"
+            ))
+        })
+    }
+
+    pub(crate) fn hmac_config(&self, name: &str) -> Result<&HmacConfig, LayoutError> {
+        self.hmac.get(name).ok_or_else(|| {
+            let available = self.hmac.keys().cloned().collect::<Vec<_>>().join(", ");
+            LayoutError::DataValueExportFailed(format!(
+                "HMAC config '{name}' not found in [mint.hmac]. Available: [{available}]"
+            ))
+        })
+    }
+
+    pub(crate) fn encryption_config(&self, name: &str) -> Result<&EncryptionConfig, LayoutError> {
+        self.encryption.get(name).ok_or_else(|| {
+            let available = self
+                .encryption
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ");
+            LayoutError::DataValueExportFailed(format!(
+                "Encryption config '{name}' not found in [mint.encryption]. Available: [{available}]"
+            ))
+        })
+    }
 }
 
-/// Named checksum algorithm configuration, referenced by leaf entries via `checksum = "name"`.
-/// All fields are required — no inheritance or merging.
-#[derive(Debug, Deserialize, Clone)]
+/// How the secret of an HMAC or encryption key is written, after a key file's final line break
+/// is dropped.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyEncoding {
+    /// The bytes are the key.
+    #[default]
+    Raw,
+    /// Two hex digits per key byte.
+    Hex,
+}
+
+/// Named HMAC-SHA256 key location, referenced by `hmac` leaves. The key itself is never part of
+/// the layout.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(try_from = "RawHmacConfig")]
+pub struct HmacConfig {
+    /// Secret key file. Relative paths resolve from the layout file's directory.
+    pub key_file: Option<PathBuf>,
+    /// Environment variable whose value is the secret key.
+    pub key_env: Option<String>,
+    pub key_encoding: KeyEncoding,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawHmacConfig {
+    key_file: Option<PathBuf>,
+    key_env: Option<String>,
+    #[serde(default)]
+    key_encoding: KeyEncoding,
+}
+
+impl TryFrom<RawHmacConfig> for HmacConfig {
+    type Error = String;
+
+    fn try_from(raw: RawHmacConfig) -> Result<Self, Self::Error> {
+        if raw.key_file.is_some() && raw.key_env.is_some() {
+            return Err("HMAC config may set only one of 'key_file' and 'key_env'".to_owned());
+        }
+        Ok(Self {
+            key_file: raw.key_file,
+            key_env: raw.key_env,
+            key_encoding: raw.key_encoding,
+        })
+    }
+}
+
+/// Named block encryption configuration, referenced by the block header `encryption` key and the
+/// `encryption_nonce`/`encryption_tag` leaves. Like HMAC keys, the secret never lives in the layout.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(try_from = "RawEncryptionConfig")]
+pub struct EncryptionConfig {
+    pub algorithm: EncryptionAlgorithm,
+    /// Secret key file. Relative paths resolve from the layout file's directory.
+    pub key_file: Option<PathBuf>,
+    /// Environment variable whose value is the secret key.
+    pub key_env: Option<String>,
+    pub key_encoding: KeyEncoding,
+}
+
+#[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
+struct RawEncryptionConfig {
+    algorithm: EncryptionAlgorithm,
+    key_file: Option<PathBuf>,
+    key_env: Option<String>,
+    #[serde(default)]
+    key_encoding: KeyEncoding,
+}
+
+impl TryFrom<RawEncryptionConfig> for EncryptionConfig {
+    type Error = String;
+
+    fn try_from(raw: RawEncryptionConfig) -> Result<Self, Self::Error> {
+        if raw.key_file.is_some() && raw.key_env.is_some() {
+            return Err(
+                "encryption config may set only one of 'key_file' and 'key_env'".to_owned(),
+            );
+        }
+        Ok(Self {
+            algorithm: raw.algorithm,
+            key_file: raw.key_file,
+            key_env: raw.key_env,
+            key_encoding: raw.key_encoding,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum EncryptionAlgorithm {
+    #[serde(rename = "aes-128-ctr")]
+    Aes128Ctr,
+    #[serde(rename = "aes-256-ctr")]
+    Aes256Ctr,
+    #[serde(rename = "aes-128-gcm")]
+    Aes128Gcm,
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm,
+}
+
+impl EncryptionAlgorithm {
+    pub fn key_len(self) -> usize {
+        match self {
+            Self::Aes128Ctr | Self::Aes128Gcm => 16,
+            Self::Aes256Ctr | Self::Aes256Gcm => 32,
+        }
+    }
+
+    /// CTR modes take the full 128-bit initial counter block; GCM takes the standard 96-bit IV.
+    pub fn nonce_len(self) -> usize {
+        match self {
+            Self::Aes128Ctr | Self::Aes256Ctr => 16,
+            Self::Aes128Gcm | Self::Aes256Gcm => 12,
+        }
+    }
+
+    /// Authentication tag length; CTR modes are unauthenticated.
+    pub fn tag_len(self) -> usize {
+        match self {
+            Self::Aes128Ctr | Self::Aes256Ctr => 0,
+            Self::Aes128Gcm | Self::Aes256Gcm => 16,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Aes128Ctr => "aes-128-ctr",
+            Self::Aes256Ctr => "aes-256-ctr",
+            Self::Aes128Gcm => "aes-128-gcm",
+            Self::Aes256Gcm => "aes-256-gcm",
+        }
+    }
+}
+
+/// Named signing configuration, referenced by `signature` and `public_key_hash` leaves and the
+/// block header `signature` trailer.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SignatureConfig {
+    pub algorithm: SignatureAlgorithm,
+    /// PKCS#8 (or SEC1 for P-256) private key in PEM or DER form. Relative paths resolve from
+    /// the layout file's directory; a build-time key for the same name takes precedence.
+    pub key_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureAlgorithm {
+    Ed25519,
+    EcdsaP256,
+}
+
+impl SignatureAlgorithm {
+    /// Signature length in bytes; ECDSA signatures are stored as fixed-width `r || s`.
+    pub fn signature_len(self) -> usize {
+        64
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ed25519 => "ed25519",
+            Self::EcdsaP256 => "ecdsa-p256",
+        }
+    }
+}
+
+/// Named checksum configuration, referenced by leaf entries via `checksum = "name"`.
+/// Either names an algorithm with `algorithm` or states every CRC parameter explicitly — no
+/// inheritance or merging.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(try_from = "RawChecksumConfig")]
 pub struct ChecksumConfig {
+    pub algorithm: ChecksumAlgorithm,
+    /// Byte order of the stored value; the ABI byte order when unset.
+    pub byte_order: Option<Endianness>,
+    /// How the device reads the covered bytes.
+    pub input: ChecksumInput,
+}
+
+impl ChecksumConfig {
+    /// Byte order the checksum value is stored in under `abi`.
+    pub fn stored_byte_order(&self, abi: Abi) -> Endianness {
+        self.byte_order.unwrap_or(abi.endianness())
+    }
+
+    /// Size of the words whose octets must be reversed before feeding the algorithm under `abi`,
+    /// or `None` when the covered octets are fed as they are laid out.
+    pub fn swapped_word_octets(&self, abi: Abi) -> Option<usize> {
+        let octets = usize::from(self.input.word_bits / 8);
+        (octets > 1 && self.input.word_order != abi.endianness()).then_some(octets)
+    }
+}
+
+/// Word width and order in which a device feeds memory to its checksum.
+///
+/// Covered bytes are read as `word_bits`-wide words in the ABI byte order, then fed to the
+/// algorithm one octet at a time in `word_order`. The default, 8-bit words, feeds octets in
+/// address order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChecksumInput {
+    pub word_bits: u8,
+    pub word_order: Endianness,
+}
+
+impl Default for ChecksumInput {
+    fn default() -> Self {
+        Self {
+            word_bits: 8,
+            word_order: Endianness::Big,
+        }
+    }
+}
+
+/// Checksum algorithm and its parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    Crc(CrcConfig),
+    /// Wrapping byte sum truncated to `width` bits, optionally complemented.
+    Sum {
+        width: u8,
+        complement: SumComplement,
+    },
+    Fletcher16,
+    Fletcher32,
+    Adler32,
+    /// XOR of every byte.
+    Xor8,
+    /// Algorithm registered on the build request.
+    Custom(CustomChecksum),
+}
+
+impl ChecksumAlgorithm {
+    /// Width of the checksum value in bits, which the storage type must match, or `None` for a
+    /// custom algorithm that is not bound yet.
+    pub fn width(&self) -> Option<u8> {
+        match self {
+            Self::Custom(custom) => custom.algorithm().map(|algorithm| algorithm.width()),
+            Self::Crc(config) => Some(config.width),
+            Self::Sum { width, .. } => Some(*width),
+            Self::Fletcher16 => Some(16),
+            Self::Fletcher32 | Self::Adler32 => Some(32),
+            Self::Xor8 => Some(8),
+        }
+    }
+}
+
+/// `custom = "<name>"` checksum, bound to its implementation by
+/// [`Extensions::bind`](crate::extensions::Extensions::bind). Computing an unbound algorithm
+/// panics; builds reject fields that use one first.
+#[derive(Clone)]
+pub struct CustomChecksum {
+    pub name: String,
+    algorithm: Option<Arc<dyn IntegrityAlgorithm>>,
+}
+
+impl CustomChecksum {
+    pub fn algorithm(&self) -> Option<&Arc<dyn IntegrityAlgorithm>> {
+        self.algorithm.as_ref()
+    }
+
+    pub(crate) fn bind(&mut self, algorithm: Arc<dyn IntegrityAlgorithm>) {
+        self.algorithm = Some(algorithm);
+    }
+}
+
+impl std::fmt::Debug for CustomChecksum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomChecksum")
+            .field("name", &self.name)
+            .field("bound", &self.algorithm.is_some())
+            .finish()
+    }
+}
+
+impl PartialEq for CustomChecksum {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for CustomChecksum {}
+
+/// CRC parameters in the Rocksoft model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrcConfig {
+    /// CRC register width in bits; 8, 16 or 32.
+    pub width: u8,
     pub polynomial: u32,
     pub start: u32,
     pub xor_out: u32,
     pub ref_in: bool,
     pub ref_out: bool,
 }
+
+/// Final transform applied to an additive sum.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SumComplement {
+    /// Store the sum as-is.
+    #[default]
+    None,
+    /// Store the bitwise inverse of the sum.
+    Ones,
+    /// Store the negated sum, so the covered bytes plus the checksum sum to zero.
+    Twos,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawChecksumConfig {
+    algorithm: Option<String>,
+    custom: Option<String>,
+    byte_order: Option<ByteOrder>,
+    input_word_bits: Option<u8>,
+    input_word_order: Option<ByteOrder>,
+    complement: Option<SumComplement>,
+    width: Option<u8>,
+    polynomial: Option<u32>,
+    start: Option<u32>,
+    xor_out: Option<u32>,
+    ref_in: Option<bool>,
+    ref_out: Option<bool>,
+}
+
+/// `"little"` or `"big"` in checksum configs.
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ByteOrder {
+    Little,
+    Big,
+}
+
+impl From<ByteOrder> for Endianness {
+    fn from(order: ByteOrder) -> Self {
+        match order {
+            ByteOrder::Little => Self::Little,
+            ByteOrder::Big => Self::Big,
+        }
+    }
+}
+
+impl TryFrom<RawChecksumConfig> for ChecksumConfig {
+    type Error = String;
+
+    fn try_from(mut raw: RawChecksumConfig) -> Result<Self, Self::Error> {
+        let byte_order = raw.byte_order.take().map(Endianness::from);
+        let mut input = ChecksumInput::default();
+        if let Some(bits) = raw.input_word_bits.take() {
+            if !matches!(bits, 8 | 16 | 32) {
+                return Err(format!(
+                    "unsupported checksum input_word_bits {bits}; must be 8, 16, or 32"
+                ));
+            }
+            input.word_bits = bits;
+        }
+        if let Some(order) = raw.input_word_order.take() {
+            if input.word_bits == 8 {
+                return Err(
+                    "checksum 'input_word_order' requires 'input_word_bits' of 16 or 32".to_owned(),
+                );
+            }
+            input.word_order = order.into();
+        }
+        Ok(Self {
+            algorithm: ChecksumAlgorithm::try_from(raw)?,
+            byte_order,
+            input,
+        })
+    }
+}
+
+impl TryFrom<RawChecksumConfig> for ChecksumAlgorithm {
+    type Error = String;
+
+    fn try_from(raw: RawChecksumConfig) -> Result<Self, Self::Error> {
+        let explicit = [
+            ("width", raw.width.is_some()),
+            ("polynomial", raw.polynomial.is_some()),
+            ("start", raw.start.is_some()),
+            ("xor_out", raw.xor_out.is_some()),
+            ("ref_in", raw.ref_in.is_some()),
+            ("ref_out", raw.ref_out.is_some()),
+        ];
+
+        if let Some(name) = raw.custom {
+            let present = [("algorithm", raw.algorithm.is_some())]
+                .iter()
+                .chain(&explicit)
+                .chain(&[("complement", raw.complement.is_some())])
+                .filter(|(_, present)| *present)
+                .map(|(key, _)| format!("'{key}'"))
+                .collect::<Vec<_>>();
+            if !present.is_empty() {
+                return Err(format!(
+                    "checksum 'custom' cannot be combined with other algorithm keys; found {}",
+                    present.join(", ")
+                ));
+            }
+            return Ok(Self::Custom(CustomChecksum {
+                name,
+                algorithm: None,
+            }));
+        }
+
+        if let Some(algorithm) = raw.algorithm {
+            let present = explicit
+                .iter()
+                .filter(|(_, present)| *present)
+                .map(|(key, _)| format!("'{key}'"))
+                .collect::<Vec<_>>();
+            if !present.is_empty() {
+                return Err(format!(
+                    "checksum 'algorithm' cannot be combined with explicit CRC parameters; found {}",
+                    present.join(", ")
+                ));
+            }
+            let config = checksum::algorithm(&algorithm).ok_or_else(|| {
+                format!(
+                    "unknown checksum algorithm '{algorithm}'; supported algorithms are {}",
+                    checksum::algorithm_names().collect::<Vec<_>>().join(", ")
+                )
+            })?;
+            return match (config, raw.complement) {
+                (Self::Sum { width, .. }, Some(complement)) => Ok(Self::Sum { width, complement }),
+                (_, Some(_)) => Err(format!(
+                    "checksum 'complement' is only valid with SUM algorithms, not '{algorithm}'"
+                )),
+                (config, None) => Ok(config),
+            };
+        }
+
+        if raw.complement.is_some() {
+            return Err(
+                "checksum 'complement' is only valid with SUM algorithms selected by 'algorithm'"
+                    .to_owned(),
+            );
+        }
+
+        let (Some(polynomial), Some(start), Some(xor_out), Some(ref_in), Some(ref_out)) = (
+            raw.polynomial,
+            raw.start,
+            raw.xor_out,
+            raw.ref_in,
+            raw.ref_out,
+        ) else {
+            let missing = explicit
+                .iter()
+                .skip(1)
+                .filter(|(_, present)| !*present)
+                .map(|(key, _)| format!("'{key}'"))
+                .collect::<Vec<_>>();
+            return Err(format!(
+                "checksum config requires either 'algorithm' or every CRC parameter; missing {}",
+                missing.join(", ")
+            ));
+        };
+
+        let width = raw.width.unwrap_or(32);
+        if !matches!(width, 8 | 16 | 32) {
+            return Err(format!(
+                "unsupported CRC width {width}; width must be 8, 16, or 32 bits"
+            ));
+        }
+        let mask = u32::MAX >> (32 - u32::from(width));
+        for (key, value) in [
+            ("polynomial", polynomial),
+            ("start", start),
+            ("xor_out", xor_out),
+        ] {
+            if value & !mask != 0 {
+                return Err(format!(
+                    "CRC {key} 0x{value:X} does not fit the configured {width}-bit width"
+                ));
+            }
+        }
+
+        Ok(Self::Crc(CrcConfig {
+            width,
+            polynomial,
+            start,
+            xor_out,
+            ref_in,
+            ref_out,
+        }))
+    }
+}
+
+/// Cryptographic hash stored in a `u8` array of the digest's length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestAlgorithm {
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+impl DigestAlgorithm {
+    /// Digest length in bytes.
+    pub fn output_len(self) -> usize {
+        match self {
+            Self::Sha256 | Self::Blake3 => 32,
+            Self::Sha512 => 64,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha512 => "sha512",
+            Self::Blake3 => "blake3",
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use super::block::{Block, Entry};
+use super::conversions::data_value_display;
+use super::error::LayoutError;
+use super::value::DataValue;
+use indexmap::IndexMap;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::sync::Arc;
+
+/// Struct type defined once in `[mint.types.<name>]` and shared by every field that names it.
+#[derive(Debug)]
+pub struct StructType {
+    pub name: String,
+    pub fields: IndexMap<String, Entry>,
+    /// Lays the fields out without alignment padding, like a C packed struct.
+    pub packed: bool,
+}
+
+/// Field declared with `struct = "<type>"`, bound to its [`StructType`] when the layout is
+/// parsed.
+#[derive(Debug, Clone)]
+pub struct StructEntry {
+    pub type_name: String,
+    /// Data-source prefix; when set, the type's leaves read `<name>.<leaf name>`.
+    pub name: Option<String>,
+    /// Explicit offset within the enclosing struct.
+    pub offset: Option<usize>,
+    struct_type: Option<Arc<StructType>>,
+}
+
+impl StructEntry {
+    pub(crate) fn new(type_name: String, name: Option<String>) -> Self {
+        Self {
+            type_name,
+            name,
+            offset: None,
+            struct_type: None,
+        }
+    }
+
+    /// Returns the bound type; `None` only for entries deserialized outside a layout.
+    pub fn struct_type(&self) -> Option<&Arc<StructType>> {
+        self.struct_type.as_ref()
+    }
+}
+
+/// Deserializes `[mint.types]`, binding types that reference each other and rejecting cycles.
+pub(crate) fn deserialize_types<'de, D>(
+    deserializer: D,
+) -> Result<IndexMap<String, Arc<StructType>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = IndexMap::<String, Entry>::deserialize(deserializer)?;
+    let order = raw.keys().cloned().collect::<Vec<_>>();
+    let mut pending = IndexMap::with_capacity(raw.len());
+    for (name, entry) in raw {
+        super::validate_c_identifier(&name, "type").map_err(D::Error::custom)?;
+        let Entry::Branch(branch) = entry else {
+            return Err(D::Error::custom(format!(
+                "type '{name}' must be a table of fields; set 'size' or 'struct' where the type is used"
+            )));
+        };
+        if branch.offset.is_some() {
+            return Err(D::Error::custom(format!(
+                "type '{name}' cannot set 'offset'; set it where the type is used"
+            )));
+        }
+        pending.insert(name, (branch.fields, branch.packed));
+    }
+
+    let mut bound = IndexMap::with_capacity(pending.len());
+    for name in &order {
+        bind_type(name, &mut pending, &mut bound, &mut Vec::new()).map_err(D::Error::custom)?;
+    }
+    bound.sort_by_cached_key(|name, _| order.iter().position(|declared| declared == name));
+    Ok(bound)
+}
+
+fn bind_type(
+    name: &str,
+    pending: &mut IndexMap<String, (IndexMap<String, Entry>, bool)>,
+    bound: &mut IndexMap<String, Arc<StructType>>,
+    stack: &mut Vec<String>,
+) -> Result<Arc<StructType>, String> {
+    if let Some(struct_type) = bound.get(name) {
+        return Ok(Arc::clone(struct_type));
+    }
+    if stack.iter().any(|open| open == name) {
+        return Err(format!(
+            "struct type cycle: {} -> {name}",
+            stack.join(" -> ")
+        ));
+    }
+    let Some((mut fields, packed)) = pending.shift_remove(name) else {
+        return Err(missing_type(name, pending.keys().chain(bound.keys())));
+    };
+
+    stack.push(name.to_owned());
+    for entry in fields.values_mut() {
+        bind_entry(entry, &mut |type_name| {
+            bind_type(type_name, pending, bound, stack)
+        })?;
+    }
+    stack.pop();
+
+    let struct_type = Arc::new(StructType {
+        name: name.to_owned(),
+        fields,
+        packed,
+    });
+    bound.insert(name.to_owned(), Arc::clone(&struct_type));
+    Ok(struct_type)
+}
+
+/// Binds every `struct = "<type>"` field of `block` to `types`.
+pub(crate) fn bind_block(
+    block: &mut Block,
+    types: &IndexMap<String, Arc<StructType>>,
+) -> Result<(), String> {
+    bind_entry(&mut block.data, &mut |name| {
+        types
+            .get(name)
+            .cloned()
+            .ok_or_else(|| missing_type(name, types.keys()))
+    })
+}
+
+fn bind_entry(
+    entry: &mut Entry,
+    resolve: &mut dyn FnMut(&str) -> Result<Arc<StructType>, String>,
+) -> Result<(), String> {
+    match entry {
+        Entry::Leaf(_) => Ok(()),
+        Entry::Branch(branch) => branch
+            .fields
+            .values_mut()
+            .try_for_each(|child| bind_entry(child, resolve)),
+        Entry::Array(array) => bind_entry(&mut array.element, resolve),
+        Entry::Struct(field) => {
+            field.struct_type = Some(resolve(&field.type_name)?);
+            Ok(())
+        }
+    }
+}
+
+fn missing_type<'a>(name: &str, available: impl Iterator<Item = &'a String>) -> String {
+    format!(
+        "struct type '{name}' not found in [mint.types]. Available: [{}]",
+        available.cloned().collect::<Vec<_>>().join(", ")
+    )
+}
+
+/// Symbolic values defined in `[mint.enum.<name>]` for leaves with `enum = "<name>"`.
+#[derive(Debug)]
+pub struct EnumType {
+    pub name: String,
+    /// Symbols and their integer values, in declaration order; values are unique.
+    pub symbols: IndexMap<String, i64>,
+}
+
+impl EnumType {
+    /// Resolves a symbol or one of the enum's integer values to its symbol and value.
+    pub(crate) fn resolve(&self, value: &DataValue) -> Result<(&str, i64), LayoutError> {
+        let number = match value {
+            DataValue::Str(symbol) => {
+                return self
+                    .symbols
+                    .get_key_value(symbol.as_str())
+                    .map(|(symbol, number)| (symbol.as_str(), *number))
+                    .ok_or_else(|| {
+                        LayoutError::DataValueExportFailed(format!(
+                            "'{symbol}' is not a symbol of enum '{}'. Symbols: [{}]",
+                            self.name,
+                            self.symbols.keys().cloned().collect::<Vec<_>>().join(", ")
+                        ))
+                    });
+            }
+            DataValue::U64(number) => i64::try_from(*number).ok(),
+            DataValue::I64(number) => Some(*number),
+            // Spreadsheet cells hold numbers as floats.
+            DataValue::F64(number) if number.fract() == 0.0 && number.abs() < 2f64.powi(63) => {
+                Some(*number as i64)
+            }
+            DataValue::F64(_) | DataValue::Bool(_) => None,
+        };
+        number
+            .and_then(|number| {
+                self.symbols
+                    .iter()
+                    .find(|(_, value)| **value == number)
+                    .map(|(symbol, value)| (symbol.as_str(), *value))
+            })
+            .ok_or_else(|| {
+                LayoutError::DataValueExportFailed(format!(
+                    "{} is not a value of enum '{}'",
+                    data_value_display(value),
+                    self.name
+                ))
+            })
+    }
+}
+
+/// Looks up the enum named by a leaf's `enum` key.
+pub(crate) fn enum_type<'a>(
+    enums: &'a IndexMap<String, EnumType>,
+    name: &str,
+) -> Result<&'a EnumType, LayoutError> {
+    enums.get(name).ok_or_else(|| {
+        LayoutError::InvalidLayout(format!(
+            "Enum '{name}' not found in [mint.enum]. Available: [{}]",
+            enums.keys().cloned().collect::<Vec<_>>().join(", ")
+        ))
+    })
+}
+
+/// Deserializes `[mint.enum]`, rejecting empty enums and values shared by two symbols.
+pub(crate) fn deserialize_enums<'de, D>(
+    deserializer: D,
+) -> Result<IndexMap<String, EnumType>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = IndexMap::<String, IndexMap<String, i64>>::deserialize(deserializer)?;
+    let mut enums = IndexMap::with_capacity(raw.len());
+    for (name, symbols) in raw {
+        super::validate_c_identifier(&name, "enum").map_err(D::Error::custom)?;
+        if symbols.is_empty() {
+            return Err(D::Error::custom(format!(
+                "enum '{name}' must define at least one symbol"
+            )));
+        }
+        for (index, (symbol, value)) in symbols.iter().enumerate() {
+            if let Some((other, _)) = symbols[..index].iter().find(|(_, other)| *other == value) {
+                return Err(D::Error::custom(format!(
+                    "enum '{name}' maps both '{other}' and '{symbol}' to {value}"
+                )));
+            }
+        }
+        enums.insert(name.clone(), EnumType { name, symbols });
+    }
+    Ok(enums)
+}
@@ -56,6 +56,11 @@ pub fn data_value_to_json(value: &DataValue) -> Result<Value, LayoutError> {
     }
 }
 
+/// Renders computed byte strings such as digests and signatures as lowercase hex.
+pub(crate) fn bytes_to_hex_json(bytes: &[u8]) -> Value {
+    Value::String(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
 pub fn i128_to_json(value: i128) -> Result<Value, LayoutError> {
     if value >= 0 {
         let unsigned = u64::try_from(value).map_err(|_| {
@@ -97,27 +102,66 @@ fn insert_value(
         ));
     }
 
-    let key = &path[0];
-    if path.len() == 1 {
+    // Array-of-struct elements arrive as `name[i]` segments, in element order.
+    let (key, indices) = split_indices(&path[0]);
+    if indices.is_empty() && path.len() == 1 {
         if root.contains_key(key) {
             return Err(LayoutError::DataValueExportFailed(format!(
                 "Duplicate value path '{}'.",
                 path.join(".")
             )));
         }
-        root.insert(key.clone(), value);
+        root.insert(key.to_owned(), value);
         return Ok(());
     }
 
-    let entry = root
-        .entry(key.clone())
-        .or_insert_with(|| Value::Object(Map::new()));
+    let collision = || {
+        LayoutError::DataValueExportFailed(format!(
+            "Path '{}' collides with existing value.",
+            path.join(".")
+        ))
+    };
+    let mut entry = root.entry(key.to_owned()).or_insert(Value::Null);
+    for index in indices {
+        if entry.is_null() {
+            *entry = Value::Array(Vec::new());
+        }
+        let Value::Array(items) = entry else {
+            return Err(collision());
+        };
+        if index == items.len() {
+            items.push(Value::Null);
+        }
+        entry = items.get_mut(index).ok_or_else(collision)?;
+    }
 
+    if path.len() == 1 {
+        if !entry.is_null() {
+            return Err(LayoutError::DataValueExportFailed(format!(
+                "Duplicate value path '{}'.",
+                path.join(".")
+            )));
+        }
+        *entry = value;
+        return Ok(());
+    }
+    if entry.is_null() {
+        *entry = Value::Object(Map::new());
+    }
     match entry {
         Value::Object(child) => insert_value(child, &path[1..], value),
-        _ => Err(LayoutError::DataValueExportFailed(format!(
-            "Path '{}' collides with existing value.",
-            path.join(".")
-        ))),
+        _ => Err(collision()),
     }
 }
+
+/// Splits a path segment such as `ch[1][2]` into its name and element indices.
+fn split_indices(segment: &str) -> (&str, Vec<usize>) {
+    let Some(open) = segment.find('[') else {
+        return (segment, Vec::new());
+    };
+    let indices = segment[open..]
+        .split(']')
+        .filter_map(|part| part.strip_prefix('[')?.parse().ok())
+        .collect();
+    (&segment[..open], indices)
+}
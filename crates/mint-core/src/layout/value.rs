@@ -54,3 +54,32 @@ impl DataValue {
         }
     }
 }
+
+/// Shape of a lookup table read from a data source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableKind {
+    /// Values over one axis, `x`.
+    Curve,
+    /// Values over two axes: `x` across the columns and `y` down the rows.
+    Map,
+}
+
+impl TableKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            TableKind::Curve => "curve",
+            TableKind::Map => "map",
+        }
+    }
+}
+
+/// Axis breakpoints and values of a curve or map, as read from a data source. An axis the
+/// source does not hold is empty.
+#[derive(Debug, Clone, Default)]
+pub struct LookupTable {
+    pub x: Vec<DataValue>,
+    /// Row breakpoints of a map; always empty for a curve.
+    pub y: Vec<DataValue>,
+    /// One row per `y` breakpoint, or a single row for a curve.
+    pub values: Vec<Vec<DataValue>>,
+}
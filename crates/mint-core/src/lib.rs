@@ -12,7 +12,9 @@
 pub mod build;
 pub mod data;
 pub mod error;
+pub mod extensions;
 pub mod fingerprint;
 pub mod header;
+pub mod keys;
 pub mod layout;
 pub mod output;
@@ -1,64 +1,613 @@
-use crate::layout::settings::ChecksumConfig;
-
-/// Computes CRC-32 with configurable polynomial, initial value, reflection, and XOR-out.
-pub fn calculate_crc(data: &[u8], crc_settings: &ChecksumConfig) -> u32 {
-    let polynomial = crc_settings.polynomial;
-    let start = crc_settings.start;
-    let xor_out = crc_settings.xor_out;
-    let ref_in = crc_settings.ref_in;
-    let ref_out = crc_settings.ref_out;
-
-    // Initialize CRC based on ref_in
-    let mut crc = if ref_in { start.reverse_bits() } else { start };
-
-    // Prepare polynomial
-    let poly = if ref_in {
-        polynomial.reverse_bits()
-    } else {
-        polynomial
+use crate::layout::settings::{ChecksumAlgorithm, CrcConfig, CustomChecksum, SumComplement};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// Named CRC parameter set from the reveng catalogue of parametrised CRC algorithms.
+#[derive(Debug, Clone, Copy)]
+pub struct CrcPreset {
+    /// Canonical catalogue name.
+    pub name: &'static str,
+    /// Alternative names accepted for the same parameters.
+    pub aliases: &'static [&'static str],
+    pub width: u8,
+    pub polynomial: u32,
+    pub start: u32,
+    pub xor_out: u32,
+    pub ref_in: bool,
+    pub ref_out: bool,
+    /// Published CRC of the ASCII string `123456789`.
+    pub check: u32,
+}
+
+impl CrcPreset {
+    pub fn config(&self) -> CrcConfig {
+        CrcConfig {
+            width: self.width,
+            polynomial: self.polynomial,
+            start: self.start,
+            xor_out: self.xor_out,
+            ref_in: self.ref_in,
+            ref_out: self.ref_out,
+        }
+    }
+}
+
+macro_rules! crc_preset {
+    ($name:literal, [$($alias:literal),* $(,)?], $width:literal, $poly:literal, $start:literal, $ref_in:literal, $ref_out:literal, $xor_out:literal, $check:literal) => {
+        CrcPreset {
+            name: $name,
+            aliases: &[$($alias),*],
+            width: $width,
+            polynomial: $poly,
+            start: $start,
+            xor_out: $xor_out,
+            ref_in: $ref_in,
+            ref_out: $ref_out,
+            check: $check,
+        }
     };
+}
 
-    // Process each byte
-    for &byte in data {
-        let idx = if ref_in {
-            (crc ^ (byte as u32)) & 0xFF
-        } else {
-            ((crc >> 24) ^ (byte as u32)) & 0xFF
+/// Supported catalogue entries, selected with `algorithm = "<name>"`.
+///
+/// Columns: name, aliases, width, polynomial, init, refin, refout, xorout, check.
+#[rustfmt::skip]
+pub const CRC_PRESETS: &[CrcPreset] = &[
+    crc_preset!("CRC-8/SMBUS", ["CRC-8"], 8, 0x07, 0x00, false, false, 0x00, 0xF4),
+    crc_preset!("CRC-8/AUTOSAR", [], 8, 0x2F, 0xFF, false, false, 0xFF, 0xDF),
+    crc_preset!("CRC-8/SAE-J1850", [], 8, 0x1D, 0xFF, false, false, 0xFF, 0x4B),
+    crc_preset!("CRC-8/MAXIM-DOW", ["CRC-8/MAXIM", "DOW-CRC"], 8, 0x31, 0x00, true, true, 0x00, 0xA1),
+    crc_preset!("CRC-16/ARC", ["ARC", "CRC-16", "CRC-16/LHA", "CRC-IBM"], 16, 0x8005, 0x0000, true, true, 0x0000, 0xBB3D),
+    crc_preset!("CRC-16/IBM-3740", ["CRC-16/AUTOSAR", "CRC-16/CCITT-FALSE"], 16, 0x1021, 0xFFFF, false, false, 0x0000, 0x29B1),
+    crc_preset!("CRC-16/IBM-SDLC", ["CRC-16/ISO-HDLC", "CRC-16/X-25", "X-25"], 16, 0x1021, 0xFFFF, true, true, 0xFFFF, 0x906E),
+    crc_preset!("CRC-16/KERMIT", ["CRC-16/CCITT", "CRC-16/CCITT-TRUE", "KERMIT"], 16, 0x1021, 0x0000, true, true, 0x0000, 0x2189),
+    crc_preset!("CRC-16/MODBUS", ["MODBUS"], 16, 0x8005, 0xFFFF, true, true, 0x0000, 0x4B37),
+    crc_preset!("CRC-16/XMODEM", ["CRC-16/ACORN", "CRC-16/LTE", "XMODEM", "ZMODEM"], 16, 0x1021, 0x0000, false, false, 0x0000, 0x31C3),
+    crc_preset!("CRC-32/AUTOSAR", [], 32, 0xF4AC_FB13, 0xFFFF_FFFF, true, true, 0xFFFF_FFFF, 0x1697_D06A),
+    crc_preset!("CRC-32/BZIP2", ["CRC-32/AAL5", "CRC-32/DECT-B", "B-CRC-32"], 32, 0x04C1_1DB7, 0xFFFF_FFFF, false, false, 0xFFFF_FFFF, 0xFC89_1918),
+    crc_preset!("CRC-32/CKSUM", ["CKSUM", "CRC-32/POSIX"], 32, 0x04C1_1DB7, 0x0000_0000, false, false, 0xFFFF_FFFF, 0x765E_7680),
+    crc_preset!("CRC-32/ISCSI", ["CRC-32C", "CRC-32/BASE91-C", "CRC-32/CASTAGNOLI", "CRC-32/INTERLAKEN"], 32, 0x1EDC_6F41, 0xFFFF_FFFF, true, true, 0xFFFF_FFFF, 0xE306_9283),
+    crc_preset!("CRC-32/ISO-HDLC", ["CRC-32", "CRC-32/ADCCP", "CRC-32/V-42", "CRC-32/XZ", "PKZIP"], 32, 0x04C1_1DB7, 0xFFFF_FFFF, true, true, 0xFFFF_FFFF, 0xCBF4_3926),
+    crc_preset!("CRC-32/JAMCRC", ["JAMCRC"], 32, 0x04C1_1DB7, 0xFFFF_FFFF, true, true, 0x0000_0000, 0x340B_C6D9),
+    crc_preset!("CRC-32/MPEG-2", [], 32, 0x04C1_1DB7, 0xFFFF_FFFF, false, false, 0x0000_0000, 0x0376_E6E7),
+];
+
+/// Looks up a catalogue entry by canonical name or alias, ignoring ASCII case.
+pub fn crc_preset(name: &str) -> Option<&'static CrcPreset> {
+    CRC_PRESETS.iter().find(|preset| {
+        preset.name.eq_ignore_ascii_case(name)
+            || preset
+                .aliases
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Non-CRC algorithms, selected with `algorithm = "<name>"` like the CRC presets.
+const OTHER_ALGORITHMS: &[(&str, ChecksumAlgorithm)] = &[
+    (
+        "SUM-8",
+        ChecksumAlgorithm::Sum {
+            width: 8,
+            complement: SumComplement::None,
+        },
+    ),
+    (
+        "SUM-16",
+        ChecksumAlgorithm::Sum {
+            width: 16,
+            complement: SumComplement::None,
+        },
+    ),
+    (
+        "SUM-32",
+        ChecksumAlgorithm::Sum {
+            width: 32,
+            complement: SumComplement::None,
+        },
+    ),
+    ("FLETCHER-16", ChecksumAlgorithm::Fletcher16),
+    ("FLETCHER-32", ChecksumAlgorithm::Fletcher32),
+    ("ADLER-32", ChecksumAlgorithm::Adler32),
+    ("XOR-8", ChecksumAlgorithm::Xor8),
+];
+
+/// Resolves an `algorithm` name to a checksum config, ignoring ASCII case.
+pub fn algorithm(name: &str) -> Option<ChecksumAlgorithm> {
+    crc_preset(name)
+        .map(|preset| ChecksumAlgorithm::Crc(preset.config()))
+        .or_else(|| {
+            OTHER_ALGORITHMS
+                .iter()
+                .find(|(other, _)| other.eq_ignore_ascii_case(name))
+                .map(|(_, config)| config.clone())
+        })
+}
+
+/// Canonical names of every supported algorithm, CRC presets first.
+pub fn algorithm_names() -> impl Iterator<Item = &'static str> {
+    CRC_PRESETS
+        .iter()
+        .map(|preset| preset.name)
+        .chain(OTHER_ALGORITHMS.iter().map(|(name, _)| *name))
+}
+
+/// Computes the configured checksum over `data`, returned in the low `config.width()` bits.
+pub fn calculate_checksum(data: &[u8], config: &ChecksumAlgorithm) -> u32 {
+    let mut state = ChecksumState::new(config);
+    state.update(data);
+    state.finish()
+}
+
+/// Computes a CRC of up to 32 bits with configurable width, polynomial, initial value,
+/// reflection, and XOR-out.
+pub fn calculate_crc(data: &[u8], crc_settings: &CrcConfig) -> u32 {
+    let table = CrcTable::shared(crc_settings);
+    let register = table.update(table.init(crc_settings.start), data);
+    table.finish(register, crc_settings)
+}
+
+/// Returns the `width / 8` bytes that, written at `offset` in `data`, make the CRC of `data`
+/// equal `target`. The bytes currently at `offset` are ignored.
+///
+/// A CRC is affine in its input bits, so the effect of each compensation bit on the final CRC
+/// is found by running it through the table and shifting it past the trailing bytes with a
+/// zero-byte matrix power; solving the resulting linear system costs one pass over `data`.
+/// Returns `None` when no bytes reach `target`, which needs a polynomial without the `x^0` term.
+pub fn crc_compensation(
+    data: &[u8],
+    offset: usize,
+    config: &CrcConfig,
+    target: u32,
+) -> Option<Vec<u8>> {
+    let table = CrcTable::shared(config);
+    let mask = width_mask(config.width);
+    let length = usize::from(config.width / 8);
+    let trailing = data.get(offset + length..)?;
+
+    let mut register = table.update(table.init(config.start), &data[..offset]);
+    register = table.update(register, &vec![0; length]);
+    register = table.update(register, trailing);
+    let residual = (table.finish(register, config) ^ target) & mask;
+
+    let shift = table.zero_bytes_operator(trailing.len());
+    let mut basis: [Option<(u32, u32)>; 32] = [None; 32];
+    for bit in 0..length * 8 {
+        let mut input = vec![0; length];
+        input[bit / 8] = 1 << (bit % 8);
+        let register = apply_operator(&shift, table.update(0, &input));
+        let mut effect = (table.finish(register, config) ^ config.xor_out) & mask;
+        let mut combination = 1u32 << bit;
+        while effect != 0 {
+            let top = 31 - effect.leading_zeros() as usize;
+            match basis[top] {
+                Some((row, row_combination)) => {
+                    effect ^= row;
+                    combination ^= row_combination;
+                }
+                None => {
+                    basis[top] = Some((effect, combination));
+                    break;
+                }
+            }
+        }
+    }
+
+    let mut remaining = residual;
+    let mut solution = 0u32;
+    while remaining != 0 {
+        let top = 31 - remaining.leading_zeros() as usize;
+        let (row, row_combination) = basis[top]?;
+        remaining ^= row;
+        solution ^= row_combination;
+    }
+    Some(
+        (0..length)
+            .map(|index| (solution >> (index * 8)) as u8)
+            .collect(),
+    )
+}
+
+/// Returns `data` with the octets of each `word`-octet word reversed.
+pub fn swap_words(data: &[u8], word: usize) -> Vec<u8> {
+    data.chunks(word)
+        .flat_map(|word| word.iter().rev())
+        .copied()
+        .collect()
+}
+
+/// Linear map on the CRC register, stored as the image of each register bit.
+type RegisterOperator = [u32; 32];
+
+fn apply_operator(operator: &RegisterOperator, register: u32) -> u32 {
+    (0..32)
+        .filter(|bit| register & (1 << bit) != 0)
+        .fold(0, |image, bit| image ^ operator[bit])
+}
+
+/// Returns the operator applying `second` after `first`.
+fn compose_operators(second: &RegisterOperator, first: &RegisterOperator) -> RegisterOperator {
+    first.map(|column| apply_operator(second, column))
+}
+
+/// Running checksum that can be fed in pieces and cloned to continue from a shared prefix.
+#[derive(Debug, Clone)]
+pub struct ChecksumState {
+    engine: Engine,
+}
+
+#[derive(Debug, Clone)]
+enum Engine {
+    Crc {
+        table: Arc<CrcTable>,
+        config: CrcConfig,
+        register: u32,
+    },
+    Sum {
+        width: u8,
+        complement: SumComplement,
+        sum: u32,
+    },
+    Fletcher16 {
+        sum1: u32,
+        sum2: u32,
+    },
+    Fletcher32 {
+        sum1: u32,
+        sum2: u32,
+        /// Low byte of a 16-bit word whose high byte has not arrived yet.
+        pending: Option<u8>,
+    },
+    Adler32 {
+        a: u32,
+        b: u32,
+    },
+    Xor8(u8),
+    /// Custom algorithms see the whole covered stream at once.
+    Custom {
+        checksum: CustomChecksum,
+        data: Vec<u8>,
+    },
+}
+
+impl ChecksumState {
+    pub fn new(config: &ChecksumAlgorithm) -> Self {
+        let engine = match config {
+            ChecksumAlgorithm::Crc(crc) => {
+                let table = CrcTable::shared(crc);
+                Engine::Crc {
+                    register: table.init(crc.start),
+                    table,
+                    config: crc.clone(),
+                }
+            }
+            ChecksumAlgorithm::Sum { width, complement } => Engine::Sum {
+                width: *width,
+                complement: *complement,
+                sum: 0,
+            },
+            ChecksumAlgorithm::Fletcher16 => Engine::Fletcher16 { sum1: 0, sum2: 0 },
+            ChecksumAlgorithm::Fletcher32 => Engine::Fletcher32 {
+                sum1: 0,
+                sum2: 0,
+                pending: None,
+            },
+            ChecksumAlgorithm::Adler32 => Engine::Adler32 { a: 1, b: 0 },
+            ChecksumAlgorithm::Xor8 => Engine::Xor8(0),
+            ChecksumAlgorithm::Custom(checksum) => Engine::Custom {
+                checksum: checksum.clone(),
+                data: Vec::new(),
+            },
         };
+        Self { engine }
+    }
 
-        // Perform 8 rounds of bitwise CRC calculation
-        let mut step = if ref_in { idx } else { idx << 24 };
-        if ref_in {
-            for _ in 0..8 {
-                step = (step >> 1) ^ ((step & 1) * poly);
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.engine {
+            Engine::Crc {
+                table, register, ..
+            } => *register = table.update(*register, data),
+            Engine::Sum { sum, .. } => {
+                *sum = data
+                    .iter()
+                    .fold(*sum, |acc, &byte| acc.wrapping_add(u32::from(byte)));
             }
-        } else {
-            for _ in 0..8 {
-                step = (step << 1) ^ (((step >> 31) & 1) * poly);
+            Engine::Fletcher16 { sum1, sum2 } => {
+                for &byte in data {
+                    *sum1 = (*sum1 + u32::from(byte)) % 255;
+                    *sum2 = (*sum2 + *sum1) % 255;
+                }
+            }
+            Engine::Fletcher32 {
+                sum1,
+                sum2,
+                pending,
+            } => {
+                for &byte in data {
+                    match pending.take() {
+                        Some(low) => {
+                            let word = u32::from(low) | (u32::from(byte) << 8);
+                            *sum1 = (*sum1 + word) % 65535;
+                            *sum2 = (*sum2 + *sum1) % 65535;
+                        }
+                        None => *pending = Some(byte),
+                    }
+                }
+            }
+            Engine::Adler32 { a, b } => {
+                // Largest run before `b` can overflow, as in zlib.
+                const NMAX: usize = 5552;
+                const MOD_ADLER: u32 = 65521;
+                for chunk in data.chunks(NMAX) {
+                    for &byte in chunk {
+                        *a += u32::from(byte);
+                        *b += *a;
+                    }
+                    *a %= MOD_ADLER;
+                    *b %= MOD_ADLER;
+                }
+            }
+            Engine::Xor8(value) => *value = data.iter().fold(*value, |acc, &byte| acc ^ byte),
+            Engine::Custom { data: fed, .. } => fed.extend_from_slice(data),
+        }
+    }
+
+    /// Feeds `data` as `word`-octet words with the octets of each word reversed, for devices
+    /// that read memory in words of the other byte order. `data` must hold whole words.
+    pub fn update_swapped(&mut self, data: &[u8], word: usize) {
+        debug_assert!(
+            data.len().is_multiple_of(word),
+            "data must hold whole words"
+        );
+        // A multiple of every supported word size.
+        let mut swapped = [0u8; 4096];
+        for chunk in data.chunks(swapped.len()) {
+            let swapped = &mut swapped[..chunk.len()];
+            for (out, word) in swapped.chunks_exact_mut(word).zip(chunk.chunks_exact(word)) {
+                out.copy_from_slice(word);
+                out.reverse();
+            }
+            self.update(swapped);
+        }
+    }
+
+    /// Returns the checksum of every byte fed so far, in the low `config.width()` bits.
+    pub fn finish(&self) -> u32 {
+        match &self.engine {
+            Engine::Crc {
+                table,
+                config,
+                register,
+            } => table.finish(*register, config),
+            Engine::Sum {
+                width,
+                complement,
+                sum,
+            } => {
+                let mask = u32::MAX >> (32 - u32::from(*width));
+                let sum = match complement {
+                    SumComplement::None => *sum,
+                    SumComplement::Ones => !*sum,
+                    SumComplement::Twos => sum.wrapping_neg(),
+                };
+                sum & mask
+            }
+            Engine::Fletcher16 { sum1, sum2 } => (sum2 << 8) | sum1,
+            Engine::Fletcher32 {
+                sum1,
+                sum2,
+                pending,
+            } => {
+                // An odd trailing byte is zero-padded to a full word.
+                let (sum1, sum2) = match pending {
+                    Some(low) => {
+                        let sum1 = (sum1 + u32::from(*low)) % 65535;
+                        (sum1, (sum2 + sum1) % 65535)
+                    }
+                    None => (*sum1, *sum2),
+                };
+                (sum2 << 16) | sum1
+            }
+            Engine::Adler32 { a, b } => (b << 16) | a,
+            Engine::Xor8(value) => u32::from(*value),
+            Engine::Custom { checksum, data } => {
+                let algorithm = checksum.algorithm().unwrap_or_else(|| {
+                    unreachable!("custom checksum '{}' is not bound", checksum.name)
+                });
+                algorithm.checksum(data) & (u32::MAX >> (32 - u32::from(algorithm.width())))
+            }
+        }
+    }
+}
+
+/// Slicing-by-8 lookup tables for one CRC width, polynomial and input reflection.
+///
+/// Reflected CRCs keep the register bit-reversed in its low `width` bits; non-reflected CRCs
+/// keep it in the top `width` bits, so both shift whole bytes through a 32-bit register.
+pub struct CrcTable {
+    width: u8,
+    reflected: bool,
+    tables: Box<[[u32; 256]; 8]>,
+}
+
+impl std::fmt::Debug for CrcTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CrcTable")
+            .field("width", &self.width)
+            .field("reflected", &self.reflected)
+            .finish_non_exhaustive()
+    }
+}
+
+type CrcTableKey = (u8, u32, bool);
+
+static CRC_TABLES: LazyLock<Mutex<HashMap<CrcTableKey, Arc<CrcTable>>>> =
+    LazyLock::new(Default::default);
+
+impl CrcTable {
+    /// Returns the tables for `config`, building them on first use. Tables depend only on
+    /// width, polynomial and input reflection, so configs differing in other parameters share.
+    pub fn shared(config: &CrcConfig) -> Arc<Self> {
+        let mask = width_mask(config.width);
+        let key = (config.width, config.polynomial & mask, config.ref_in);
+        let mut tables = CRC_TABLES
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Arc::clone(
+            tables
+                .entry(key)
+                .or_insert_with(|| Arc::new(Self::new(key.0, key.1, key.2))),
+        )
+    }
+
+    fn new(width: u8, polynomial: u32, reflected: bool) -> Self {
+        debug_assert!(
+            (8..=32).contains(&width),
+            "CRC width is validated when the layout is parsed"
+        );
+        let mut tables = Box::new([[0u32; 256]; 8]);
+        for (index, entry) in tables[0].iter_mut().enumerate() {
+            let mut crc = index as u32;
+            if reflected {
+                let polynomial = reflect(polynomial, width);
+                for _ in 0..8 {
+                    crc = if crc & 1 != 0 {
+                        (crc >> 1) ^ polynomial
+                    } else {
+                        crc >> 1
+                    };
+                }
+            } else {
+                let polynomial = polynomial << (32 - u32::from(width));
+                crc <<= 24;
+                for _ in 0..8 {
+                    crc = if crc & 0x8000_0000 != 0 {
+                        (crc << 1) ^ polynomial
+                    } else {
+                        crc << 1
+                    };
+                }
+            }
+            *entry = crc;
+        }
+        for slice in 1..8 {
+            for index in 0..256 {
+                let previous = tables[slice - 1][index];
+                tables[slice][index] = if reflected {
+                    (previous >> 8) ^ tables[0][(previous & 0xFF) as usize]
+                } else {
+                    (previous << 8) ^ tables[0][(previous >> 24) as usize]
+                };
+            }
+        }
+        Self {
+            width,
+            reflected,
+            tables,
+        }
+    }
+
+    /// Returns the register map for feeding `count` zero bytes, by repeated squaring.
+    fn zero_bytes_operator(&self, mut count: usize) -> RegisterOperator {
+        let mut power: RegisterOperator = std::array::from_fn(|bit| self.update(1 << bit, &[0]));
+        let mut result: RegisterOperator = std::array::from_fn(|bit| 1 << bit);
+        while count != 0 {
+            if count & 1 != 0 {
+                result = compose_operators(&power, &result);
             }
+            power = compose_operators(&power, &power);
+            count >>= 1;
         }
+        result
+    }
 
-        crc = if ref_in {
-            step ^ (crc >> 8)
+    fn init(&self, start: u32) -> u32 {
+        let start = start & width_mask(self.width);
+        if self.reflected {
+            reflect(start, self.width)
         } else {
-            step ^ (crc << 8)
-        };
+            start << (32 - u32::from(self.width))
+        }
     }
 
-    // Finalize
-    if ref_in ^ ref_out {
-        crc = crc.reverse_bits();
+    fn update(&self, mut register: u32, data: &[u8]) -> u32 {
+        let t = &self.tables;
+        let mut chunks = data.chunks_exact(8);
+        for chunk in &mut chunks {
+            let low = [chunk[0], chunk[1], chunk[2], chunk[3]];
+            let high = [chunk[4], chunk[5], chunk[6], chunk[7]];
+            register = if self.reflected {
+                let low = register ^ u32::from_le_bytes(low);
+                let high = u32::from_le_bytes(high);
+                t[7][(low & 0xFF) as usize]
+                    ^ t[6][((low >> 8) & 0xFF) as usize]
+                    ^ t[5][((low >> 16) & 0xFF) as usize]
+                    ^ t[4][(low >> 24) as usize]
+                    ^ t[3][(high & 0xFF) as usize]
+                    ^ t[2][((high >> 8) & 0xFF) as usize]
+                    ^ t[1][((high >> 16) & 0xFF) as usize]
+                    ^ t[0][(high >> 24) as usize]
+            } else {
+                let low = register ^ u32::from_be_bytes(low);
+                let high = u32::from_be_bytes(high);
+                t[7][(low >> 24) as usize]
+                    ^ t[6][((low >> 16) & 0xFF) as usize]
+                    ^ t[5][((low >> 8) & 0xFF) as usize]
+                    ^ t[4][(low & 0xFF) as usize]
+                    ^ t[3][(high >> 24) as usize]
+                    ^ t[2][((high >> 16) & 0xFF) as usize]
+                    ^ t[1][((high >> 8) & 0xFF) as usize]
+                    ^ t[0][(high & 0xFF) as usize]
+            };
+        }
+        for &byte in chunks.remainder() {
+            register = if self.reflected {
+                t[0][((register ^ u32::from(byte)) & 0xFF) as usize] ^ (register >> 8)
+            } else {
+                t[0][((register >> 24) ^ u32::from(byte)) as usize] ^ (register << 8)
+            };
+        }
+        register
     }
 
-    crc ^ xor_out
+    fn finish(&self, register: u32, config: &CrcConfig) -> u32 {
+        let crc = if self.reflected {
+            // The register already holds the reflected CRC.
+            if config.ref_out {
+                register
+            } else {
+                reflect(register, self.width)
+            }
+        } else {
+            let crc = register >> (32 - u32::from(self.width));
+            if config.ref_out {
+                reflect(crc, self.width)
+            } else {
+                crc
+            }
+        };
+        (crc ^ config.xor_out) & width_mask(self.width)
+    }
+}
+
+fn width_mask(width: u8) -> u32 {
+    u32::MAX >> (32 - u32::from(width))
+}
+
+/// Reverses the low `width` bits of `value`.
+fn reflect(value: u32, width: u8) -> u32 {
+    value.reverse_bits() >> (32 - u32::from(width))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn standard_crc_config() -> ChecksumConfig {
-        ChecksumConfig {
+    const CHECK_INPUT: &[u8] = b"123456789";
+
+    fn standard_crc_config() -> CrcConfig {
+        CrcConfig {
+            width: 32,
             polynomial: 0x04C11DB7,
             start: 0xFFFF_FFFF,
             xor_out: 0xFFFF_FFFF,
@@ -73,8 +622,7 @@ mod tests {
         let crc_settings = standard_crc_config();
 
         // The standard CRC32 test vector - "123456789" should produce 0xCBF43926
-        let test_str = b"123456789";
-        let result = calculate_crc(test_str, &crc_settings);
+        let result = calculate_crc(CHECK_INPUT, &crc_settings);
         assert_eq!(
             result, 0xCBF43926,
             "Standard CRC32 test vector failed (expected 0xCBF43926 for \"123456789\")"
@@ -88,7 +636,8 @@ mod tests {
 
     #[test]
     fn test_crc32_mpeg2_non_reflected_vector() {
-        let crc_settings = ChecksumConfig {
+        let crc_settings = CrcConfig {
+            width: 32,
             polynomial: 0x04C11DB7,
             start: 0xFFFF_FFFF,
             xor_out: 0x0000_0000,
@@ -97,11 +646,198 @@ mod tests {
         };
 
         // CRC-32/MPEG-2 parameters (non-reflected) over "123456789" should produce 0x0376E6E7
-        let test_str = b"123456789";
-        let result = calculate_crc(test_str, &crc_settings);
+        let result = calculate_crc(CHECK_INPUT, &crc_settings);
         assert_eq!(
             result, 0x0376E6E7,
             "CRC32/MPEG-2 test vector failed (expected 0x0376E6E7 for \"123456789\")"
         );
     }
+
+    #[test]
+    fn every_preset_matches_its_published_check_value() {
+        for preset in CRC_PRESETS {
+            assert_eq!(
+                calculate_crc(CHECK_INPUT, &preset.config()),
+                preset.check,
+                "{} check value mismatch",
+                preset.name
+            );
+        }
+    }
+
+    /// Bit-at-a-time reference CRC, straight from the parameter model definition.
+    fn bitwise_crc(data: &[u8], config: &CrcConfig) -> u32 {
+        let width = u32::from(config.width);
+        let mask = u32::MAX >> (32 - width);
+        let top_bit = 1u32 << (width - 1);
+        let mut crc = config.start & mask;
+        for &byte in data {
+            let byte = if config.ref_in {
+                byte.reverse_bits()
+            } else {
+                byte
+            };
+            crc ^= u32::from(byte) << (width - 8);
+            for _ in 0..8 {
+                crc = if crc & top_bit != 0 {
+                    (crc << 1) ^ config.polynomial
+                } else {
+                    crc << 1
+                };
+            }
+            crc &= mask;
+        }
+        if config.ref_out {
+            crc = crc.reverse_bits() >> (32 - width);
+        }
+        (crc ^ config.xor_out) & mask
+    }
+
+    #[test]
+    fn sliced_crc_matches_bitwise_reference_for_every_length_and_split() {
+        let data = (0..67u32)
+            .map(|index| (index.wrapping_mul(0x9E37_79B9) >> 13) as u8)
+            .collect::<Vec<_>>();
+        let mut configs = CRC_PRESETS
+            .iter()
+            .map(CrcPreset::config)
+            .collect::<Vec<_>>();
+        // Mixed reflection is not in the catalogue but is a valid explicit config.
+        configs.push(CrcConfig {
+            width: 16,
+            polynomial: 0x1021,
+            start: 0x1D0F,
+            xor_out: 0x0000,
+            ref_in: true,
+            ref_out: false,
+        });
+        configs.push(CrcConfig {
+            width: 32,
+            polynomial: 0x04C1_1DB7,
+            start: 0x1234_5678,
+            xor_out: 0,
+            ref_in: false,
+            ref_out: true,
+        });
+        for config in &configs {
+            for len in 0..=data.len() {
+                let expected = bitwise_crc(&data[..len], config);
+                assert_eq!(
+                    calculate_crc(&data[..len], config),
+                    expected,
+                    "{config:?} len {len}"
+                );
+                let split = len / 3;
+                let mut state = ChecksumState::new(&ChecksumAlgorithm::Crc(config.clone()));
+                state.update(&data[..split]);
+                state.update(&data[split..len]);
+                assert_eq!(
+                    state.finish(),
+                    expected,
+                    "{config:?} split at {split} of {len}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn streamed_checksums_match_one_shot_results() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        for name in algorithm_names() {
+            let config = algorithm(name).expect("known algorithm");
+            let expected = calculate_checksum(data, &config);
+            for split in [1, 2, 7, 13] {
+                let mut state = ChecksumState::new(&config);
+                for chunk in data.chunks(split) {
+                    state.update(chunk);
+                }
+                assert_eq!(state.finish(), expected, "{name} in {split}-byte chunks");
+            }
+        }
+    }
+
+    #[test]
+    fn compensation_bytes_force_the_target_crc_at_any_offset() {
+        let mut data = (0..5000u32)
+            .map(|index| (index.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect::<Vec<_>>();
+        for preset in CRC_PRESETS {
+            let config = preset.config();
+            let length = usize::from(config.width / 8);
+            let mask = width_mask(config.width);
+            for offset in [0, 1, 9, 4000, data.len() - length] {
+                for target in [0, 0x2144_DF1C, u32::MAX] {
+                    let bytes = crc_compensation(&data, offset, &config, target)
+                        .expect("compensation exists for catalogue polynomials");
+                    data[offset..offset + length].copy_from_slice(&bytes);
+                    assert_eq!(
+                        calculate_crc(&data, &config),
+                        target & mask,
+                        "{} at offset {offset}",
+                        preset.name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn preset_names_and_aliases_are_unique() {
+        let mut names = std::collections::HashSet::new();
+        for preset in CRC_PRESETS {
+            for name in std::iter::once(&preset.name).chain(preset.aliases) {
+                assert!(
+                    names.insert(name.to_ascii_uppercase()),
+                    "duplicate CRC preset name {name}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn presets_resolve_by_alias_and_ignore_case() {
+        assert_eq!(
+            crc_preset("crc-32c").map(|preset| preset.name),
+            Some("CRC-32/ISCSI")
+        );
+        assert_eq!(
+            crc_preset("CRC-16/AUTOSAR").map(|preset| preset.name),
+            Some("CRC-16/IBM-3740")
+        );
+        assert!(crc_preset("CRC-99/UNKNOWN").is_none());
+    }
+
+    #[test]
+    fn non_crc_algorithms_match_reference_vectors() {
+        let check = |name: &str, data: &[u8]| {
+            calculate_checksum(data, &algorithm(name).expect("known algorithm"))
+        };
+        assert_eq!(check("FLETCHER-16", b"abcde"), 0xC8F0);
+        assert_eq!(check("FLETCHER-16", b"abcdef"), 0x2057);
+        assert_eq!(check("FLETCHER-32", b"abcde"), 0xF04F_C729);
+        assert_eq!(check("FLETCHER-32", b"abcdef"), 0x5650_2D2A);
+        assert_eq!(check("FLETCHER-32", b"abcdefgh"), 0xEBE1_9591);
+        assert_eq!(check("ADLER-32", b"Wikipedia"), 0x11E6_0398);
+        assert_eq!(check("XOR-8", &[0x01, 0x02, 0x04, 0xF0]), 0xF7);
+        assert_eq!(check("SUM-8", CHECK_INPUT), 0xDD);
+        assert_eq!(check("SUM-16", CHECK_INPUT), 0x01DD);
+        assert_eq!(check("sum-32", &[0xFF; 4]), 0x03FC);
+    }
+
+    #[test]
+    fn sum_complements_invert_or_negate_the_truncated_sum() {
+        let sum = |width, complement| {
+            calculate_checksum(CHECK_INPUT, &ChecksumAlgorithm::Sum { width, complement })
+        };
+        assert_eq!(sum(8, SumComplement::Ones), 0x22);
+        assert_eq!(sum(8, SumComplement::Twos), 0x23);
+        assert_eq!(sum(16, SumComplement::Twos), 0xFE23);
+        assert_eq!(sum(32, SumComplement::Twos), 0xFFFF_FE23);
+        let total = CHECK_INPUT
+            .iter()
+            .fold(sum(8, SumComplement::Twos), |acc, &byte| {
+                acc + u32::from(byte)
+            });
+        assert_eq!(total & 0xFF, 0);
+    }
 }
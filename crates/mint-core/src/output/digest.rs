@@ -0,0 +1,82 @@
+use crate::layout::settings::DigestAlgorithm;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256, Sha512};
+
+/// HMAC-SHA256 tag length in bytes.
+pub const HMAC_SHA256_LEN: usize = 32;
+
+/// Hashes `data` with the selected algorithm, returning `algorithm.output_len()` bytes.
+pub fn calculate_digest(data: &[u8], algorithm: DigestAlgorithm) -> Vec<u8> {
+    match algorithm {
+        DigestAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+        DigestAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+        DigestAlgorithm::Blake3 => blake3::hash(data).as_bytes().to_vec(),
+    }
+}
+
+/// Computes the HMAC-SHA256 tag of `data` under `key`.
+pub fn calculate_hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key)
+        .unwrap_or_else(|_| unreachable!("HMAC accepts keys of any length"));
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    #[test]
+    fn digests_match_published_abc_vectors() {
+        assert_eq!(
+            hex(&calculate_digest(b"abc", DigestAlgorithm::Sha256)),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex(&calculate_digest(b"abc", DigestAlgorithm::Sha512)),
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        );
+        assert_eq!(
+            hex(&calculate_digest(b"", DigestAlgorithm::Blake3)),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+    }
+
+    #[test]
+    fn digest_lengths_match_algorithm_output_len() {
+        for algorithm in [
+            DigestAlgorithm::Sha256,
+            DigestAlgorithm::Sha512,
+            DigestAlgorithm::Blake3,
+        ] {
+            assert_eq!(
+                calculate_digest(b"mint", algorithm).len(),
+                algorithm.output_len()
+            );
+        }
+    }
+
+    #[test]
+    fn hmac_sha256_matches_rfc_4231_vectors() {
+        assert_eq!(
+            hex(&calculate_hmac_sha256(
+                b"Jefe",
+                b"what do ya want for nothing?"
+            )),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Test case 6: keys longer than the block size are hashed first.
+        assert_eq!(
+            hex(&calculate_hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+}
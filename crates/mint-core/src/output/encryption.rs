@@ -0,0 +1,118 @@
+use crate::layout::settings::EncryptionAlgorithm;
+use aes::{Aes128, Aes256};
+use aes_gcm::aead::AeadInPlace;
+use aes_gcm::{Aes128Gcm, Aes256Gcm, KeyInit};
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+type Aes256Ctr = ctr::Ctr128BE<Aes256>;
+
+/// HKDF-SHA256 label of the key that derives nonces, so the AES key is never also an HMAC key.
+const NONCE_KEY_INFO: &[u8] = b"mint block encryption nonce key";
+
+/// Secret key loaded for one `[mint.encryption]` config.
+pub struct EncryptionKey {
+    algorithm: EncryptionAlgorithm,
+    key: Vec<u8>,
+    nonce_key: [u8; 32],
+}
+
+impl EncryptionKey {
+    /// Accepts exactly `algorithm.key_len()` key bytes.
+    pub fn from_key_bytes(algorithm: EncryptionAlgorithm, bytes: &[u8]) -> Result<Self, String> {
+        let length = algorithm.key_len();
+        if bytes.len() != length {
+            return Err(format!(
+                "{} needs a {length}-byte key; got {} bytes",
+                algorithm.name(),
+                bytes.len()
+            ));
+        }
+        Ok(Self::new(algorithm, bytes.to_vec()))
+    }
+
+    fn new(algorithm: EncryptionAlgorithm, key: Vec<u8>) -> Self {
+        let mut nonce_key = [0; 32];
+        Hkdf::<Sha256>::new(None, &key)
+            .expand(NONCE_KEY_INFO, &mut nonce_key)
+            .unwrap_or_else(|_| unreachable!("32 octets is a valid HKDF-SHA256 output length"));
+        Self {
+            algorithm,
+            key,
+            nonce_key,
+        }
+    }
+
+    pub fn algorithm(&self) -> EncryptionAlgorithm {
+        self.algorithm
+    }
+
+    /// Derives the nonce as the HMAC-SHA256 of the plaintext under a nonce key derived from the
+    /// encryption key with HKDF-SHA256. Identical plaintexts produce identical nonces and
+    /// ciphertexts, while any change to the plaintext changes the nonce.
+    pub fn derive_nonce(&self, plaintext: &[u8]) -> Vec<u8> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.nonce_key)
+            .unwrap_or_else(|_| unreachable!("HMAC accepts keys of any length"));
+        mac.update(plaintext);
+        mac.finalize().into_bytes()[..self.algorithm.nonce_len()].to_vec()
+    }
+
+    /// Encrypts `data` in place under `nonce`, returning the authentication tag, which is empty
+    /// for CTR modes.
+    pub fn encrypt(&self, nonce: &[u8], data: &mut [u8]) -> Vec<u8> {
+        match self.algorithm {
+            EncryptionAlgorithm::Aes128Ctr => {
+                self.ctr_cipher::<Aes128Ctr>(nonce).apply_keystream(data);
+                Vec::new()
+            }
+            EncryptionAlgorithm::Aes256Ctr => {
+                self.ctr_cipher::<Aes256Ctr>(nonce).apply_keystream(data);
+                Vec::new()
+            }
+            EncryptionAlgorithm::Aes128Gcm => self.gcm_encrypt::<Aes128Gcm>(nonce, data),
+            EncryptionAlgorithm::Aes256Gcm => self.gcm_encrypt::<Aes256Gcm>(nonce, data),
+        }
+    }
+
+    /// Decrypts `data` in place, checking `tag` for GCM modes.
+    pub fn decrypt(&self, nonce: &[u8], data: &mut [u8], tag: &[u8]) -> Result<(), String> {
+        match self.algorithm {
+            EncryptionAlgorithm::Aes128Ctr | EncryptionAlgorithm::Aes256Ctr => {
+                self.encrypt(nonce, data);
+                Ok(())
+            }
+            EncryptionAlgorithm::Aes128Gcm => self.gcm_decrypt::<Aes128Gcm>(nonce, data, tag),
+            EncryptionAlgorithm::Aes256Gcm => self.gcm_decrypt::<Aes256Gcm>(nonce, data, tag),
+        }
+    }
+
+    fn ctr_cipher<C: KeyIvInit>(&self, nonce: &[u8]) -> C {
+        C::new_from_slices(&self.key, nonce)
+            .unwrap_or_else(|_| unreachable!("key and nonce lengths match the algorithm"))
+    }
+
+    fn gcm_encrypt<C: KeyInit + AeadInPlace>(&self, nonce: &[u8], data: &mut [u8]) -> Vec<u8> {
+        C::new_from_slice(&self.key)
+            .unwrap_or_else(|_| unreachable!("key length matches the algorithm"))
+            .encrypt_in_place_detached(nonce.into(), &[], data)
+            .unwrap_or_else(|_| unreachable!("block data is far below the GCM length limit"))
+            .to_vec()
+    }
+
+    fn gcm_decrypt<C: KeyInit + AeadInPlace>(
+        &self,
+        nonce: &[u8],
+        data: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), String> {
+        C::new_from_slice(&self.key)
+            .unwrap_or_else(|_| unreachable!("key length matches the algorithm"))
+            .decrypt_in_place_detached(nonce.into(), &[], data, tag.into())
+            .map_err(|_| {
+                "authentication tag mismatch; the image was modified or the key is wrong".to_owned()
+            })
+    }
+}
@@ -14,4 +14,7 @@ pub enum OutputError {
 
     #[error("block memory overlap detected: {0}")]
     BlockOverlapError(String),
+
+    #[error("output writer error: {0}")]
+    WriterError(String),
 }
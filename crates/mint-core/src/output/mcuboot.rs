@@ -0,0 +1,116 @@
+use crate::layout::abi::{EndianBytes, Endianness};
+use crate::layout::header::McubootImage;
+use crate::layout::settings::{DigestAlgorithm, SignatureAlgorithm};
+use crate::output::digest::calculate_digest;
+use crate::output::signature::SigningKey;
+use ed25519_dalek::pkcs8::EncodePublicKey as _;
+use p256::ecdsa::signature::Signer as _;
+
+pub const IMAGE_MAGIC: u32 = 0x96F3_B83D;
+/// Size of `struct image_header`; `header_size` pads it with zeros.
+pub const IMAGE_HEADER_LEN: usize = 32;
+pub const IMAGE_F_RAM_LOAD: u32 = 0x20;
+pub const TLV_INFO_MAGIC: u16 = 0x6907;
+pub const TLV_KEYHASH: u16 = 0x01;
+pub const TLV_SHA256: u16 = 0x10;
+pub const TLV_ECDSA_SIG: u16 = 0x22;
+pub const TLV_ED25519: u16 = 0x24;
+
+const TLV_HEADER_LEN: usize = 4;
+const SHA256_LEN: usize = 32;
+/// Longest DER encoding of a P-256 ECDSA signature.
+const ECDSA_P256_DER_MAX_LEN: usize = 72;
+
+/// Upper bound on the TLV trailer length: the info header, the SHA-256 hash and, when signed,
+/// the key hash and signature.
+pub fn tlv_area_len(signature: Option<SignatureAlgorithm>) -> usize {
+    let signed = match signature {
+        None => 0,
+        Some(algorithm) => {
+            let signature_len = match algorithm {
+                SignatureAlgorithm::Ed25519 => algorithm.signature_len(),
+                SignatureAlgorithm::EcdsaP256 => ECDSA_P256_DER_MAX_LEN,
+            };
+            2 * TLV_HEADER_LEN + SHA256_LEN + signature_len
+        }
+    };
+    TLV_HEADER_LEN + TLV_HEADER_LEN + SHA256_LEN + signed
+}
+
+/// Wraps `payload` as `imgtool sign` would: the image header padded to `header_size`, the
+/// payload, then an unprotected TLV area holding the SHA-256 of header and payload and, with a
+/// key, the SHA-256 of its DER public key and the signature. Ed25519 signs the hash; ECDSA
+/// signs header and payload with SHA-256 and is DER encoded.
+pub fn wrap_image(
+    image: &McubootImage,
+    payload: &[u8],
+    endianness: Endianness,
+    key: Option<&SigningKey>,
+) -> Vec<u8> {
+    let header_size = usize::from(image.header_size);
+    let flags = if image.load_address.is_some() {
+        IMAGE_F_RAM_LOAD
+    } else {
+        0
+    };
+    let mut out = Vec::with_capacity(header_size + payload.len() + tlv_area_len(None));
+    out.extend(IMAGE_MAGIC.to_endian_bytes(endianness));
+    out.extend(image.load_address.unwrap_or(0).to_endian_bytes(endianness));
+    out.extend(image.header_size.to_endian_bytes(endianness));
+    out.extend(0u16.to_endian_bytes(endianness)); // ih_protect_tlv_size
+    out.extend((payload.len() as u32).to_endian_bytes(endianness));
+    out.extend(flags.to_endian_bytes(endianness));
+    out.push(image.version.major);
+    out.push(image.version.minor);
+    out.extend(image.version.revision.to_endian_bytes(endianness));
+    out.extend(image.version.build.to_endian_bytes(endianness));
+    out.extend(0u32.to_endian_bytes(endianness)); // _pad1
+    debug_assert_eq!(out.len(), IMAGE_HEADER_LEN);
+    out.resize(header_size, 0);
+    out.extend_from_slice(payload);
+
+    let hash = calculate_digest(&out, DigestAlgorithm::Sha256);
+    let mut tlvs = vec![(TLV_SHA256, hash.clone())];
+    if let Some(key) = key {
+        let (public_key, kind, signature) = match key {
+            SigningKey::Ed25519(key) => (
+                key.verifying_key()
+                    .to_public_key_der()
+                    .map(|der| der.into_vec()),
+                TLV_ED25519,
+                ed25519_dalek::Signer::sign(key, &hash).to_bytes().to_vec(),
+            ),
+            SigningKey::EcdsaP256(key) => {
+                let signature: p256::ecdsa::Signature = key.sign(&out);
+                (
+                    key.verifying_key()
+                        .to_public_key_der()
+                        .map(|der| der.into_vec()),
+                    TLV_ECDSA_SIG,
+                    signature.to_der().as_bytes().to_vec(),
+                )
+            }
+        };
+        let public_key =
+            public_key.unwrap_or_else(|_| unreachable!("valid public keys encode as DER"));
+        tlvs.push((
+            TLV_KEYHASH,
+            calculate_digest(&public_key, DigestAlgorithm::Sha256),
+        ));
+        tlvs.push((kind, signature));
+    }
+
+    let total = TLV_HEADER_LEN
+        + tlvs
+            .iter()
+            .map(|(_, value)| TLV_HEADER_LEN + value.len())
+            .sum::<usize>();
+    out.extend(TLV_INFO_MAGIC.to_endian_bytes(endianness));
+    out.extend((total as u16).to_endian_bytes(endianness));
+    for (kind, value) in tlvs {
+        out.extend(kind.to_endian_bytes(endianness));
+        out.extend((value.len() as u16).to_endian_bytes(endianness));
+        out.extend(value);
+    }
+    out
+}
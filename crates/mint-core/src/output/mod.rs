@@ -1,6 +1,10 @@
 pub mod checksum;
+pub mod digest;
+pub mod encryption;
 pub mod error;
+pub mod mcuboot;
 pub mod report;
+pub mod signature;
 
 use crate::layout::abi::Abi;
 use crate::layout::header::Header;
@@ -189,6 +193,9 @@ mod tests {
             start_address: 0,
             length: len,
             padding: 0xFF,
+            signature: None,
+            encryption: None,
+            mcuboot: None,
         }
     }
 
@@ -207,6 +214,9 @@ mod tests {
             start_address: 0,
             length: 3,
             padding: 0xFF,
+            signature: None,
+            encryption: None,
+            mcuboot: None,
         };
         let error = bytestream_to_datarange(vec![0, 0], &header, Abi::TiC28xEabi)
             .expect_err("odd C28x block length should fail");
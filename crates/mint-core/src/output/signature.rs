@@ -0,0 +1,70 @@
+use crate::layout::settings::SignatureAlgorithm;
+use ed25519_dalek::pkcs8::DecodePrivateKey as _;
+use p256::ecdsa::signature::Signer as _;
+
+/// Private key loaded for one `[mint.signature]` config.
+pub enum SigningKey {
+    Ed25519(ed25519_dalek::SigningKey),
+    EcdsaP256(p256::ecdsa::SigningKey),
+}
+
+impl SigningKey {
+    /// Parses a PEM or DER private key. Ed25519 keys must be PKCS#8; P-256 keys may be PKCS#8
+    /// or SEC1 (`EC PRIVATE KEY`).
+    pub fn from_key_bytes(algorithm: SignatureAlgorithm, bytes: &[u8]) -> Result<Self, String> {
+        let pem = std::str::from_utf8(bytes)
+            .ok()
+            .filter(|text| text.trim_start().starts_with("-----BEGIN"));
+        match algorithm {
+            SignatureAlgorithm::Ed25519 => match pem {
+                Some(pem) => ed25519_dalek::SigningKey::from_pkcs8_pem(pem),
+                None => ed25519_dalek::SigningKey::from_pkcs8_der(bytes),
+            }
+            .map(Self::Ed25519)
+            .map_err(|error| format!("invalid Ed25519 PKCS#8 private key: {error}")),
+            SignatureAlgorithm::EcdsaP256 => {
+                let secret = match pem {
+                    Some(pem) => p256::SecretKey::from_pkcs8_pem(pem)
+                        .or_else(|_| p256::SecretKey::from_sec1_pem(pem)),
+                    None => p256::SecretKey::from_pkcs8_der(bytes)
+                        .or_else(|_| p256::SecretKey::from_sec1_der(bytes)),
+                };
+                secret
+                    .map(|secret| Self::EcdsaP256(secret.into()))
+                    .map_err(|error| format!("invalid P-256 PKCS#8 or SEC1 private key: {error}"))
+            }
+        }
+    }
+
+    pub fn algorithm(&self) -> SignatureAlgorithm {
+        match self {
+            Self::Ed25519(_) => SignatureAlgorithm::Ed25519,
+            Self::EcdsaP256(_) => SignatureAlgorithm::EcdsaP256,
+        }
+    }
+
+    /// Signs `data`. Ed25519 signs the message directly; ECDSA hashes it with SHA-256 and uses
+    /// RFC 6979 nonces, so both algorithms produce reproducible images.
+    pub fn sign(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Ed25519(key) => ed25519_dalek::Signer::sign(key, data).to_bytes().to_vec(),
+            Self::EcdsaP256(key) => {
+                let signature: p256::ecdsa::Signature = key.sign(data);
+                signature.to_bytes().to_vec()
+            }
+        }
+    }
+
+    /// Public key bytes: the raw 32-byte Ed25519 key or the 65-byte uncompressed SEC1 P-256
+    /// point.
+    pub fn public_key(&self) -> Vec<u8> {
+        match self {
+            Self::Ed25519(key) => key.verifying_key().to_bytes().to_vec(),
+            Self::EcdsaP256(key) => key
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes()
+                .to_vec(),
+        }
+    }
+}
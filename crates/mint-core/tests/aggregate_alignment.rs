@@ -1,6 +1,6 @@
 use mint_core::build::{self, BlockSelector, BuildFromLayoutsRequest, NamedLayout};
 use mint_core::layout;
-use mint_core::output::checksum::calculate_crc;
+use mint_core::output::checksum::calculate_checksum;
 use std::path::PathBuf;
 
 struct BuildOutput {
@@ -218,7 +218,10 @@ checksum = { checksum = "crc32", type = "u32" }
     let source = layout(data);
     let output = build_output(data);
     let config = layout::parse_toml_layout(&source).expect("layout parses");
-    let checksum = calculate_crc(&output.bytestream[..12], &config.mint.checksum["crc32"]);
+    let checksum = calculate_checksum(
+        &output.bytestream[..12],
+        &config.mint.checksum["crc32"].algorithm,
+    );
 
     assert_eq!(
         &output.bytestream[..12],
@@ -0,0 +1,637 @@
+use mint_core::build::{self, BlockSelector, BuildFromLayoutsRequest, NamedLayout};
+use mint_core::layout;
+use mint_core::output::checksum::calculate_checksum;
+use std::path::PathBuf;
+
+#[path = "common/mod.rs"]
+mod common;
+
+fn checksum_layout(abi: &str, checksums: &str, data: &str) -> String {
+    format!(
+        r#"
+[mint]
+abi = "{abi}"
+
+{checksums}
+
+[block.header]
+start_address = 0x1000
+length = 0x100
+
+[block.data]
+{data}
+"#
+    )
+}
+
+fn build_bytes(abi: &str, checksums: &str, data: &str) -> Result<Vec<u8>, String> {
+    let config = layout::parse_toml_layout(&checksum_layout(abi, checksums, data))
+        .map_err(|error| common::error_chain(&error))?;
+    let artifact = build::build_from_layouts(BuildFromLayoutsRequest {
+        layouts: vec![NamedLayout {
+            name: PathBuf::from("checksum.toml"),
+            config,
+        }],
+        blocks: vec![BlockSelector::named("checksum.toml", "block")],
+        data_source: None,
+        strict: false,
+        capture_values: false,
+    })
+    .map_err(|error| common::error_chain(&error))?;
+    Ok(artifact.ranges[0].bytestream.clone())
+}
+
+#[test]
+fn preset_matches_equivalent_explicit_parameters() {
+    let data = r#"
+payload = { value = [1, 2, 3, 4, 5, 6, 7, 8], type = "u8", size = 8 }
+checksum = { checksum = "crc", type = "u32" }
+"#;
+    let preset = build_bytes(
+        "generic-le",
+        r#"
+[mint.checksum.crc]
+algorithm = "CRC-32/ISO-HDLC"
+"#,
+        data,
+    )
+    .expect("preset checksum builds");
+    let explicit = build_bytes(
+        "generic-le",
+        r#"
+[mint.checksum.crc]
+polynomial = 0x04C11DB7
+start = 0xFFFFFFFF
+xor_out = 0xFFFFFFFF
+ref_in = true
+ref_out = true
+"#,
+        data,
+    )
+    .expect("explicit checksum builds");
+
+    assert_eq!(preset, explicit);
+}
+
+#[test]
+fn crc16_preset_uses_u16_storage_in_abi_byte_order() {
+    let checksums = r#"
+[mint.checksum.crc16]
+algorithm = "CRC-16/IBM-3740"
+"#;
+    let data = r#"
+payload = { value = "123456789", type = "u8", size = 9 }
+checksum = { checksum = "crc16", type = "u16" }
+"#;
+
+    let little = build_bytes("generic-le", checksums, data).expect("little-endian builds");
+    let big = build_bytes("generic-be", checksums, data).expect("big-endian builds");
+
+    let config = layout::parse_toml_layout(&checksum_layout("generic-le", checksums, data))
+        .expect("layout parses");
+    let crc16 = &config.mint.checksum["crc16"].algorithm;
+    assert_eq!(calculate_checksum(b"123456789", crc16), 0x29B1);
+
+    // The u16 checksum aligns to offset 10, so the CRC covers the padding byte at offset 9.
+    let expected = u16::try_from(calculate_checksum(&little[..10], crc16)).expect("16-bit CRC");
+    assert_eq!(little[9], 0xFF);
+    assert_eq!(&little[10..12], &expected.to_le_bytes());
+    assert_eq!(&big[10..12], &expected.to_be_bytes());
+}
+
+#[test]
+fn checksum_config_rejects_invalid_forms() {
+    let data = r#"
+value = { value = 1, type = "u32" }
+checksum = { checksum = "crc", type = "u32" }
+"#;
+    let cases = [
+        (
+            "algorithm = \"CRC-99/UNKNOWN\"",
+            "unknown checksum algorithm 'CRC-99/UNKNOWN'",
+        ),
+        (
+            "algorithm = \"CRC-32C\"\nref_out = false",
+            "cannot be combined with explicit CRC parameters; found 'ref_out'",
+        ),
+        (
+            "polynomial = 0x04C11DB7\nstart = 0xFFFFFFFF",
+            "missing 'xor_out', 'ref_in', 'ref_out'",
+        ),
+        (
+            "width = 12\npolynomial = 0x80F\nstart = 0\nxor_out = 0\nref_in = false\nref_out = false",
+            "width must be 8, 16, or 32 bits",
+        ),
+        (
+            "width = 16\npolynomial = 0x04C11DB7\nstart = 0\nxor_out = 0\nref_in = false\nref_out = false",
+            "does not fit the configured 16-bit width",
+        ),
+    ];
+
+    for (config, expected) in cases {
+        let error = build_bytes(
+            "generic-le",
+            &format!("[mint.checksum.crc]\n{config}"),
+            data,
+        )
+        .expect_err("checksum config should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+#[test]
+fn checksum_storage_must_match_algorithm_width() {
+    let error = build_bytes(
+        "generic-le",
+        r#"
+[mint.checksum.crc]
+algorithm = "CRC-32C"
+"#,
+        r#"
+value = { value = 1, type = "u32" }
+checksum = { checksum = "crc", type = "u16" }
+"#,
+    )
+    .expect_err("width mismatch should be rejected");
+
+    assert!(
+        error.contains("does not match the 32-bit checksum width"),
+        "unexpected error: {error}"
+    );
+}
+
+#[test]
+fn twos_complement_sum_makes_covered_bytes_sum_to_zero() {
+    let bytes = build_bytes(
+        "generic-be",
+        r#"
+[mint.checksum.sum]
+algorithm = "SUM-16"
+complement = "twos"
+"#,
+        r#"
+payload = { value = [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC], type = "u8", size = 6 }
+checksum = { checksum = "sum", type = "u16" }
+"#,
+    )
+    .expect("sum checksum builds");
+
+    let covered = bytes[..6]
+        .iter()
+        .fold(0u16, |acc, &byte| acc.wrapping_add(u16::from(byte)));
+    let stored = u16::from_be_bytes([bytes[6], bytes[7]]);
+    assert_eq!(covered.wrapping_add(stored), 0);
+}
+
+#[test]
+fn non_crc_algorithms_build_through_checksum_leaves() {
+    // Published check values over "123456789"; Fletcher-32 pads the odd byte with zero and
+    // reads little-endian words.
+    let cases = [
+        ("FLETCHER-16", "u16", 0x1EDEu32),
+        ("FLETCHER-32", "u32", 0xDF09_D509),
+        ("ADLER-32", "u32", 0x091E_01DE),
+        ("XOR-8", "u8", 0x31),
+        ("SUM-8", "u8", 0xDD),
+        ("SUM-16", "u16", 0x01DD),
+    ];
+    for (algorithm, storage, expected) in cases {
+        let checksums = format!("[mint.checksum.check]\nalgorithm = \"{algorithm}\"");
+        let data = format!(
+            "payload = {{ value = \"123456789\", type = \"u8\", size = 9 }}\n\
+             checksum = {{ checksum = \"check\", type = \"{storage}\", to = \"payload\" }}"
+        );
+        let bytes = build_bytes("generic-le", &checksums, &data).expect("checksum builds");
+        let size = usize::from(storage[1..].parse::<u8>().expect("storage width") / 8);
+        let offset = 9usize.next_multiple_of(size);
+        assert_eq!(
+            &bytes[offset..offset + size],
+            &expected.to_le_bytes()[..size],
+            "{algorithm} stored value"
+        );
+    }
+}
+
+#[test]
+fn complement_is_only_valid_for_sum_algorithms() {
+    let data = r#"
+value = { value = 1, type = "u32" }
+checksum = { checksum = "crc", type = "u32" }
+"#;
+    for config in [
+        "algorithm = \"ADLER-32\"\ncomplement = \"ones\"",
+        "polynomial = 0x04C11DB7\nstart = 0\nxor_out = 0\nref_in = false\nref_out = false\ncomplement = \"twos\"",
+    ] {
+        let error = build_bytes(
+            "generic-le",
+            &format!("[mint.checksum.crc]\n{config}"),
+            data,
+        )
+        .expect_err("complement should be rejected");
+        assert!(
+            error.contains("'complement' is only valid with SUM algorithms"),
+            "unexpected error: {error}"
+        );
+    }
+}
+
+const CRC32: &str = r#"
+[mint.checksum.crc]
+algorithm = "CRC-32"
+"#;
+
+fn crc32(data: &[u8]) -> [u8; 4] {
+    let config = layout::parse_toml_layout(&checksum_layout("generic-le", CRC32, ""))
+        .expect("layout parses")
+        .mint
+        .checksum
+        .remove("crc")
+        .expect("crc config");
+    calculate_checksum(data, &config.algorithm).to_le_bytes()
+}
+
+#[test]
+fn checksum_from_to_covers_a_sub_struct() {
+    let bytes = build_bytes(
+        "generic-le",
+        CRC32,
+        r#"
+header.magic = { value = 0xA5A5A5A5, type = "u32" }
+params.gain = { value = 1, type = "u16" }
+params.offset = { value = 2, type = "u16" }
+crc = { checksum = "crc", type = "u32", from = "params", to = "params" }
+"#,
+    )
+    .expect("sub-struct checksum builds");
+    assert_eq!(&bytes[8..12], &crc32(&bytes[4..8]));
+}
+
+#[test]
+fn checksum_exclude_skips_fields_inside_coverage() {
+    let bytes = build_bytes(
+        "generic-le",
+        CRC32,
+        r#"
+version = { value = 1, type = "u32" }
+write_count = { value = 7, type = "u32" }
+limit = { value = 3, type = "u32" }
+crc = { checksum = "crc", type = "u32", exclude = ["write_count"] }
+"#,
+    )
+    .expect("checksum with exclusion builds");
+    let covered = [&bytes[0..4], &bytes[8..12]].concat();
+    assert_eq!(&bytes[12..16], &crc32(&covered));
+}
+
+#[test]
+fn chained_checksums_match_one_shot_results() {
+    let bytes = build_bytes(
+        "generic-le",
+        CRC32,
+        r#"
+first = { value = [1, 2, 3, 4], type = "u8", size = 4 }
+crc_a = { checksum = "crc", type = "u32" }
+second = { value = [5, 6, 7, 8], type = "u8", size = 4 }
+crc_b = { checksum = "crc", type = "u32" }
+crc_c = { checksum = "crc", type = "u32", exclude = ["crc_a"] }
+crc_d = { checksum = "crc", type = "u32", from = "second" }
+"#,
+    )
+    .expect("chained checksums build");
+    let covered_c = [&bytes[..4], &bytes[8..16]].concat();
+    assert_eq!(&bytes[4..8], &crc32(&bytes[..4]));
+    assert_eq!(&bytes[12..16], &crc32(&bytes[..12]));
+    assert_eq!(&bytes[16..20], &crc32(&covered_c));
+    assert_eq!(&bytes[20..24], &crc32(&bytes[8..20]));
+}
+
+#[test]
+fn checksum_to_length_covers_tail_padding_and_emits_it() {
+    let bytes = build_bytes(
+        "generic-le",
+        CRC32,
+        r#"
+crc = { checksum = "crc", type = "u32", to_length = true, exclude = ["crc"] }
+payload = { value = [1, 2, 3], type = "u8", size = 3 }
+"#,
+    )
+    .expect("full-length checksum builds");
+    assert_eq!(
+        bytes.len(),
+        0x100,
+        "tail padding is emitted up to header.length"
+    );
+    assert!(bytes[7..].iter().all(|byte| *byte == 0xFF));
+    assert_eq!(&bytes[..4], &crc32(&bytes[4..]));
+}
+
+#[test]
+fn checksum_coverage_errors_are_reported() {
+    let cases = [
+        (
+            r#"
+value = { value = 1, type = "u32" }
+crc = { checksum = "crc", type = "u32", exclude = ["missing"] }
+"#,
+            "coverage 'exclude' path 'missing' not found in block",
+        ),
+        (
+            r#"
+value = { value = 1, type = "u32" }
+crc = { checksum = "crc", type = "u32", to = "value", exclude = ["later"] }
+later = { value = 2, type = "u32" }
+"#,
+            "coverage 'exclude' path 'later' is outside coverage range 0x0..0x4",
+        ),
+        (
+            r#"
+value = { value = 1, type = "u32" }
+crc = { checksum = "crc", type = "u32", exclude = ["value"] }
+"#,
+            "coverage range 0x0..0x4 is empty after exclusions",
+        ),
+        (
+            r#"
+value = { value = 1, type = "u32" }
+crc = { checksum = "crc", type = "u32", to_length = true }
+"#,
+            "coverage range 0x0..0x100 includes the field itself",
+        ),
+        (
+            r#"
+value = { value = 1, type = "u32" }
+crc = { checksum = "crc", type = "u32", to = "value", to_length = true }
+"#,
+            "leaf may contain only one coverage end; found 'to' and 'to_length'",
+        ),
+    ];
+    for (data, expected) in cases {
+        let error = build_bytes("generic-le", CRC32, data)
+            .expect_err("checksum coverage should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+const CRC32_RESIDUE: u32 = 0x2144_DF1C;
+
+#[test]
+fn crc_compensation_forces_the_full_block_crc_to_the_target() {
+    let bytes = build_bytes(
+        "generic-le",
+        CRC32,
+        &format!(
+            r#"
+version = {{ value = 7, type = "u32" }}
+compensation = {{ crc_compensation = {{ checksum = "crc", target = 0x{CRC32_RESIDUE:X} }}, type = "u32", to_length = true }}
+payload = {{ value = [1, 2, 3, 4, 5], type = "u8", size = 5 }}
+"#
+        ),
+    )
+    .expect("compensated block builds");
+    assert_eq!(bytes.len(), 0x100, "to_length coverage emits tail padding");
+    assert_eq!(crc32(&bytes), CRC32_RESIDUE.to_le_bytes());
+}
+
+#[test]
+fn crc_compensation_resolves_before_checksums_that_cover_it() {
+    let bytes = build_bytes(
+        "generic-le",
+        CRC32,
+        r#"
+params.gain = { value = 3, type = "u16" }
+params.fix = { crc_compensation = { checksum = "crc", target = 0 }, type = "u8", size = 4, from = "params", to = "params" }
+params.offset = { value = 9, type = "u16" }
+crc = { checksum = "crc", type = "u32" }
+"#,
+    )
+    .expect("compensation inside checksum coverage builds");
+    assert_eq!(crc32(&bytes[..8]), [0; 4]);
+    assert_eq!(&bytes[8..12], &crc32(&bytes[..8]));
+}
+
+#[test]
+fn crc_compensation_errors_are_reported() {
+    let checksums = r#"
+[mint.checksum.crc]
+algorithm = "CRC-32"
+
+[mint.checksum.crc16]
+algorithm = "CRC-16/XMODEM"
+
+[mint.checksum.sum]
+algorithm = "SUM-8"
+"#;
+    let cases = [
+        (
+            r#"fix = { crc_compensation = { checksum = "sum", target = 0 }, type = "u8" }"#,
+            "CRC compensation requires a CRC; [mint.checksum.sum] is not a CRC config.",
+        ),
+        (
+            r#"fix = { crc_compensation = { checksum = "crc16", target = 0 }, type = "u32" }"#,
+            "CRC compensation for a 16-bit CRC needs 2 bytes; field has 4.",
+        ),
+        (
+            r#"fix = { crc_compensation = { checksum = "crc16", target = 0x10000 }, type = "u16" }"#,
+            "CRC compensation target 0x10000 does not fit the 16-bit CRC width.",
+        ),
+        (
+            r#"fix = { crc_compensation = { checksum = "crc", target = 0 }, type = "i32" }"#,
+            "CRC compensation type must be u8, u16, u32 or a u8 array, got i32.",
+        ),
+        (
+            r#"fix = { crc_compensation = { checksum = "crc", target = 0, seed = 1 }, type = "u32" }"#,
+            "unknown field `seed`",
+        ),
+        (
+            r#"fix = { crc_compensation = { checksum = "crc", target = 0 }, type = "u32", block = "other" }"#,
+            "'block' coverage is not valid with 'crc_compensation'",
+        ),
+        (
+            r#"
+value = { value = 1, type = "u32" }
+fix = { crc_compensation = { checksum = "crc", target = 0 }, type = "u32", exclude = ["fix"] }
+"#,
+            "coverage range 0x0..0x8 must include the compensation field",
+        ),
+        (
+            r#"
+fix = { crc_compensation = { checksum = "crc", target = 0 }, type = "u32" }
+crc = { checksum = "crc", type = "u32", to_length = true, exclude = ["crc"] }
+"#,
+            "integrity field coverage is circular between fields [fix, crc]",
+        ),
+    ];
+    for (data, expected) in cases {
+        let error = build_bytes("generic-le", checksums, data)
+            .expect_err("CRC compensation should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+fn swap_words(data: &[u8], word: usize) -> Vec<u8> {
+    data.chunks(word)
+        .flat_map(|word| word.iter().rev())
+        .copied()
+        .collect()
+}
+
+#[test]
+fn checksum_byte_order_overrides_the_abi() {
+    let checksums = r#"
+[mint.checksum.crc]
+algorithm = "CRC-32"
+byte_order = "big"
+"#;
+    let bytes = build_bytes(
+        "generic-le",
+        checksums,
+        r#"
+payload = { value = [1, 2, 3, 4], type = "u8", size = 4 }
+crc = { checksum = "crc", type = "u32" }
+"#,
+    )
+    .expect("big-endian checksum builds");
+    let mut expected = crc32(&bytes[..4]);
+    expected.reverse();
+    assert_eq!(&bytes[4..8], &expected);
+}
+
+#[test]
+fn checksum_input_words_are_fed_in_word_order() {
+    let checksums = r#"
+[mint.checksum.crc]
+algorithm = "CRC-32"
+input_word_bits = 16
+
+[mint.checksum.words]
+algorithm = "CRC-32"
+input_word_bits = 32
+input_word_order = "little"
+"#;
+    let data = r#"
+payload = { value = [0x1234, 0x5678, 0x9ABC, 0xDEF0], type = "u16", size = 4 }
+crc = { checksum = "crc", type = "u32" }
+same = { checksum = "words", type = "u32", to = "payload" }
+"#;
+    let c28x = build_bytes("ti-c28x-eabi", checksums, data).expect("C28x word CRC builds");
+    assert_eq!(&c28x[..4], &[0x34, 0x12, 0x78, 0x56]);
+    assert_eq!(&c28x[8..12], &crc32(&swap_words(&c28x[..8], 2)));
+    assert_eq!(
+        &c28x[12..16],
+        &crc32(&c28x[..8]),
+        "little-endian words on a little-endian ABI are fed as laid out"
+    );
+
+    let big = build_bytes("generic-be", checksums, data).expect("big-endian word CRC builds");
+    let crc = u32::from_le_bytes(crc32(&big[..8]));
+    assert_eq!(
+        &big[8..12],
+        &crc.to_be_bytes(),
+        "big-endian words need no swap"
+    );
+}
+
+#[test]
+fn crc_compensation_solves_in_input_word_order() {
+    let checksums = r#"
+[mint.checksum.crc]
+algorithm = "CRC-32"
+input_word_bits = 32
+"#;
+    let bytes = build_bytes(
+        "generic-le",
+        checksums,
+        r#"
+version = { value = 7, type = "u32" }
+fix = { crc_compensation = { checksum = "crc", target = 0 }, type = "u32" }
+payload = { value = [1, 2], type = "u32", size = 2 }
+"#,
+    )
+    .expect("word-order compensation builds");
+    assert_eq!(crc32(&swap_words(&bytes, 4)), [0; 4]);
+}
+
+#[test]
+fn checksum_encoding_errors_are_reported() {
+    let data = r#"
+payload = { value = [1, 2, 3], type = "u8", size = 3 }
+crc = { checksum = "crc", type = "u32", to = "payload" }
+"#;
+    let cases = [
+        (
+            "[mint.checksum.crc]\nalgorithm = \"CRC-32\"\ninput_word_bits = 12",
+            "unsupported checksum input_word_bits 12; must be 8, 16, or 32",
+        ),
+        (
+            "[mint.checksum.crc]\nalgorithm = \"CRC-32\"\ninput_word_order = \"big\"",
+            "checksum 'input_word_order' requires 'input_word_bits' of 16 or 32",
+        ),
+        (
+            "[mint.checksum.crc]\nalgorithm = \"CRC-32\"\nbyte_order = \"middle\"",
+            "unknown variant `middle`",
+        ),
+        (
+            "[mint.checksum.crc]\nalgorithm = \"CRC-32\"\ninput_word_bits = 16",
+            "checksum 'crc' reads 16-bit words, but range 0x0..0x3 does not hold whole words",
+        ),
+    ];
+    for (checksums, expected) in cases {
+        let error = build_bytes("generic-le", checksums, data)
+            .expect_err("checksum encoding should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+#[test]
+fn misaligned_input_words_are_reported_without_building() {
+    let cases = [
+        (
+            "[mint.checksum.crc]\nalgorithm = \"CRC-32\"\ninput_word_bits = 16",
+            r#"
+payload = { value = [1, 2, 3], type = "u8", size = 3 }
+crc = { checksum = "crc", type = "u32", to = "payload" }
+"#,
+            "checksum 'crc' reads 16-bit words, but range 0x0..0x3 does not hold whole words",
+        ),
+        (
+            "[mint.checksum.crc]\nalgorithm = \"CRC-16/XMODEM\"\ninput_word_bits = 32",
+            r#"
+version = { value = 7, type = "u16" }
+fix = { crc_compensation = { checksum = "crc", target = 0 }, type = "u16" }
+"#,
+            "checksum 'crc' reads 32-bit words, but range 0x2..0x4 does not hold whole words",
+        ),
+    ];
+    for (checksums, data, expected) in cases {
+        let layout_text = checksum_layout("generic-le", checksums, data);
+        let config = layout::parse_toml_layout(&layout_text).expect("layout parses");
+        let error = mint_core::fingerprint::calculate(&config)
+            .expect_err("fingerprint should reject misaligned words");
+        let error = common::error_chain(&error);
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+
+        let path = common::write_layout_file("checksum-words", &layout_text);
+        let error = mint_core::header::generate(&[BlockSelector::named(&path, "block")])
+            .expect_err("header should reject misaligned words");
+        let error = common::error_chain(&error);
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
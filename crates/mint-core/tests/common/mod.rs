@@ -4,8 +4,12 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 
-use mint_core::build::{self, BlockSelector, BuildRequest};
+use mint_core::build::{
+    self, BlockSelector, BuildArtifact, BuildFromLayoutsRequest, BuildRequest, NamedLayout,
+};
 use mint_core::data::DataSource;
+use mint_core::extensions::Extensions;
+use mint_core::keys::KeyRing;
 
 static UNIQUE_FILE_ID: AtomicU64 = AtomicU64::new(0);
 
@@ -79,6 +83,48 @@ pub fn build_block_with_values(
     Ok((bytestream, values))
 }
 
+/// Inputs of [`build_layout_text`] beyond the layout; the default builds without a data source,
+/// keys or extensions.
+#[derive(Default)]
+pub struct BuildOptions<'a> {
+    pub data_source: Option<&'a dyn DataSource>,
+    pub strict: bool,
+    pub keys: KeyRing,
+    pub extensions: Extensions,
+}
+
+/// Parses `layout_text` as a layout named `name`, so relative key paths resolve from its parent
+/// directory.
+pub fn named_layout(name: &str, layout_text: &str) -> Result<NamedLayout, String> {
+    let config =
+        mint_core::layout::parse_toml_layout(layout_text).map_err(|error| error_chain(&error))?;
+    Ok(NamedLayout {
+        name: PathBuf::from(name),
+        config,
+    })
+}
+
+/// Builds block `block` of `layout_text`, parsed as a layout named `name`, capturing used
+/// values. Errors are rendered with [`error_chain`].
+pub fn build_layout_text(
+    name: &str,
+    layout_text: &str,
+    options: BuildOptions<'_>,
+) -> Result<BuildArtifact, String> {
+    let layout = named_layout(name, layout_text)?;
+    let request = BuildFromLayoutsRequest {
+        layouts: vec![layout],
+        blocks: vec![BlockSelector::named(name, "block")],
+        data_source: options.data_source,
+        strict: options.strict,
+        capture_values: true,
+    };
+    let request = request
+        .with_keys(options.keys)
+        .with_extensions(options.extensions);
+    build::build_from_layouts(request).map_err(|error| error_chain(&error))
+}
+
 /// Renders an error and its full source chain as a single string.
 pub fn error_chain(err: &dyn std::error::Error) -> String {
     let mut message = err.to_string();
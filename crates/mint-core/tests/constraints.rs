@@ -0,0 +1,172 @@
+use mint_core::build::BlockSelector;
+use mint_core::data::JsonDataSource;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const CALIBRATION: &str = r#"
+[mint]
+abi = "generic-le"
+
+[mint.const]
+MAX_GAIN = 200
+
+[mint.enum.mode]
+Off = 0
+Run = 1
+Test = 2
+
+[block.header]
+start_address = 0x1000
+length = 0x40
+padding = 0xFF
+
+[block.data]
+gain = { name = "Gain", type = "u8", min = 10, max = "MAX_GAIN" }
+offsets = { name = "Offsets", type = "i16", size = 3, min = -100, max = 100 }
+mode = { name = "Mode", type = "u8", enum = "mode", allowed = ["Off", "Run"] }
+rate = { value = 4, type = "u8", allowed = [1, 2, 4, 8] }
+voltage = { name = "Voltage", type = "f32", min = 0.5, max = 5.5 }
+flags = { type = "u8", bitmap = [
+    { bits = 4, name = "Level", max = 9 },
+    { bits = 4, value = 0 },
+] }
+"#;
+
+const DATA: &str = r#"{
+    "Default": {
+        "Gain": 120, "Offsets": [-100, 0, 100], "Mode": "Run", "Voltage": 3.3, "Level": 9
+    },
+    "Debug": { "Gain": 250, "Mode": "Test", "Level": 12 }
+}"#;
+
+fn build(layout_text: &str, variants: &[&str]) -> Result<Vec<u8>, String> {
+    let variants = variants
+        .iter()
+        .map(|variant| (*variant).to_owned())
+        .collect::<Vec<_>>();
+    let source = JsonDataSource::from_str(DATA, &variants).expect("JSON data parses");
+    let path = common::write_layout_file("constraints", layout_text);
+    common::build_block(&path, "block", false, Some(&source))
+        .map_err(|error| common::error_chain(&error))
+}
+
+#[test]
+fn values_within_their_limits_build() {
+    let bytes = build(CALIBRATION, &["Default"]).expect("values in range build");
+
+    let mut expected = vec![120, 0xFF];
+    for offset in [-100i16, 0, 100] {
+        expected.extend(offset.to_le_bytes());
+    }
+    expected.extend([1, 4, 0xFF, 0xFF]);
+    expected.extend(3.3f32.to_le_bytes());
+    expected.push(0x09);
+    assert_eq!(&bytes[..expected.len()], expected);
+}
+
+#[test]
+fn violations_name_the_field_data_name_and_variant() {
+    let error = build(CALIBRATION, &["Debug", "Default"]).expect_err("gain is out of range");
+    assert!(
+        error.contains("in field 'gain'")
+            && error.contains("value 250 from 'Gain' in variant 'Debug' is above 'max' 200"),
+        "{error}"
+    );
+
+    let cases = [
+        (
+            CALIBRATION.replace("max = \"MAX_GAIN\"", "max = 255"),
+            "in field 'mode'",
+            "value \"Test\" from 'Mode' in variant 'Debug' is not one of the allowed values [\"Off\", \"Run\"]",
+        ),
+        (
+            CALIBRATION
+                .replace("max = \"MAX_GAIN\"", "max = 255")
+                .replace("allowed = [\"Off\", \"Run\"]", "max = 2"),
+            "in field 'flags'",
+            "value 12 from 'Level' in variant 'Debug' is above 'max' 9",
+        ),
+    ];
+    for (layout_text, field, expected) in cases {
+        let error = build(&layout_text, &["Debug", "Default"]).expect_err("value is rejected");
+        assert!(
+            error.contains(field) && error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+
+    let cases = [
+        (
+            CALIBRATION.replace("min = -100", "min = -50"),
+            "value -100 at index 0 from 'Offsets' in variant 'Default' is below 'min' -50",
+        ),
+        (
+            CALIBRATION.replace("value = 4,", "value = 3,"),
+            "value 3 is not one of the allowed values [1, 2, 4, 8]",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build(&layout_text, &["Default"]).expect_err("value is rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+#[test]
+fn limits_are_validated_with_the_layout() {
+    let cases = [
+        (
+            CALIBRATION.replace("min = 10", "min = 250"),
+            "'min' 250 is greater than 'max' 200",
+        ),
+        (
+            CALIBRATION.replace("min = 0.5", "min = \"low\""),
+            "'low' is not a numeric const",
+        ),
+        (
+            CALIBRATION.replace("min = 0.5", "min = true"),
+            "'min' must be a number, got true",
+        ),
+        (
+            CALIBRATION.replace("allowed = [1, 2, 4, 8]", "allowed = []"),
+            "'allowed' must list at least one value",
+        ),
+        (
+            CALIBRATION.replace(
+                "rate = { value = 4, type = \"u8\", allowed = [1, 2, 4, 8] }",
+                "rate = { checksum = \"crc\", type = \"u32\", max = 4 }",
+            ),
+            "'min', 'max' and 'allowed' are only valid with a 'name', 'value' or 'const' source",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build(&layout_text, &["Default"]).expect_err("limits are rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+#[test]
+fn header_defines_min_and_max_macros() {
+    let path = common::write_layout_file("constraints-header", CALIBRATION);
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&path)]).expect("header generates");
+
+    for expected in [
+        "#define BLOCK_GAIN_MIN 10u\n#define BLOCK_GAIN_MAX 200u\n",
+        "#define BLOCK_OFFSETS_MIN INT64_C(-100)\n#define BLOCK_OFFSETS_MAX 100u\n",
+        "#define BLOCK_VOLTAGE_MIN 0.5\n#define BLOCK_VOLTAGE_MAX 5.5\n",
+        "#define BLOCK_FLAGS_LEVEL_MASK UINT8_C(0x0F)\n#define BLOCK_FLAGS_LEVEL_MAX 9u\n",
+    ] {
+        assert!(
+            header.contains(expected),
+            "missing '{expected}' in {header}"
+        );
+    }
+    assert!(!header.contains("BLOCK_RATE_M") && !header.contains("BLOCK_MODE_M"));
+}
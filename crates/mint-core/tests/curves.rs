@@ -0,0 +1,196 @@
+use mint_core::build::BlockSelector;
+use mint_core::data::JsonDataSource;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const ENGINE: &str = r#"
+[mint]
+abi = "generic-le"
+
+[block.header]
+start_address = 0x1000
+length = 0x40
+padding = 0xFF
+
+[block.data]
+rpm = { axis = "EngineSpeed", type = "u16", size = 4 }
+idle = { curve = "IdleTarget", type = "u16", size = 4, x = { type = "i16" } }
+torque = { curve = "TorqueLimit", type = "i16", size = 4, x = "rpm", scale = { factor = 0.5 } }
+fuel = { map = "FuelMap", type = "u8", size = [2, 4], x = "rpm", y = { type = "u8" }, max = 200 }
+"#;
+
+const DATA: &str = r#"{
+    "Default": {
+        "EngineSpeed": [800, 1500, 3000, 6000],
+        "IdleTarget": { "x": [-20, 0, 40, 90], "values": [1200, 1000, 850, 800] },
+        "TorqueLimit": { "values": [100, 150.5, 200, 180] },
+        "FuelMap": {
+            "x": [800, 1500, 3000, 6000],
+            "y": [20, 80],
+            "values": [[10, 20, 30, 40], [50, 60, 70, 80]]
+        }
+    }
+}"#;
+
+fn build(layout_text: &str, data: &str) -> Result<Vec<u8>, String> {
+    let source = JsonDataSource::from_str(data, &["Default".to_owned()]).expect("JSON data parses");
+    let path = common::write_layout_file("curves", layout_text);
+    common::build_block(&path, "block", false, Some(&source))
+        .map_err(|error| common::error_chain(&error))
+}
+
+#[test]
+fn curves_and_maps_emit_their_axes_and_values() {
+    let bytes = build(ENGINE, DATA).expect("curves build");
+
+    let mut expected = Vec::new();
+    for rpm in [800u16, 1500, 3000, 6000] {
+        expected.extend(rpm.to_le_bytes());
+    }
+    for x in [-20i16, 0, 40, 90] {
+        expected.extend(x.to_le_bytes());
+    }
+    for target in [1200u16, 1000, 850, 800] {
+        expected.extend(target.to_le_bytes());
+    }
+    for raw in [200i16, 301, 400, 360] {
+        expected.extend(raw.to_le_bytes());
+    }
+    expected.extend([20, 80]);
+    expected.extend([10, 20, 30, 40, 50, 60, 70, 80]);
+    assert_eq!(&bytes[..expected.len()], expected);
+}
+
+#[test]
+fn axes_and_dimensions_are_checked_against_the_data() {
+    let cases = [
+        (
+            DATA.replace("[800, 1500, 3000, 6000],\n", "[800, 3000, 1500, 6000],\n"),
+            "in field 'rpm'",
+            "axis 'EngineSpeed' is not strictly increasing: 1500 at index 2 follows 3000",
+        ),
+        (
+            DATA.replace("[1200, 1000, 850, 800]", "[1200, 1000, 850]"),
+            "in field 'idle': in field 'x'",
+            "curve 'IdleTarget' has 3 values, expected 4",
+        ),
+        (
+            DATA.replace("\"x\": [-20, 0, 40, 90], ", ""),
+            "in field 'idle': in field 'x'",
+            "curve 'IdleTarget' has no x axis",
+        ),
+        (
+            DATA.replace("[-20, 0, 40, 90]", "[-20, 0, 0, 90]"),
+            "in field 'idle': in field 'x'",
+            "x axis of curve 'IdleTarget' is not strictly increasing: 0 at index 2 follows 0",
+        ),
+        (
+            DATA.replace(
+                "\"x\": [800, 1500, 3000, 6000]",
+                "\"x\": [800, 1500, 3100, 6000]",
+            ),
+            "in field 'fuel': in field 'y'",
+            "x axis of map 'FuelMap' does not match shared axis 'rpm': 3100 at index 2, expected 3000",
+        ),
+        (
+            DATA.replace("[-20, 0, 40, 90]", "[-20, 0.2, 0.4, 90]"),
+            "in field 'idle': in field 'x'",
+            "x axis of curve 'IdleTarget' is not strictly increasing once stored as i16: 0.4 at index 2 is stored as 0, after 0",
+        ),
+        (
+            DATA.replace(
+                "[800, 1500, 3000, 6000],\n",
+                "[800, 1500, 3000.25, 3000.5],\n",
+            ),
+            "in field 'rpm'",
+            "axis 'EngineSpeed' is not strictly increasing once stored as u16: 3000.5 at index 3 is stored as 3000, after 3000",
+        ),
+        (
+            DATA.replace(", [50, 60, 70, 80]", ""),
+            "in field 'fuel': in field 'y'",
+            "map 'FuelMap' has 1 value rows, expected 2",
+        ),
+        (
+            DATA.replace("[50, 60, 70, 80]", "[50, 60, 70, 250]"),
+            "in field 'fuel': in field 'values'",
+            "value 250 at [1][3] from 'FuelMap' in variant 'Default' is above 'max' 200",
+        ),
+    ];
+    for (data, field, expected) in cases {
+        let error = build(ENGINE, &data).expect_err("table is rejected");
+        assert!(
+            error.contains(field) && error.contains(expected),
+            "expected '{expected}' {field}, got: {error}"
+        );
+    }
+}
+
+#[test]
+fn lookup_tables_are_validated_with_the_layout() {
+    let cases = [
+        (
+            ENGINE.replace("x = \"rpm\", scale", "x = \"idle\", scale"),
+            "in data field 'torque': x axis 'idle' must name an 'axis' field in the same table",
+        ),
+        (
+            ENGINE.replace(
+                "type = \"i16\", size = 4, x = \"rpm\"",
+                "type = \"i16\", size = 5, x = \"rpm\"",
+            ),
+            "x axis 'rpm' has 4 breakpoints, but the table needs 5",
+        ),
+        (
+            ENGINE.replace("size = [2, 4]", "size = 8"),
+            "map 'size' must be [rows, columns], the number of y and x breakpoints",
+        ),
+        (
+            ENGINE.replace(
+                "x = { type = \"i16\" }",
+                "x = { type = \"i16\" }, y = \"rpm\"",
+            ),
+            "'y' is only valid for a map",
+        ),
+        (
+            ENGINE.replace(", y = { type = \"u8\" }", ""),
+            "map requires a 'y' axis",
+        ),
+        (
+            ENGINE.replace("x = { type = \"i16\" }", "x = { type = \"i16\", size = 4 }"),
+            "invalid 'x' axis: unknown field `size`",
+        ),
+        (
+            ENGINE.replace(
+                "[block.data]\n",
+                "[block.data]\nspare = { axis = \"Spare\", type = \"u8\" }\n",
+            ),
+            "Axis requires a one-dimensional size, the number of breakpoints.",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build(&layout_text, DATA).expect_err("layout is rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+#[test]
+fn header_declares_tables_as_structs() {
+    let path = common::write_layout_file("curves-header", ENGINE);
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&path)]).expect("header generates");
+
+    for expected in [
+        "  uint16_t rpm[BLOCK_RPM_LEN];\n",
+        "  struct {\n    int16_t x[BLOCK_IDLE_X_LEN];\n    uint16_t values[BLOCK_IDLE_VALUES_LEN];\n  } idle;\n",
+        "    int16_t values[BLOCK_TORQUE_VALUES_LEN]; /* physical = raw * 0.5 */\n",
+        "    uint8_t values[BLOCK_FUEL_VALUES_ROWS][BLOCK_FUEL_VALUES_COLS];\n",
+    ] {
+        assert!(
+            header.contains(expected),
+            "missing '{expected}' in {header}"
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use mint_core::build::BuildArtifact;
+use mint_core::layout;
+use mint_core::layout::settings::DigestAlgorithm;
+use mint_core::output::checksum::calculate_checksum;
+use mint_core::output::digest::calculate_digest;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const CRC32: &str = r#"
+[mint.checksum.crc32]
+algorithm = "CRC-32"
+"#;
+
+fn digest_layout(data: &str) -> String {
+    format!(
+        r#"
+[mint]
+abi = "generic-le"
+{CRC32}
+[block.header]
+start_address = 0x1000
+length = 0x200
+
+[block.data]
+{data}
+"#
+    )
+}
+
+fn build_artifact(data: &str) -> Result<BuildArtifact, String> {
+    common::build_layout_text("digest.toml", &digest_layout(data), Default::default())
+}
+
+fn build_bytes(data: &str) -> Result<Vec<u8>, String> {
+    build_artifact(data).map(|artifact| artifact.ranges[0].bytestream.clone())
+}
+
+#[test]
+fn digests_cover_preceding_bytes_by_default() {
+    for (algorithm, name, length) in [
+        (DigestAlgorithm::Sha256, "sha256", 32usize),
+        (DigestAlgorithm::Sha512, "sha512", 64),
+        (DigestAlgorithm::Blake3, "blake3", 32),
+    ] {
+        let bytes = build_bytes(&format!(
+            "payload = {{ value = \"parameters\", type = \"u8\", size = 10 }}\n\
+             hash = {{ digest = \"{name}\", type = \"u8\", size = {length} }}"
+        ))
+        .expect("digest builds");
+
+        assert_eq!(
+            &bytes[10..10 + length],
+            calculate_digest(&bytes[..10], algorithm).as_slice(),
+            "{name} digest"
+        );
+    }
+}
+
+#[test]
+fn digest_before_payload_covers_configured_range_and_trailing_checksum_sees_it() {
+    let artifact = build_artifact(
+        r#"
+hash = { digest = "sha256", type = "u8", size = 32, from = "payload", to = "payload" }
+payload = { value = [1, 2, 3, 4, 5, 6, 7, 8], type = "u32", size = 8 }
+crc = { checksum = "crc32", type = "u32" }
+"#,
+    )
+    .expect("digest builds");
+    let bytes = &artifact.ranges[0].bytestream;
+    let crc_config =
+        layout::parse_toml_layout(&digest_layout("value = { value = 1, type = \"u8\" }"))
+            .expect("layout parses")
+            .mint
+            .checksum
+            .remove("crc32")
+            .expect("crc32 config");
+
+    let digest = calculate_digest(&bytes[32..64], DigestAlgorithm::Sha256);
+    assert_eq!(&bytes[..32], digest.as_slice());
+    let crc = calculate_checksum(&bytes[..64], &crc_config.algorithm);
+    assert_eq!(&bytes[64..68], &crc.to_le_bytes());
+
+    let used = artifact.used_values.expect("used values captured");
+    let hex = digest
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    assert_eq!(
+        used["digest.toml"]["block"]["hash"],
+        serde_json::Value::String(hex)
+    );
+}
+
+#[test]
+fn digest_storage_must_be_u8_array_of_digest_length() {
+    let cases = [
+        (
+            "hash = { digest = \"sha256\", type = \"u8\", size = 16 }",
+            "Digest 'sha256' produces 32 bytes; size must be 32.",
+        ),
+        (
+            "hash = { digest = \"sha512\", type = \"u8\" }",
+            "Digest 'sha512' produces 64 bytes; size must be 64.",
+        ),
+        (
+            "hash = { digest = \"blake3\", type = \"u16\", size = 16 }",
+            "Digest type must be u8, got u16.",
+        ),
+        (
+            "hash = { digest = \"md5\", type = \"u8\", size = 16 }",
+            "unknown variant `md5`",
+        ),
+    ];
+    for (field, expected) in cases {
+        let error = build_bytes(&format!("value = {{ value = 1, type = \"u32\" }}\n{field}"))
+            .expect_err("digest storage should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+#[test]
+fn coverage_keys_are_validated_against_resolved_layout() {
+    let cases = [
+        (
+            r#"
+value = { value = 1, type = "u32", from = "value" }
+"#,
+            "'block') are only valid with a 'checksum', 'digest', 'signature', 'hmac' or 'crc_compensation' source",
+        ),
+        (
+            r#"
+value = { value = 1, type = "u32" }
+hash = { digest = "sha256", type = "u8", size = 32, from = "missing" }
+"#,
+            "coverage 'from' path 'missing' not found in block",
+        ),
+        (
+            r#"
+first = { value = 1, type = "u32" }
+second = { value = 2, type = "u32" }
+hash = { digest = "sha256", type = "u8", size = 32, from = "second", to = "first" }
+"#,
+            "coverage range 0x4..0x4 is empty",
+        ),
+        (
+            r#"
+value = { value = 1, type = "u32" }
+hash = { digest = "sha256", type = "u8", size = 32, to = "hash" }
+"#,
+            "includes the field itself",
+        ),
+        (
+            r#"
+hash = { digest = "sha256", type = "u8", size = 32, from = "payload", to = "crc" }
+payload = { value = 1, type = "u32" }
+crc = { checksum = "crc32", type = "u32" }
+"#,
+            "integrity field coverage is circular between fields [hash, crc]",
+        ),
+    ];
+    for (data, expected) in cases {
+        let error = build_bytes(data).expect_err("coverage should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use aes::Aes256;
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use ed25519_dalek::Verifier as _;
+use ed25519_dalek::pkcs8::DecodePrivateKey as _;
+use mint_core::build::{self, NamedLayout};
+use mint_core::keys::{KeyRing, KeySource};
+use std::path::PathBuf;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const AES_256_KEY: &[u8] = b"mint-test-aes-256-key-0123456789";
+
+const DATA: &str = r#"
+first = { value = 1, type = "u32" }
+second = { value = [2, 3, 4], type = "u32", size = 3 }
+crc = { checksum = "crc", type = "u32" }
+"#;
+
+fn encrypted_layout(header: &str, encryption: &str, data: &str) -> String {
+    format!(
+        r#"
+[mint]
+abi = "generic-le"
+
+[mint.checksum.crc]
+algorithm = "CRC-32"
+
+[mint.encryption.fw]
+{encryption}
+
+[block.header]
+start_address = 0x1000
+length = 0x100
+{header}
+
+[block.data]
+{data}
+"#
+    )
+}
+
+/// Names the layout as if it lived in `tests/data`, so `key_file = "keys/..."` resolves to the
+/// fixture keys.
+fn named_layout(layout_text: &str) -> Result<NamedLayout, String> {
+    common::named_layout("tests/data/encryption.toml", layout_text)
+}
+
+fn build_artifact(layout_text: &str, keys: KeyRing) -> Result<build::BuildArtifact, String> {
+    let options = common::BuildOptions {
+        keys,
+        ..Default::default()
+    };
+    common::build_layout_text("tests/data/encryption.toml", layout_text, options)
+}
+
+fn build_bytes(layout_text: &str) -> Vec<u8> {
+    build_artifact(layout_text, KeyRing::new())
+        .expect("block builds")
+        .ranges
+        .remove(0)
+        .bytestream
+}
+
+fn decrypt(layout_text: &str, image: &[u8]) -> Result<Vec<u8>, String> {
+    let layout = named_layout(layout_text)?;
+    build::decrypt_block(&layout, "block", image, &KeyRing::new())
+        .map_err(|error| common::error_chain(&error))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[test]
+fn gcm_block_round_trips_with_trailer() {
+    let plaintext = build_bytes(&encrypted_layout(
+        "",
+        "algorithm = \"aes-128-gcm\"\nkey_file = \"keys/aes128.key\"\nkey_encoding = \"hex\"",
+        DATA,
+    ));
+    let layout_text = encrypted_layout(
+        "encryption = \"fw\"",
+        "algorithm = \"aes-128-gcm\"\nkey_file = \"keys/aes128.key\"\nkey_encoding = \"hex\"",
+        DATA,
+    );
+    let image = build_bytes(&layout_text);
+
+    assert_eq!(plaintext.len(), 20);
+    assert_eq!(
+        image.len(),
+        20 + 12 + 16,
+        "data, then nonce and tag trailer"
+    );
+    assert_ne!(&image[..20], plaintext.as_slice());
+    assert_eq!(
+        build_bytes(&layout_text),
+        image,
+        "encryption is deterministic"
+    );
+
+    assert_eq!(decrypt(&layout_text, &image).expect("decrypts"), plaintext);
+    let mut padded = image.clone();
+    padded.resize(0x100, 0xFF);
+    assert_eq!(decrypt(&layout_text, &padded).expect("decrypts"), plaintext);
+
+    let mut tampered = image;
+    tampered[3] ^= 1;
+    let error = decrypt(&layout_text, &tampered).expect_err("tampering is detected");
+    assert!(
+        error.contains("while decrypting block 'block'")
+            && error.contains("[mint.encryption.fw]: authentication tag mismatch"),
+        "got: {error}"
+    );
+}
+
+#[test]
+fn ctr_nonce_field_stays_plaintext_and_is_reported() {
+    let data = format!("{DATA}nonce = {{ encryption_nonce = \"fw\", type = \"u8\", size = 16 }}");
+    let encryption = "algorithm = \"aes-256-ctr\"\nkey_file = \"keys/aes256.key\"";
+    let plaintext = build_bytes(&encrypted_layout("", encryption, DATA));
+    let layout_text = encrypted_layout("encryption = \"fw\"", encryption, &data);
+    let artifact = build_artifact(&layout_text, KeyRing::new()).expect("block builds");
+    let image = &artifact.ranges[0].bytestream;
+
+    assert_eq!(image.len(), 36, "the nonce field replaces the trailer");
+    let nonce = &image[20..36];
+    assert_ne!(nonce, &[0; 16]);
+    let mut expected = plaintext.clone();
+    ctr::Ctr128BE::<Aes256>::new(AES_256_KEY.into(), nonce.into()).apply_keystream(&mut expected);
+    assert_eq!(&image[..20], expected.as_slice());
+
+    let used = artifact.used_values.expect("used values captured");
+    assert_eq!(
+        used["tests/data/encryption.toml"]["block"]["nonce"],
+        serde_json::Value::String(hex(nonce))
+    );
+    let report = used.to_string();
+    assert!(
+        !report.contains("mint-test-aes-256-key"),
+        "report: {report}"
+    );
+
+    let decrypted = decrypt(&layout_text, image).expect("decrypts");
+    assert_eq!(&decrypted[..20], plaintext.as_slice());
+    assert_eq!(&decrypted[20..], &[0; 16], "nonce field reads as zero");
+}
+
+#[test]
+fn gcm_tag_field_round_trips_and_signature_covers_ciphertext() {
+    let data = format!("tag = {{ encryption_tag = \"fw\", type = \"u8\", size = 16 }}\n{DATA}");
+    let layout_text = encrypted_layout(
+        "encryption = \"fw\"\nsignature = \"boot\"",
+        "algorithm = \"aes-256-gcm\"\nkey_env = \"MINT_TEST_UNSET_AES_KEY\"\n\n[mint.signature.boot]\nalgorithm = \"ed25519\"\nkey_file = \"keys/ed25519.pem\"",
+        &data,
+    );
+    let mut keys = KeyRing::new();
+    keys.insert(
+        "fw",
+        KeySource::File(PathBuf::from("tests/data/keys/aes256.key")),
+    );
+    let image = build_artifact(&layout_text, keys.clone())
+        .expect("key ring supplies the key")
+        .ranges
+        .remove(0)
+        .bytestream;
+    assert_eq!(
+        image.len(),
+        16 + 20 + 12 + 64,
+        "tag field, data, nonce, signature"
+    );
+    assert_ne!(&image[..16], &[0; 16]);
+
+    let pem = std::fs::read_to_string("tests/data/keys/ed25519.pem").expect("read signing key");
+    let signing_key = ed25519_dalek::SigningKey::from_pkcs8_pem(&pem).expect("parse signing key");
+    let signature = ed25519_dalek::Signature::from_slice(&image[48..]).expect("signature");
+    signing_key
+        .verifying_key()
+        .verify(&image[..48], &signature)
+        .expect("signature covers the ciphertext, tag and nonce");
+
+    let layout = named_layout(&layout_text).expect("layout parses");
+    let plaintext = build::decrypt_block(&layout, "block", &image, &keys).expect("decrypts");
+    assert_eq!(plaintext.len(), 36, "trailers are dropped");
+    assert_eq!(&plaintext[..16], &[0; 16], "tag field reads as zero");
+    assert_eq!(&plaintext[16..20], &1u32.to_le_bytes());
+}
+
+#[test]
+fn encryption_configuration_errors_are_reported() {
+    let gcm = "algorithm = \"aes-128-gcm\"\nkey_file = \"keys/aes128.key\"\nkey_encoding = \"hex\"";
+    let ctr = "algorithm = \"aes-128-ctr\"\nkey_file = \"keys/aes128.key\"\nkey_encoding = \"hex\"";
+    let header = "encryption = \"fw\"";
+    let cases = [
+        (
+            encrypted_layout(header, "algorithm = \"aes-128-gcm\"", DATA),
+            "no key for [mint.encryption.fw]; set 'key_file' or 'key_env' in the layout or pass --key fw=PATH",
+        ),
+        (
+            encrypted_layout(
+                header,
+                "algorithm = \"aes-128-gcm\"\nkey_file = \"keys/aes128.key\"\nkey_encoding = \"hex\"\nkey_env = \"KEY\"",
+                DATA,
+            ),
+            "encryption config may set only one of 'key_file' and 'key_env'",
+        ),
+        (
+            encrypted_layout(
+                header,
+                "algorithm = \"aes-128-gcm\"\nkey_file = \"keys/hmac.key\"",
+                DATA,
+            ),
+            "[mint.encryption.fw]: aes-128-gcm needs a 16-byte key; got 18 bytes",
+        ),
+        (
+            encrypted_layout(
+                header,
+                "algorithm = \"aes-128-gcm\"\nkey_file = \"keys/aes128.key\"",
+                DATA,
+            ),
+            "[mint.encryption.fw]: aes-128-gcm needs a 16-byte key; got 32 bytes",
+        ),
+        (
+            encrypted_layout(
+                header,
+                "algorithm = \"aes-128-gcm\"\nkey_file = \"keys/hmac.key\"\nkey_encoding = \"hex\"",
+                DATA,
+            ),
+            "[mint.encryption.fw]: key_encoding is 'hex' but the key is not an even number of hex digits",
+        ),
+        (
+            encrypted_layout(header, "algorithm = \"aes-192-gcm\"", DATA),
+            "unknown variant `aes-192-gcm`",
+        ),
+        (
+            encrypted_layout("encryption = \"other\"", gcm, DATA),
+            "Encryption config 'other' not found in [mint.encryption]. Available: [fw]",
+        ),
+        (
+            encrypted_layout(
+                "",
+                gcm,
+                &format!("{DATA}nonce = {{ encryption_nonce = \"fw\", type = \"u8\", size = 12 }}"),
+            ),
+            "'encryption_nonce' requires header 'encryption = \"fw\"'.",
+        ),
+        (
+            encrypted_layout(
+                header,
+                gcm,
+                &format!("{DATA}nonce = {{ encryption_nonce = \"fw\", type = \"u8\", size = 16 }}"),
+            ),
+            "Nonce for 'aes-128-gcm' produces 12 bytes; size must be 12.",
+        ),
+        (
+            encrypted_layout(
+                header,
+                ctr,
+                &format!("{DATA}tag = {{ encryption_tag = \"fw\", type = \"u8\", size = 16 }}"),
+            ),
+            "'aes-128-ctr' is unauthenticated and produces no tag.",
+        ),
+        (
+            encrypted_layout(
+                header,
+                gcm,
+                &format!(
+                    "{DATA}a = {{ encryption_tag = \"fw\", type = \"u8\", size = 16 }}\nb = {{ encryption_tag = \"fw\", type = \"u8\", size = 16 }}"
+                ),
+            ),
+            "block may contain only one 'encryption_tag' field; found [a, b]",
+        ),
+        (
+            encrypted_layout(
+                header,
+                gcm,
+                "payload = { value = 0, type = \"u8\", size = 0xF0 }",
+            ),
+            "resolved layout size (240 octets) plus the 'fw' encryption trailer (28 octets) exceeds configured block length (256 octets)",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build_artifact(&layout_text, KeyRing::new())
+            .expect_err("encryption configuration should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+
+    let plain = encrypted_layout("", gcm, DATA);
+    let error = decrypt(&plain, &build_bytes(&plain)).expect_err("block is not encrypted");
+    assert!(
+        error.contains("block header has no 'encryption' config to decrypt with"),
+        "got: {error}"
+    );
+    let layout_text = encrypted_layout(header, gcm, DATA);
+    let error = decrypt(&layout_text, &[0; 40]).expect_err("image is truncated");
+    assert!(
+        error.contains("encrypted image is 40 octets, but the block emits 48"),
+        "got: {error}"
+    );
+}
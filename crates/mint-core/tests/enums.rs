@@ -0,0 +1,142 @@
+use mint_core::build::{BlockSelector, BuildArtifact};
+use mint_core::data::{DataSource, JsonDataSource};
+
+#[path = "common/mod.rs"]
+mod common;
+
+const REGIONS: &str = r#"
+[mint]
+abi = "generic-le"
+
+[mint.enum.region]
+America = 1
+Asia = 2
+Europe = 3
+
+[mint.enum.mode]
+Off = 0
+Standby = 0x10
+Run = -1
+
+[mint.const]
+default_mode = "Standby"
+
+[block.header]
+start_address = 0x1000
+length = 0x100
+
+[block.data]
+region = { name = "Region", type = "u8", enum = "region" }
+fallback = { value = "Asia", type = "u8", enum = "region" }
+mode = { const = "default_mode", type = "i16", enum = "mode" }
+route = { name = "Route", type = "u8", size = 4, enum = "region" }
+"#;
+
+fn build_artifact(
+    layout_text: &str,
+    data_source: Option<&dyn DataSource>,
+) -> Result<BuildArtifact, String> {
+    let options = common::BuildOptions {
+        data_source,
+        ..Default::default()
+    };
+    common::build_layout_text("enums.toml", layout_text, options)
+}
+
+fn json(text: &str) -> JsonDataSource {
+    JsonDataSource::from_str(text, &["Default".to_owned()]).expect("JSON data parses")
+}
+
+#[test]
+fn symbols_and_values_encode_as_integers_and_report_as_symbols() {
+    let source = json(r#"{ "Default": { "Region": "Europe", "Route": ["Asia", 1, "Europe"] } }"#);
+    let artifact = build_artifact(REGIONS, Some(&source)).expect("enum layout builds");
+
+    assert_eq!(
+        artifact.ranges[0].bytestream,
+        [3, 2, 0x10, 0, 2, 1, 3, 0xFF]
+    );
+    let used = artifact.used_values.expect("used values captured");
+    assert_eq!(
+        used["enums.toml"]["block"],
+        serde_json::json!({
+            "region": "Europe",
+            "fallback": "Asia",
+            "mode": "Standby",
+            "route": ["Asia", "America", "Europe"],
+        })
+    );
+}
+
+#[test]
+fn header_declares_used_enums_and_annotates_fields() {
+    let path = common::write_layout_file(
+        "enums-header",
+        &REGIONS
+            .replace("Run = -1", "Run = -1\nBurst = 0x10000")
+            .replace("type = \"i16\"", "type = \"i32\""),
+    );
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&path)]).expect("header generates");
+
+    assert!(
+        header.contains(
+            "enum region {\n  REGION_AMERICA = 1,\n  REGION_ASIA = 2,\n  REGION_EUROPE = 3\n};"
+        ),
+        "{header}"
+    );
+    assert!(
+        header.contains(
+            "/* enum mode */\n#define MODE_OFF INT64_C(0)\n#define MODE_STANDBY INT64_C(16)\n#define MODE_RUN INT64_C(-1)\n#define MODE_BURST INT64_C(65536)\n"
+        ),
+        "values beyond the portable int range fall back to macros"
+    );
+    assert!(header.contains("  uint8_t region; /* enum region */"));
+    assert!(header.contains("  uint8_t route[BLOCK_ROUTE_LEN]; /* enum region */"));
+}
+
+#[test]
+fn enum_errors_are_reported() {
+    let data = json(r#"{ "Default": { "Region": "Europe", "Route": [1] } }"#);
+    let cases = [
+        (
+            REGIONS.replace("value = \"Asia\"", "value = \"Africa\""),
+            "'Africa' is not a symbol of enum 'region'. Symbols: [America, Asia, Europe]",
+        ),
+        (
+            REGIONS.replace("value = \"Asia\"", "value = 7"),
+            "7 is not a value of enum 'region'",
+        ),
+        (
+            REGIONS.replace("enum = \"mode\"", "enum = \"missing\""),
+            "Enum 'missing' not found in [mint.enum]. Available: [region, mode]",
+        ),
+        (
+            REGIONS.replace("type = \"i16\", enum", "type = \"f32\", enum"),
+            "Enum requires integer storage type, got f32.",
+        ),
+        (
+            REGIONS.replace("type = \"i16\", enum", "type = \"u16\", enum"),
+            "Enum 'mode' value Run = -1 does not fit type u16.",
+        ),
+        (
+            REGIONS.replace("Asia = 2", "Asia = 1"),
+            "enum 'region' maps both 'America' and 'Asia' to 1",
+        ),
+        (
+            REGIONS.replace(
+                "fallback = { value = \"Asia\", type = \"u8\"",
+                "fallback = { fingerprint = true, type = \"u64\"",
+            ),
+            "'enum' is only valid with a 'name', 'value' or 'const' source.",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build_artifact(&layout_text, Some(&data))
+            .expect_err("enum configuration should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use mint_core::build::BlockSelector;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const CHANNELS: &str = r#"
+[mint]
+abi = "generic-le"
+
+[mint.const]
+MAX_CHANNELS = 3
+NUM_BYTES = "MAX_CHANNELS * 4"
+GAIN_STEP = { expr = "1.0 / (1 << 3)" }
+APP_END = { expr = "app.start_address + app.length" }
+label = "AB"
+build_date = "2024-01-01"
+serial = "0042"
+
+[mint.enum.region]
+Asia = 2
+
+[app.header]
+start_address = 0x8000
+length = 0x1000
+
+[app.data]
+marker = { value = 1, type = "u8" }
+
+[block.header]
+start_address = 0x1000
+length = 0x40
+padding = 0xFF
+
+[block.data]
+gains = { value = ["GAIN_STEP", "GAIN_STEP * 2", 0.5], type = "f32", size = "MAX_CHANNELS" }
+bytes = { value = "NUM_BYTES - 1", type = "u16" }
+end = { value = "app.start_address + app.length", type = "u32" }
+mask = { value = "~0 & 0xF0 | 0b1", type = "u8" }
+end_const = { const = "APP_END", type = "u32" }
+label = { const = "label", type = "u8", size = 2 }
+build_date = { const = "build_date", type = "u8", size = 10 }
+serial = { const = "serial", type = "u8", size = 4 }
+regions = { value = ["Asia", 2], type = "u8", size = 2, enum = "region" }
+"#;
+
+fn build(name: &str, layout_text: &str) -> Result<Vec<u8>, String> {
+    let path = common::write_layout_file(name, layout_text);
+    common::build_block(&path, "block", false, None).map_err(|error| common::error_chain(&error))
+}
+
+#[test]
+fn expressions_evaluate_in_consts_sizes_and_values() {
+    let bytes = build("expressions", CHANNELS).expect("expressions build");
+
+    let mut expected = Vec::new();
+    for gain in [0.125f32, 0.25, 0.5] {
+        expected.extend(gain.to_le_bytes());
+    }
+    expected.extend(11u16.to_le_bytes());
+    expected.extend([0xFF, 0xFF]);
+    expected.extend(0x9000u32.to_le_bytes());
+    expected.push(0xF1);
+    expected.extend([0xFF, 0xFF, 0xFF]);
+    expected.extend(0x9000u32.to_le_bytes());
+    expected.extend(*b"AB");
+    expected.extend(*b"2024-01-01");
+    expected.extend(*b"0042");
+    expected.extend([2, 2, 0xFF, 0xFF]);
+    assert_eq!(bytes, expected);
+}
+
+#[test]
+fn expression_errors_name_the_field_and_the_cause() {
+    let cases = [
+        (
+            CHANNELS.replace(
+                "MAX_CHANNELS = 3",
+                "MAX_CHANNELS = { expr = \"NUM_BYTES / 4\" }",
+            ),
+            "const expression cycle: MAX_CHANNELS -> NUM_BYTES -> MAX_CHANNELS",
+        ),
+        (
+            CHANNELS.replace("\"NUM_BYTES - 1\"", "\"NUM_BYTES / (MAX_CHANNELS - 3)\""),
+            "division by zero",
+        ),
+        (
+            CHANNELS.replace("\"MAX_CHANNELS * 4\"", "\"MAX_CHANNELS / 0\""),
+            "[mint.const] 'NUM_BYTES' expression is invalid: division by zero",
+        ),
+        (
+            CHANNELS.replace("\"NUM_BYTES - 1\"", "\"0x7FFFFFFFFFFFFFFF + NUM_BYTES\""),
+            "result overflows a 64-bit signed integer",
+        ),
+        (
+            CHANNELS.replace("size = \"MAX_CHANNELS\"", "size = \"GAIN_STEP\""),
+            "a size must be a non-negative integer",
+        ),
+        (
+            CHANNELS.replace("\"NUM_BYTES - 1\"", "\"GAIN_STEP & 1\""),
+            "'&' needs integer operands",
+        ),
+        (
+            CHANNELS.replace("\"NUM_BYTES - 1\"", "\"MISSING + 1\""),
+            "'MISSING' is not a numeric const",
+        ),
+        (
+            CHANNELS.replace("label = \"AB\"", "label = { expr = \"AB\" }"),
+            "[mint.const] 'label' expression is invalid: 'AB' is not a numeric const",
+        ),
+        (
+            CHANNELS.replace("MAX_CHANNELS = 3", "MAX_CHANNELS = { expr = 3 }"),
+            "a const table must be { expr = \"...\" }",
+        ),
+        (
+            CHANNELS.replace("\"NUM_BYTES - 1\"", "\"NUM_BYTES +\""),
+            "'value' expression \"NUM_BYTES +\" in 'block.data.bytes' is invalid",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build("expression-errors", &layout_text)
+            .expect_err(&format!("expression is rejected: {expected}"));
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+#[test]
+fn plain_const_strings_keep_their_text() {
+    let (_, used) =
+        common::build_block_with_values(common::write_layout_file("const-text", CHANNELS), "block")
+            .expect("string consts build");
+
+    assert_eq!(used["build_date"], "2024-01-01");
+    assert_eq!(used["serial"], "0042");
+
+    let scalar = CHANNELS.replace(
+        "serial = { const = \"serial\", type = \"u8\", size = 4 }",
+        "serial = { const = \"serial\", type = \"u16\" }",
+    );
+    let error = build("const-text-scalar", &scalar).expect_err("text const is not a number");
+    assert!(
+        error.contains("in field 'serial'")
+            && error.contains("Cannot convert string to scalar type."),
+        "{error}"
+    );
+}
+
+#[test]
+fn header_defines_numeric_consts() {
+    let path = common::write_layout_file("expression-header", CHANNELS);
+    let header = mint_core::header::generate(&[BlockSelector::named(&path, "block")])
+        .expect("header generates");
+
+    assert!(
+        header.contains(
+            "#define APP_END 36864u\n#define GAIN_STEP 0.125\n#define MAX_CHANNELS 3u\n#define NUM_BYTES 12u\n\n"
+        ),
+        "{header}"
+    );
+    assert!(!header.contains("#define LABEL") && !header.contains("APP_START_ADDRESS"));
+    assert!(header.contains("#define BLOCK_GAINS_LEN 3u"));
+}
@@ -0,0 +1,213 @@
+use mint_core::build::{BlockSelector, BuildArtifact};
+use mint_core::extensions::{Extensions, IntegrityAlgorithm, OutputWriter};
+use mint_core::layout;
+use mint_core::output::error::OutputError;
+
+#[path = "common/mod.rs"]
+mod common;
+
+/// Position-sensitive byte hash, so reordered input changes the result.
+struct CountedSum {
+    width: u8,
+}
+
+impl IntegrityAlgorithm for CountedSum {
+    fn width(&self) -> u8 {
+        self.width
+    }
+
+    fn checksum(&self, data: &[u8]) -> u32 {
+        data.iter()
+            .enumerate()
+            .fold(0u32, |acc, (index, byte)| {
+                acc.wrapping_mul(31)
+                    .wrapping_add(u32::from(*byte))
+                    .wrapping_add(index as u32)
+            })
+            .wrapping_add(0xA5A5_0000)
+    }
+}
+
+/// Length-prefixed concatenation of every range.
+struct Container;
+
+impl OutputWriter for Container {
+    fn write(&self, artifact: &BuildArtifact) -> Result<Vec<u8>, OutputError> {
+        let mut out = Vec::new();
+        for range in &artifact.ranges {
+            let length = u32::try_from(range.bytestream.len())
+                .map_err(|_| OutputError::WriterError("range exceeds 4 GiB".to_owned()))?;
+            out.extend_from_slice(&range.start_address.to_le_bytes());
+            out.extend_from_slice(&length.to_le_bytes());
+            out.extend_from_slice(&range.bytestream);
+        }
+        Ok(out)
+    }
+}
+
+fn checksum_layout(checksums: &str, data: &str) -> String {
+    format!(
+        r#"
+[mint]
+abi = "generic-le"
+
+{checksums}
+
+[block.header]
+start_address = 0x1000
+length = 0x100
+
+[block.data]
+{data}
+"#
+    )
+}
+
+fn build_artifact(layout_text: &str, extensions: Extensions) -> Result<BuildArtifact, String> {
+    let options = common::BuildOptions {
+        extensions,
+        ..Default::default()
+    };
+    common::build_layout_text("extensions.toml", layout_text, options)
+}
+
+fn acme(width: u8) -> Extensions {
+    let mut extensions = Extensions::new();
+    extensions.register_checksum("acme", CountedSum { width });
+    extensions
+}
+
+#[test]
+fn custom_checksum_is_computed_by_the_registered_algorithm() {
+    let artifact = build_artifact(
+        &checksum_layout(
+            "[mint.checksum.acme]\ncustom = \"acme\"\n\n[mint.checksum.half]\ncustom = \"acme16\"\nbyte_order = \"big\"",
+            r#"
+payload = { value = [1, 2, 3, 4], type = "u8", size = 4 }
+sum = { checksum = "acme", type = "u32", to = "payload" }
+half = { checksum = "half", type = "u16", to = "payload" }
+"#,
+        ),
+        {
+            let mut extensions = acme(32);
+            extensions.register_checksum("acme16", CountedSum { width: 16 });
+            extensions
+        },
+    )
+    .expect("custom checksum builds");
+    let bytes = &artifact.ranges[0].bytestream;
+    let expected = CountedSum { width: 32 }.checksum(&bytes[..4]);
+
+    assert_eq!(&bytes[4..8], &expected.to_le_bytes());
+    assert_eq!(
+        &bytes[8..10],
+        &((expected & 0xFFFF) as u16).to_be_bytes(),
+        "a 16-bit algorithm has its upper bits discarded and honours byte_order"
+    );
+}
+
+#[test]
+fn custom_checksum_sees_input_words_in_word_order() {
+    let artifact = build_artifact(
+        &checksum_layout(
+            "[mint.checksum.acme]\ncustom = \"acme\"\ninput_word_bits = 16\ninput_word_order = \"big\"",
+            r#"
+payload = { value = [0x1234, 0x5678], type = "u16", size = 2 }
+sum = { checksum = "acme", type = "u32", to = "payload" }
+"#,
+        ),
+        acme(32),
+    )
+    .expect("custom checksum builds");
+    let bytes = &artifact.ranges[0].bytestream;
+
+    assert_eq!(&bytes[..4], &[0x34, 0x12, 0x78, 0x56]);
+    assert_eq!(
+        &bytes[4..8],
+        &CountedSum { width: 32 }
+            .checksum(&[0x12, 0x34, 0x56, 0x78])
+            .to_le_bytes()
+    );
+}
+
+#[test]
+fn custom_output_writer_renders_the_artifact() {
+    let mut extensions = Extensions::new();
+    extensions.register_writer("container", Container);
+    let artifact = build_artifact(
+        &checksum_layout("", "version = { value = 0x0102, type = \"u16\" }"),
+        extensions,
+    )
+    .expect("block builds");
+
+    assert_eq!(
+        artifact.write("container").expect("writer is registered"),
+        [0x00, 0x10, 0, 0, 2, 0, 0, 0, 0x02, 0x01]
+    );
+    let error = artifact
+        .write("elf")
+        .expect_err("unknown writer should be rejected");
+    assert!(
+        error
+            .to_string()
+            .contains("output writer 'elf' is not registered. Registered: [container]"),
+        "got: {error}"
+    );
+}
+
+#[test]
+fn custom_checksum_errors_are_reported() {
+    let data =
+        "payload = { value = 1, type = \"u32\" }\nsum = { checksum = \"acme\", type = \"u32\" }";
+    let cases = [
+        (
+            checksum_layout("[mint.checksum.acme]\ncustom = \"acme\"", data),
+            Extensions::new(),
+            "[mint.checksum.acme] uses custom algorithm 'acme', which is not registered on the build request",
+        ),
+        (
+            checksum_layout(
+                "[mint.checksum.acme]\ncustom = \"acme\"\nalgorithm = \"CRC-32\"\nxor_out = 0",
+                data,
+            ),
+            acme(32),
+            "checksum 'custom' cannot be combined with other algorithm keys; found 'algorithm', 'xor_out'",
+        ),
+        (
+            checksum_layout("[mint.checksum.acme]\ncustom = \"acme\"", data),
+            acme(24),
+            "custom checksum algorithm 'acme' has unsupported width 24; must be 8, 16, or 32",
+        ),
+        (
+            checksum_layout(
+                "[mint.checksum.acme]\ncustom = \"acme\"",
+                "payload = { value = 1, type = \"u32\" }\nsum = { checksum = \"acme\", type = \"u16\" }",
+            ),
+            acme(32),
+            "Checksum type u16 (16 bits) does not match the 32-bit checksum width.",
+        ),
+    ];
+    for (layout_text, extensions, expected) in cases {
+        let error = build_artifact(&layout_text, extensions)
+            .expect_err("custom checksum configuration should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+#[test]
+fn headers_and_fingerprints_do_not_need_custom_checksums_registered() {
+    let layout_text = checksum_layout(
+        "[mint.checksum.acme]\ncustom = \"acme\"",
+        "payload = { value = 1, type = \"u32\" }\nsum = { checksum = \"acme\", type = \"u16\" }",
+    );
+    let config = layout::parse_toml_layout(&layout_text).expect("layout parses");
+    mint_core::fingerprint::calculate(&config).expect("fingerprint calculates unbound");
+
+    let path = common::write_layout_file("extensions-header", &layout_text);
+    let header = mint_core::header::generate(&[BlockSelector::named(&path, "block")])
+        .expect("header generates unbound");
+    assert!(header.contains("  uint16_t sum;"), "{header}");
+}
@@ -0,0 +1,157 @@
+use mint_core::build::BlockSelector;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[path = "common/mod.rs"]
+mod common;
+
+const CERTS: &str = r#"
+[mint]
+abi = "generic-le"
+
+[mint.const]
+HEADER_LEN = 2
+
+[block.header]
+start_address = 0x1000
+length = 0x20
+padding = 0xFF
+
+[block.data]
+cert = { file = "certs/cert.der", type = "u8", size = 8 }
+payload = { file = { path = "certs/cert.der", offset = "HEADER_LEN", length = 3 }, type = "u16", SIZE = 3 }
+"#;
+
+const CERT: &[u8] = &[0x30, 0x82, 0x01, 0x0A, 0x02, 0x01];
+
+/// Writes `layout_text` as `layout.toml` into a fresh directory holding `certs/cert.der`.
+fn write_layout(layout_text: &str) -> PathBuf {
+    let directory = common::unique_out_path("files", "d");
+    fs::create_dir_all(directory.join("certs")).expect("create layout directory");
+    fs::write(directory.join("certs/cert.der"), CERT).expect("write embedded file");
+    let path = directory.join("layout.toml");
+    fs::write(&path, layout_text).expect("write layout file");
+    path
+}
+
+fn build(layout_path: &Path) -> Result<Vec<u8>, String> {
+    common::build_block(layout_path, "block", false, None)
+        .map_err(|error| common::error_chain(&error))
+}
+
+#[test]
+fn files_embed_bytes_relative_to_the_layout() {
+    let path = write_layout(CERTS);
+    let (bytes, used) = common::build_block_with_values(&path, "block").expect("files embed");
+
+    let mut expected = CERT.to_vec();
+    expected.extend([0xFF, 0xFF]);
+    for byte in [0x01u16, 0x0A, 0x02] {
+        expected.extend(byte.to_le_bytes());
+    }
+    assert_eq!(&bytes[..expected.len()], expected);
+
+    let hash = Sha256::digest(CERT)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    let cert = &used["cert"];
+    assert_eq!(cert["sha256"], hash.as_str());
+    assert_eq!(
+        (cert["offset"].as_u64(), cert["length"].as_u64()),
+        (Some(0), Some(6))
+    );
+    assert_eq!(cert["file"], "certs/cert.der");
+    assert_eq!(used["payload"]["sha256"], hash.as_str());
+    assert_eq!(used["payload"]["length"], 3);
+}
+
+#[test]
+fn included_layouts_resolve_files_against_their_own_directory() {
+    let root = write_layout("include = [\"certs/block.toml\"]\n\n[mint]\nabi = \"generic-le\"\n");
+    let included = CERTS
+        .replace("[mint]\nabi = \"generic-le\"\n", "")
+        .replace("certs/cert.der", "cert.der");
+    fs::write(root.with_file_name("certs").join("block.toml"), included)
+        .expect("write included layout");
+
+    let (bytes, used) =
+        common::build_block_with_values(&root, "block").expect("included file resolves");
+    assert_eq!(&bytes[..CERT.len()], CERT);
+    assert_eq!(used["cert"]["file"], "certs/cert.der");
+}
+
+#[test]
+fn files_of_sibling_includes_are_reported_relative_to_the_root_layout() {
+    let directory = write_layout(CERTS).with_file_name("");
+    let included = CERTS
+        .replace("[mint]\nabi = \"generic-le\"\n", "")
+        .replace("certs/cert.der", "cert.der");
+    fs::write(directory.join("certs/block.toml"), included).expect("write included layout");
+    fs::create_dir_all(directory.join("app")).expect("create root directory");
+    let root = directory.join("app/layout.toml");
+    fs::write(
+        &root,
+        "include = [\"../certs/block.toml\"]\n\n[mint]\nabi = \"generic-le\"\n",
+    )
+    .expect("write root layout");
+
+    let (bytes, used) = common::build_block_with_values(&root, "block").expect("file resolves");
+    assert_eq!(&bytes[..CERT.len()], CERT);
+    assert_eq!(used["cert"]["file"], "../certs/cert.der");
+}
+
+#[test]
+fn file_sizes_and_slices_are_checked() {
+    let cases = [
+        (
+            CERTS.replace("type = \"u8\", size = 8", "type = \"u8\", size = 4"),
+            "File contents (6 bytes) are larger than defined size (4).",
+        ),
+        (
+            CERTS.replace("type = \"u8\", size = 8", "type = \"u8\", SIZE = 8"),
+            "File contents (6 bytes) are smaller than defined size (8, strict SIZE).",
+        ),
+        (
+            CERTS.replace("length = 3", "length = 10"),
+            "has 6 bytes; the slice at offset 2 with length 10 runs past its end.",
+        ),
+        (
+            CERTS.replace("file = \"certs/cert.der\"", "file = \"certs/missing.der\""),
+            "failed to read file",
+        ),
+        (
+            CERTS.replace("type = \"u8\", size = 8", "type = \"i16\", size = 8"),
+            "File type must be u8 or u16, got i16.",
+        ),
+        (
+            CERTS.replace("type = \"u8\", size = 8", "type = \"u8\""),
+            "File requires a one-dimensional size or SIZE.",
+        ),
+        (
+            CERTS.replace("offset = \"HEADER_LEN\"", "start = 2"),
+            "invalid 'file' slice: unknown field `start`",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build(&write_layout(&layout_text)).expect_err("file is rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+#[test]
+fn header_declares_file_arrays() {
+    let path = write_layout(CERTS);
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&path)]).expect("header generates");
+
+    assert!(
+        header.contains("  uint8_t cert[BLOCK_CERT_LEN]; /* embedded file */\n"),
+        "{header}"
+    );
+    assert!(header.contains("  uint16_t payload[BLOCK_PAYLOAD_LEN]; /* embedded file */\n"));
+}
@@ -0,0 +1,208 @@
+use mint_core::build;
+use mint_core::keys::{KeyRing, KeySource};
+use mint_core::output::digest::calculate_hmac_sha256;
+use std::path::PathBuf;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const PAYLOAD: [u8; 8] = [1, 0, 0, 0, 2, 0, 0, 0];
+const FILE_KEY: &[u8] = b"mint-test-hmac-key";
+
+fn hmac_layout(hmac: &str, data: &str) -> String {
+    format!(
+        r#"
+[mint]
+abi = "generic-le"
+
+[mint.hmac.auth]
+{hmac}
+
+[block.header]
+start_address = 0x1000
+length = 0x100
+
+[block.data]
+{data}
+"#
+    )
+}
+
+const TAGGED_DATA: &str = r#"
+first = { value = 1, type = "u32" }
+second = { value = 2, type = "u32" }
+tag = { hmac = "auth", type = "u8", size = 32 }
+"#;
+
+/// Builds as if the layout lived in `tests/data`, so `key_file = "keys/..."` resolves to the
+/// fixture keys.
+fn build_artifact(layout_text: &str, keys: KeyRing) -> Result<build::BuildArtifact, String> {
+    let options = common::BuildOptions {
+        keys,
+        ..Default::default()
+    };
+    common::build_layout_text("tests/data/hmac.toml", layout_text, options)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[test]
+fn hmac_field_uses_key_file_and_keeps_key_out_of_report() {
+    let artifact = build_artifact(
+        &hmac_layout("key_file = \"keys/hmac.key\"", TAGGED_DATA),
+        KeyRing::new(),
+    )
+    .expect("HMAC block builds");
+    let bytes = &artifact.ranges[0].bytestream;
+
+    assert_eq!(&bytes[..8], &PAYLOAD);
+    assert_eq!(
+        hex(&bytes[8..40]),
+        "55cd3e86df96a1ab7bd01b0e65c4788d67d044dede4569c9f4d8e5c0b91a47a3"
+    );
+
+    let used = artifact.used_values.expect("used values captured");
+    assert_eq!(
+        used["tests/data/hmac.toml"]["block"]["tag"],
+        serde_json::Value::String(hex(&bytes[8..40]))
+    );
+    let report = used.to_string();
+    assert!(!report.contains("mint-test-hmac-key"), "report: {report}");
+    assert!(!report.contains(&hex(FILE_KEY)), "report: {report}");
+}
+
+#[test]
+fn hmac_key_env_reads_variable_and_key_ring_overrides_layout() {
+    // Cargo sets CARGO_PKG_NAME for test binaries, so no test mutates the environment.
+    let layout_text = hmac_layout("key_env = \"CARGO_PKG_NAME\"", TAGGED_DATA);
+
+    let artifact = build_artifact(&layout_text, KeyRing::new()).expect("HMAC block builds");
+    assert_eq!(
+        &artifact.ranges[0].bytestream[8..40],
+        calculate_hmac_sha256(b"mint-core", &PAYLOAD).as_slice()
+    );
+
+    let mut keys = KeyRing::new();
+    keys.insert(
+        "auth",
+        KeySource::File(PathBuf::from("tests/data/keys/hmac.key")),
+    );
+    let artifact = build_artifact(&layout_text, keys).expect("HMAC block builds");
+    assert_eq!(
+        &artifact.ranges[0].bytestream[8..40],
+        calculate_hmac_sha256(FILE_KEY, &PAYLOAD).as_slice()
+    );
+}
+
+#[test]
+fn key_encoding_selects_raw_or_hex_and_key_files_drop_their_final_newline() {
+    let cases = [
+        ("", b"mint-test-hmac-key\n".to_vec(), FILE_KEY.to_vec()),
+        ("", b"mint-test-hmac-key\r\n".to_vec(), FILE_KEY.to_vec()),
+        ("", b"deadbeef".to_vec(), b"deadbeef".to_vec()),
+        (
+            "key_encoding = \"hex\"",
+            hex(FILE_KEY).into_bytes(),
+            FILE_KEY.to_vec(),
+        ),
+        (
+            "key_encoding = \"hex\"",
+            format!("{}\n", hex(FILE_KEY).to_uppercase()).into_bytes(),
+            FILE_KEY.to_vec(),
+        ),
+    ];
+    for (hmac, contents, key) in cases {
+        let path = common::unique_out_path("hmac", "key");
+        std::fs::write(&path, &contents).expect("write key file");
+        let mut keys = KeyRing::new();
+        keys.insert("auth", KeySource::File(path));
+        let artifact =
+            build_artifact(&hmac_layout(hmac, TAGGED_DATA), keys).expect("HMAC block builds");
+        assert_eq!(
+            &artifact.ranges[0].bytestream[8..40],
+            calculate_hmac_sha256(&key, &PAYLOAD).as_slice(),
+            "{contents:?}"
+        );
+    }
+}
+
+#[test]
+fn hmac_coverage_range_is_respected() {
+    let artifact = build_artifact(
+        &hmac_layout(
+            "key_file = \"keys/hmac.key\"",
+            r#"
+tag = { hmac = "auth", type = "u8", size = 32, from = "payload" }
+payload = { value = [1, 2], type = "u32", size = 2 }
+"#,
+        ),
+        KeyRing::new(),
+    )
+    .expect("HMAC block builds");
+    let bytes = &artifact.ranges[0].bytestream;
+    assert_eq!(&bytes[32..40], &PAYLOAD);
+    assert_eq!(
+        &bytes[..32],
+        calculate_hmac_sha256(FILE_KEY, &PAYLOAD).as_slice()
+    );
+}
+
+#[test]
+fn hmac_configuration_errors_are_reported() {
+    let cases = [
+        (
+            hmac_layout("", TAGGED_DATA),
+            "no key for [mint.hmac.auth]; set 'key_file' or 'key_env' in the layout or pass --key auth=PATH",
+        ),
+        (
+            hmac_layout(
+                "key_file = \"keys/hmac.key\"\nkey_env = \"MINT_HMAC_KEY\"",
+                TAGGED_DATA,
+            ),
+            "HMAC config may set only one of 'key_file' and 'key_env'",
+        ),
+        (
+            hmac_layout(
+                "key_file = \"keys/hmac.key\"\nkey_encoding = \"hex\"",
+                TAGGED_DATA,
+            ),
+            "[mint.hmac.auth]: key_encoding is 'hex' but the key is not an even number of hex digits",
+        ),
+        (
+            hmac_layout("key_encoding = \"base64\"", TAGGED_DATA),
+            "unknown variant `base64`",
+        ),
+        (
+            hmac_layout("key = \"secret\"", TAGGED_DATA),
+            "unknown field `key`",
+        ),
+        (
+            hmac_layout("key_env = \"MINT_TEST_UNSET_HMAC_KEY\"", TAGGED_DATA),
+            "failed to read key from environment variable 'MINT_TEST_UNSET_HMAC_KEY'",
+        ),
+        (
+            hmac_layout(
+                "key_file = \"keys/hmac.key\"",
+                "value = { value = 1, type = \"u32\" }\ntag = { hmac = \"auth\", type = \"u8\", size = 16 }",
+            ),
+            "HMAC-SHA256 produces 32 bytes; size must be 32.",
+        ),
+        (
+            hmac_layout(
+                "key_file = \"keys/hmac.key\"",
+                "value = { value = 1, type = \"u32\" }\ntag = { hmac = \"other\", type = \"u8\", size = 32 }",
+            ),
+            "HMAC config 'other' not found in [mint.hmac]. Available: [auth]",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build_artifact(&layout_text, KeyRing::new())
+            .expect_err("HMAC configuration should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
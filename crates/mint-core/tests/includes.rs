@@ -0,0 +1,151 @@
+use mint_core::build::{self, BlockSelector, BuildRequest};
+use mint_core::fingerprint;
+use mint_core::layout;
+use std::path::Path;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const COMMON: &str = r#"
+[mint]
+abi = "generic-le"
+
+[mint.checksum.crc]
+algorithm = "CRC-32/ISO-HDLC"
+
+[mint.const]
+version = 7
+
+[shared.header]
+start_address = 0x2000
+length = 0x10
+
+[shared.data]
+version = { const = "version", type = "u16" }
+"#;
+
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .expect("layout file name")
+}
+
+fn product_layout(includes: &[&str]) -> String {
+    let includes = includes
+        .iter()
+        .map(|path| format!("\"{}\"", file_name(path)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r#"
+include = [{includes}]
+
+[block.header]
+start_address = 0x1000
+length = 0x10
+
+[block.data]
+version = {{ const = "version", type = "u16" }}
+checksum = {{ checksum = "crc", type = "u32" }}
+"#
+    )
+}
+
+#[test]
+fn included_consts_checksums_and_blocks_build_with_the_including_layout() {
+    let common_path = common::write_layout_file("include-common", COMMON);
+    let path = common::write_layout_file("include-product", &product_layout(&[&common_path]));
+
+    let artifact = build::build(BuildRequest {
+        blocks: vec![BlockSelector::all(&path)],
+        data_source: None,
+        strict: false,
+        capture_values: false,
+    })
+    .expect("layout with includes builds");
+
+    let starts = artifact
+        .ranges
+        .iter()
+        .map(|range| range.start_address)
+        .collect::<Vec<_>>();
+    assert_eq!(starts, [0x2000, 0x1000], "included blocks come first");
+    assert_eq!(&artifact.ranges[0].bytestream[..2], [7, 0]);
+    assert_eq!(&artifact.ranges[1].bytestream[..2], [7, 0]);
+
+    let config = layout::load_layout(&path).expect("layout loads");
+    let fingerprints = fingerprint::calculate(&config).expect("fingerprints calculate");
+    let blocks = fingerprints
+        .iter()
+        .map(|fingerprint| fingerprint.block.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(blocks, ["shared", "block"]);
+}
+
+#[test]
+fn a_file_included_twice_contributes_its_keys_once() {
+    let common_path = common::write_layout_file("include-diamond-common", COMMON);
+    let left = common::write_layout_file(
+        "include-diamond-left",
+        &format!("include = [\"{}\"]\n", file_name(&common_path)),
+    );
+    let right = common::write_layout_file(
+        "include-diamond-right",
+        &format!("include = [\"{}\"]\n", file_name(&common_path)),
+    );
+    let path = common::write_layout_file("include-diamond", &product_layout(&[&left, &right]));
+
+    let config = layout::load_layout(&path).expect("diamond includes load");
+    assert_eq!(
+        config.blocks.keys().collect::<Vec<_>>(),
+        ["shared", "block"]
+    );
+}
+
+#[test]
+fn include_errors_name_the_files_involved() {
+    let first = common::write_layout_file("include-cycle-a", "");
+    let second = common::write_layout_file(
+        "include-cycle-b",
+        &format!("include = [\"{}\"]\n", file_name(&first)),
+    );
+    std::fs::write(&first, format!("include = [\"{}\"]\n", file_name(&second)))
+        .expect("rewrite layout file");
+    let error = common::error_chain(&layout::load_layout(&first).expect_err("cycle rejected"));
+    assert!(error.contains("layout include cycle: "), "{error}");
+    assert!(
+        error.contains(&format!("{} -> ", file_name(&first)))
+            && error.contains(&format!("{} -> ", file_name(&second))),
+        "{error}"
+    );
+
+    let common_path = common::write_layout_file("include-duplicate-common", COMMON);
+    let path = common::write_layout_file(
+        "include-duplicate",
+        &format!(
+            "{}\n[mint.const]\nversion = 8\n",
+            product_layout(&[&common_path])
+        ),
+    );
+    let error = common::error_chain(&layout::load_layout(&path).expect_err("duplicate rejected"));
+    assert!(
+        error.contains("duplicate layout key 'mint.const.version' in ")
+            && error.contains(file_name(&common_path))
+            && error.contains(file_name(&path)),
+        "{error}"
+    );
+
+    let missing = common::write_layout_file("include-missing", "include = [\"absent.toml\"]\n");
+    let error = common::error_chain(&layout::load_layout(&missing).expect_err("missing rejected"));
+    assert!(error.contains("absent.toml"), "{error}");
+
+    let error = common::error_chain(
+        &layout::parse_toml_layout("include = [\"common.toml\"]\n")
+            .expect_err("include needs a file"),
+    );
+    assert!(
+        error.contains("'include' is only supported in layout files loaded from disk"),
+        "{error}"
+    );
+}
@@ -0,0 +1,159 @@
+use mint_core::build::{self, BlockSelector, BuildArtifact, BuildFromLayoutsRequest, NamedLayout};
+use mint_core::layout;
+use mint_core::layout::settings::DigestAlgorithm;
+use mint_core::output::checksum::calculate_checksum;
+use mint_core::output::digest::calculate_digest;
+use std::path::PathBuf;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const MANIFEST_LAYOUT: &str = r#"
+[mint]
+abi = "generic-le"
+
+[mint.checksum.crc]
+algorithm = "CRC-32"
+
+[manifest.header]
+start_address = 0x0000
+length = 0x100
+
+[manifest.data]
+app_crc = { checksum = "crc", type = "u32", block = "app" }
+app_padded_crc = { checksum = "crc", type = "u32", block = "app", to_length = true }
+config_hash = { digest = "sha256", type = "u8", size = 32, block = "config" }
+crc = { checksum = "crc", type = "u32" }
+
+[app.header]
+start_address = 0x1000
+length = 0x20
+
+[app.data]
+version = { value = 3, type = "u32" }
+crc = { checksum = "crc", type = "u32" }
+
+[config.header]
+start_address = 0x2000
+length = 0x10
+
+[config.data]
+gain = { value = 1.5, type = "f32" }
+limits = { value = [1, 2], type = "u16", size = 2 }
+"#;
+
+fn build(layout_text: &str, blocks: &[&str]) -> Result<BuildArtifact, String> {
+    let config =
+        layout::parse_toml_layout(layout_text).map_err(|error| common::error_chain(&error))?;
+    let name = PathBuf::from("manifest.toml");
+    build::build_from_layouts(BuildFromLayoutsRequest {
+        layouts: vec![NamedLayout {
+            name: name.clone(),
+            config,
+        }],
+        blocks: if blocks.is_empty() {
+            vec![BlockSelector::all(&name)]
+        } else {
+            blocks
+                .iter()
+                .map(|block| BlockSelector::named(&name, block))
+                .collect()
+        },
+        data_source: None,
+        strict: false,
+        capture_values: true,
+    })
+    .map_err(|error| common::error_chain(&error))
+}
+
+fn crc(data: &[u8]) -> [u8; 4] {
+    let config = layout::parse_toml_layout(MANIFEST_LAYOUT)
+        .expect("layout parses")
+        .mint
+        .checksum
+        .remove("crc")
+        .expect("crc config");
+    calculate_checksum(data, &config.algorithm).to_le_bytes()
+}
+
+#[test]
+fn manifest_fields_cover_blocks_declared_after_it() {
+    let artifact = build(MANIFEST_LAYOUT, &[]).expect("manifest layout builds");
+    assert_eq!(artifact.ranges.len(), 3, "all selected blocks are emitted");
+    let manifest = &artifact.ranges[0].bytestream;
+    let app = &artifact.ranges[1].bytestream;
+    let config = &artifact.ranges[2].bytestream;
+
+    assert_eq!(app.len(), 8);
+    assert_eq!(&manifest[..4], &crc(app));
+    let mut padded = app.clone();
+    padded.resize(0x20, 0xFF);
+    assert_eq!(&manifest[4..8], &crc(&padded));
+    assert_eq!(
+        &manifest[8..40],
+        calculate_digest(config, DigestAlgorithm::Sha256).as_slice()
+    );
+    assert_eq!(&manifest[40..44], &crc(&manifest[..40]));
+}
+
+#[test]
+fn manifest_builds_unselected_targets_without_emitting_them() {
+    let full = build(MANIFEST_LAYOUT, &[]).expect("manifest layout builds");
+    let manifest_only = build(MANIFEST_LAYOUT, &["manifest"]).expect("manifest builds alone");
+
+    assert_eq!(manifest_only.ranges.len(), 1);
+    assert_eq!(
+        manifest_only.ranges[0].bytestream,
+        full.ranges[0].bytestream
+    );
+    assert_eq!(manifest_only.stats.blocks_processed, 1);
+
+    let used = manifest_only.used_values.expect("used values captured");
+    let report = used["manifest.toml"].as_object().expect("layout report");
+    assert_eq!(report.keys().collect::<Vec<_>>(), ["manifest"]);
+}
+
+#[test]
+fn cross_block_coverage_errors_are_reported() {
+    let base = r#"
+[mint]
+abi = "generic-le"
+
+[mint.checksum.crc]
+algorithm = "CRC-32"
+
+[a.header]
+start_address = 0x0000
+length = 0x10
+
+[b.header]
+start_address = 0x1000
+length = 0x10
+"#;
+    let cases = [
+        (
+            "[a.data]\ncrc = { checksum = \"crc\", type = \"u32\", block = \"missing\" }\n[b.data]\nv = { value = 1, type = \"u32\" }",
+            "coverage target 'missing' from block 'a'. Available blocks: a, b",
+        ),
+        (
+            "[a.data]\ncrc = { checksum = \"crc\", type = \"u32\", block = \"a\" }\n[b.data]\nv = { value = 1, type = \"u32\" }",
+            "coverage block 'a' is the containing block",
+        ),
+        (
+            "[a.data]\ncrc = { checksum = \"crc\", type = \"u32\", block = \"b\" }\n[b.data]\ncrc = { checksum = \"crc\", type = \"u32\", block = \"a\" }",
+            "cross-block coverage is circular between blocks [a, b]",
+        ),
+        (
+            "[a.data]\nv = { value = 1, type = \"u32\" }\ncrc = { checksum = \"crc\", type = \"u32\", block = \"b\", from = \"v\" }\n[b.data]\nv = { value = 1, type = \"u32\" }",
+            "'block' coverage cannot be combined with 'from', 'to' or 'exclude'",
+        ),
+    ];
+    for (data, expected) in cases {
+        let error = build(&format!("{base}{data}"), &["a"])
+            .expect_err("cross-block coverage should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
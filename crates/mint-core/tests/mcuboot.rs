@@ -0,0 +1,233 @@
+use ed25519_dalek::Verifier as _;
+use sha2::{Digest, Sha256};
+
+#[path = "common/mod.rs"]
+mod common;
+
+const ED25519_PUBLIC_KEY: &str = "316a80cdca25d412c88a82369056a48825f89655e2c203fd09bb96df45f06bed";
+const P256_PUBLIC_KEY: &str = "04fda0a7f3f0df7ce2c55c7fc8e06b0b84b7b39cd7f2e26884b3401227e8dbca\
+c9c30cbaba5936f210059f7b75d22a59ee0c8b9d1d00160ab7b7da12e0e66292f8";
+/// DER SubjectPublicKeyInfo prefixes that precede the raw public keys.
+const ED25519_SPKI_PREFIX: &str = "302a300506032b6570032100";
+const P256_SPKI_PREFIX: &str = "3059301306072a8648ce3d020106082a8648ce3d030107034200";
+
+const DATA: &str = r#"
+version = { value = 7, type = "u32" }
+self_ref = { ref = "version", type = "u32" }
+"#;
+
+fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).expect("hex digit"))
+        .collect()
+}
+
+fn mcuboot_layout(abi: &str, mcuboot: &str, data: &str) -> String {
+    format!(
+        r#"
+[mint]
+abi = "{abi}"
+
+[mint.signature.ed]
+algorithm = "ed25519"
+key_file = "keys/ed25519.pem"
+
+[mint.signature.ec]
+algorithm = "ecdsa-p256"
+key_file = "keys/p256.pem"
+
+[block.header]
+start_address = 0x8000
+length = 0x400
+mcuboot = {{ {mcuboot} }}
+
+[block.data]
+{data}
+"#
+    )
+}
+
+/// Builds as if the layout lived in `tests/data`, so `key_file = "keys/..."` resolves to the
+/// fixture keys.
+fn build_bytes(layout_text: &str) -> Result<Vec<u8>, String> {
+    let artifact =
+        common::build_layout_text("tests/data/mcuboot.toml", layout_text, Default::default())?;
+    Ok(artifact.ranges[0].bytestream.clone())
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(bytes[offset..offset + 2].try_into().expect("2 bytes"))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().expect("4 bytes"))
+}
+
+/// Splits the TLV area after `image_end` into `(type, value)` pairs, checking its info header.
+fn tlvs(bytes: &[u8], image_end: usize) -> Vec<(u16, &[u8])> {
+    assert_eq!(u16_at(bytes, image_end), 0x6907, "TLV info magic");
+    let end = image_end + usize::from(u16_at(bytes, image_end + 2));
+    assert_eq!(end, bytes.len(), "TLV total covers the rest of the image");
+    let mut offset = image_end + 4;
+    let mut out = Vec::new();
+    while offset < end {
+        let length = usize::from(u16_at(bytes, offset + 2));
+        out.push((
+            u16_at(bytes, offset),
+            &bytes[offset + 4..offset + 4 + length],
+        ));
+        offset += 4 + length;
+    }
+    out
+}
+
+#[test]
+fn unsigned_image_has_header_payload_and_hash_tlv() {
+    let bytes = build_bytes(&mcuboot_layout("generic-le", "version = \"1.2.3+4\"", DATA))
+        .expect("MCUboot block builds");
+
+    assert_eq!(u32_at(&bytes, 0), 0x96F3_B83D, "ih_magic");
+    assert_eq!(u32_at(&bytes, 4), 0, "ih_load_addr");
+    assert_eq!(u16_at(&bytes, 8), 0x20, "ih_hdr_size");
+    assert_eq!(u16_at(&bytes, 10), 0, "ih_protect_tlv_size");
+    assert_eq!(u32_at(&bytes, 12), 8, "ih_img_size");
+    assert_eq!(u32_at(&bytes, 16), 0, "ih_flags");
+    assert_eq!(&bytes[20..24], &[1, 2, 3, 0], "major, minor, revision");
+    assert_eq!(u32_at(&bytes, 24), 4, "build number");
+    assert_eq!(u32_at(&bytes, 28), 0, "_pad1");
+    assert_eq!(u32_at(&bytes, 32), 7);
+    assert_eq!(
+        u32_at(&bytes, 36),
+        0x8020,
+        "refs address the data after the header"
+    );
+
+    let tlvs = tlvs(&bytes, 40);
+    assert_eq!(tlvs.len(), 1);
+    assert_eq!(tlvs[0].0, 0x10, "IMAGE_TLV_SHA256");
+    assert_eq!(tlvs[0].1, Sha256::digest(&bytes[..40]).as_slice());
+}
+
+#[test]
+fn header_size_load_address_and_byte_order_follow_the_layout() {
+    let bytes = build_bytes(&mcuboot_layout(
+        "generic-be",
+        "version = \"2\", header_size = 0x200, load_address = 0x20000000",
+        DATA,
+    ))
+    .expect("MCUboot block builds");
+
+    assert_eq!(&bytes[..4], &0x96F3_B83Du32.to_be_bytes());
+    assert_eq!(&bytes[4..8], &0x2000_0000u32.to_be_bytes());
+    assert_eq!(&bytes[8..10], &0x200u16.to_be_bytes());
+    assert_eq!(&bytes[16..20], &0x20u32.to_be_bytes(), "IMAGE_F_RAM_LOAD");
+    assert_eq!(&bytes[20..28], &[2, 0, 0, 0, 0, 0, 0, 0]);
+    assert!(bytes[32..0x200].iter().all(|byte| *byte == 0));
+    assert_eq!(&bytes[0x204..0x208], &0x8200u32.to_be_bytes());
+    assert_eq!(&bytes[0x208..0x20A], &0x6907u16.to_be_bytes());
+}
+
+#[test]
+fn ed25519_image_signs_the_hash_and_records_the_key_hash() {
+    let bytes = build_bytes(&mcuboot_layout(
+        "generic-le",
+        "version = \"1.0.0\", signature = \"ed\"",
+        DATA,
+    ))
+    .expect("signed MCUboot block builds");
+    let tlvs = tlvs(&bytes, 40);
+    let kinds = tlvs.iter().map(|(kind, _)| *kind).collect::<Vec<_>>();
+    assert_eq!(kinds, [0x10, 0x01, 0x24]);
+
+    let public_key = unhex(ED25519_PUBLIC_KEY);
+    let spki = [unhex(ED25519_SPKI_PREFIX), public_key.clone()].concat();
+    assert_eq!(tlvs[1].1, Sha256::digest(&spki).as_slice());
+    let verifying_key =
+        ed25519_dalek::VerifyingKey::from_bytes(&public_key.try_into().expect("32-byte key"))
+            .expect("public key");
+    let signature = ed25519_dalek::Signature::from_slice(tlvs[2].1).expect("signature");
+    verifying_key
+        .verify(tlvs[0].1, &signature)
+        .expect("signature verifies over the image hash");
+}
+
+#[test]
+fn ecdsa_image_signature_is_der_over_header_and_payload() {
+    let bytes = build_bytes(&mcuboot_layout(
+        "generic-le",
+        "version = \"1.0.0\", signature = \"ec\"",
+        DATA,
+    ))
+    .expect("signed MCUboot block builds");
+    let tlvs = tlvs(&bytes, 40);
+    assert_eq!(tlvs[2].0, 0x22, "IMAGE_TLV_ECDSA_SIG");
+
+    let public_key = unhex(P256_PUBLIC_KEY);
+    let spki = [unhex(P256_SPKI_PREFIX), public_key.clone()].concat();
+    assert_eq!(tlvs[1].1, Sha256::digest(&spki).as_slice());
+    let verifying_key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&public_key).expect("key");
+    let signature = p256::ecdsa::Signature::from_der(tlvs[2].1).expect("DER signature");
+    verifying_key
+        .verify(&bytes[..40], &signature)
+        .expect("signature verifies over header and payload");
+}
+
+#[test]
+fn mcuboot_configuration_errors_are_reported() {
+    let cases = [
+        (
+            mcuboot_layout("generic-le", "version = \"1.2.3.4\"", DATA),
+            "invalid MCUboot version '1.2.3.4'; expected MAJOR.MINOR.REVISION+BUILD",
+        ),
+        (
+            mcuboot_layout("generic-le", "version = \"256.0.0\"", DATA),
+            "invalid MCUboot version '256.0.0'",
+        ),
+        (
+            mcuboot_layout("generic-le", "header_size = 0x20", DATA),
+            "missing field `version`",
+        ),
+        (
+            mcuboot_layout("generic-le", "version = \"1\", header_size = 0x10", DATA),
+            "MCUboot header_size 0x10 is smaller than the 32-octet image header",
+        ),
+        (
+            mcuboot_layout(
+                "generic-le",
+                "version = \"1\", signature = \"missing\"",
+                DATA,
+            ),
+            "Signature config 'missing' not found in [mint.signature]",
+        ),
+        (
+            mcuboot_layout(
+                "generic-le",
+                "version = \"1\", signature = \"ed\"",
+                "payload = { value = 0, type = \"u8\", size = 0x380 }",
+            ),
+            "resolved layout size (896 octets) plus the MCUboot image header (32 octets) and the MCUboot TLV trailer (144 octets) exceeds configured block length (1024 octets)",
+        ),
+        (
+            mcuboot_layout(
+                "ti-c28x-eabi",
+                "version = \"1\"",
+                "version = { value = 7, type = \"u32\" }",
+            ),
+            "MCUboot images require an 8-bit addressable unit; ABI 'ti-c28x-eabi' uses 2 octets",
+        ),
+        (
+            mcuboot_layout("generic-le", "version = \"1\"", DATA)
+                .replace("mcuboot =", "signature = \"ed\"\nmcuboot ="),
+            "'mcuboot' cannot be combined with header 'signature' or 'encryption'",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error =
+            build_bytes(&layout_text).expect_err("MCUboot configuration should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use mint_core::build::{BlockSelector, BuildArtifact};
+
+#[path = "common/mod.rs"]
+mod common;
+
+const LEGACY: &str = r#"
+[mint]
+abi = "generic-le"
+
+[mint.types.slot]
+id = { value = 1, type = "u8" }
+gain = { value = 2, type = "u16", offset = 4 }
+
+[block.header]
+start_address = 0x1000
+length = 0x40
+padding = 0xEE
+
+[block.data]
+version = { value = 0x11, type = "u8" }
+spare = { reserved = true, type = "u8", size = 3 }
+serial = { value = 0x22, type = "u32", offset = 8 }
+nested.flag = { value = 0x33, type = "u8" }
+nested.offset = 0x0C
+nested.limit = { value = 0x44, type = "u16", offset = 2 }
+slot = { struct = "slot", offset = 0x14 }
+"#;
+
+fn build_artifact(layout_text: &str) -> Result<BuildArtifact, String> {
+    common::build_layout_text("offsets.toml", layout_text, Default::default())
+}
+
+#[test]
+fn explicit_offsets_place_fields_and_reserved_space_is_padding() {
+    let artifact = build_artifact(LEGACY).expect("legacy layout builds");
+
+    assert_eq!(
+        artifact.ranges[0].bytestream,
+        [
+            0x11, 0xEE, 0xEE, 0xEE, 0xEE, 0xEE, 0xEE, 0xEE, // version, spare, gap
+            0x22, 0x00, 0x00, 0x00, // serial at 8
+            0x33, 0xEE, 0x44, 0x00, // nested at 0x0C, limit at +2
+            0xEE, 0xEE, 0xEE, 0xEE, // gap
+            0x01, 0xEE, 0xEE, 0xEE, 0x02, 0x00, 0xEE, 0xEE, // slot at 0x14, gain at +4
+        ]
+    );
+    let used = artifact.used_values.expect("used values captured");
+    assert!(used["offsets.toml"]["block"].get("spare").is_none());
+}
+
+#[test]
+fn header_fills_offset_gaps_and_names_reserved_members() {
+    let path = common::write_layout_file("offsets-header", LEGACY);
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&path)]).expect("header generates");
+
+    assert!(
+        header.contains(
+            "  uint8_t version;\n  uint8_t spare[BLOCK_SPARE_LEN]; /* reserved */\n  uint8_t mint_gap_0x4[4u]; /* before 'serial' */\n  uint32_t serial;\n"
+        ),
+        "{header}"
+    );
+    assert!(
+        header.contains("    uint8_t flag;\n    uint16_t limit;\n"),
+        "aligned offsets need no gap member: {header}"
+    );
+    assert!(header.contains("  uint8_t mint_gap_0x10[4u]; /* before 'slot' */\n  slot_t slot;\n"));
+    assert!(
+        header.contains("  uint8_t id;\n  uint8_t mint_gap_0x1[3u]; /* before 'gain' */\n"),
+        "{header}"
+    );
+    assert!(header.contains("offsetof(block_t, slot) * CHAR_BIT == 20u * 8u"));
+}
+
+#[test]
+fn offsets_that_overlap_or_misalign_are_rejected() {
+    let cases = [
+        (
+            LEGACY.replace("offset = 8", "offset = 0"),
+            "field 'serial' offset 0x0 overlaps the preceding fields, which end at offset 0x4",
+        ),
+        (
+            LEGACY.replace("offset = 8", "offset = 6"),
+            "field 'serial' offset 0x6 is not a multiple of its 4-octet alignment",
+        ),
+        (
+            LEGACY.replace("offset = 0x14", "offset = 0x0C"),
+            "field 'slot' offset 0xC overlaps the preceding fields, which end at offset 0x10",
+        ),
+        (
+            LEGACY.replace("reserved = true", "reserved = false"),
+            "'reserved' must be true",
+        ),
+        (LEGACY.replace("offset = 4 }", "offset = -4 }"), "offset"),
+        (
+            LEGACY.replace("nested.offset = 0x0C", "nested.offset = \"high\""),
+            "branch 'offset' must be a non-negative integer",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build_artifact(&layout_text).expect_err("offset should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use mint_core::build::{BlockSelector, BuildArtifact};
+use mint_core::fingerprint;
+use mint_core::layout;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const EEPROM: &str = r#"
+[mint]
+abi = "generic-le"
+
+[mint.types.sample]
+packed = true
+tag = { value = 0x0A, type = "u8" }
+value = { value = 0x0B, type = "u32" }
+
+[block.header]
+start_address = 0x1000
+length = 0x20
+padding = 0xFF
+
+[block.data]
+packed = true
+kind = { value = 1, type = "u8" }
+count = { value = 0x01020304, type = "u32" }
+sample = { struct = "sample" }
+spare = { value = 0x0F, type = "u8" }
+inner.flag = { value = 0x0C, type = "u8" }
+inner.word = { value = 0x0D, type = "u16" }
+tail = { value = 0x0E, type = "u8" }
+"#;
+
+fn build_artifact(layout_text: &str) -> Result<BuildArtifact, String> {
+    common::build_layout_text("packed.toml", layout_text, Default::default())
+}
+
+fn fingerprint_of(layout_text: &str) -> u64 {
+    let config = layout::parse_toml_layout(layout_text).expect("layout parses");
+    fingerprint::calculate(&config).expect("fingerprint calculates")[0].value
+}
+
+#[test]
+fn packed_structs_drop_alignment_and_tail_padding() {
+    let artifact = build_artifact(EEPROM).expect("packed layout builds");
+
+    assert_eq!(
+        artifact.ranges[0].bytestream,
+        [
+            0x01, // kind
+            0x04, 0x03, 0x02, 0x01, // count at 1
+            0x0A, 0x0B, 0x00, 0x00, 0x00, // packed sample at 5
+            0x0F, // spare
+            0x0C, 0xFF, 0x0D,
+            0x00, // unpacked inner at 11 aligns its fields from its own start
+            0x0E, // tail, with no tail padding after it
+        ]
+    );
+
+    let unpacked = build_artifact(&EEPROM.replace("packed = true\nkind", "kind"))
+        .expect("unpacked layout builds");
+    assert_eq!(unpacked.ranges[0].bytestream.len(), 20);
+}
+
+#[test]
+fn packed_arrays_of_structs_use_the_packed_element_size_as_stride() {
+    let layout_text = EEPROM.replace(
+        "tail = { value = 0x0E, type = \"u8\" }",
+        "[block.data.rows]\nsize = 2\npacked = true\nid = { value = 7, type = \"u8\" }\nlimit = { value = 9, type = \"u16\" }",
+    );
+    let artifact = build_artifact(&layout_text).expect("packed rows build");
+
+    assert_eq!(
+        &artifact.ranges[0].bytestream[15..],
+        [0x07, 0x09, 0x00, 0x07, 0x09, 0x00]
+    );
+}
+
+#[test]
+fn packing_changes_the_fingerprint() {
+    let bytes_only = |packed: &str| {
+        format!(
+            r#"
+[mint]
+abi = "generic-le"
+
+[block.header]
+start_address = 0x1000
+length = 0x10
+
+[block.data]
+{packed}
+a = {{ value = 1, type = "u8" }}
+b = {{ value = 2, type = "u8" }}
+"#
+        )
+    };
+
+    assert_ne!(
+        fingerprint_of(&bytes_only("packed = true")),
+        fingerprint_of(&bytes_only("")),
+        "identical offsets still differ in packing"
+    );
+    assert_eq!(
+        fingerprint_of(&bytes_only("packed = false")),
+        fingerprint_of(&bytes_only(""))
+    );
+}
+
+#[test]
+fn header_declares_packed_structs_with_attribute_and_pragma_variants() {
+    let path = common::write_layout_file("packed-header", EEPROM);
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&path)]).expect("header generates");
+
+    assert!(
+        header.contains(
+            "#ifdef __GNUC__\n#define MINT_PACKED __attribute__((packed))\n#else\n#define MINT_PACKED\n#endif\n"
+        ),
+        "{header}"
+    );
+    assert!(header.contains(
+        "#ifndef __GNUC__\n#pragma pack(push, 1)\n#endif\ntypedef struct MINT_PACKED {\n  uint8_t tag;\n"
+    ));
+    assert!(header.contains("} sample_t;\n#ifndef __GNUC__\n#pragma pack(pop)\n#endif\n"));
+    // `#pragma pack` would also pack the unpacked `inner`, so only the attribute can declare it.
+    assert!(header.contains(
+        "#ifndef __GNUC__\n#error \"block_t nests packed and unpacked structs, which needs __attribute__((packed))\"\n#endif\ntypedef struct MINT_PACKED {\n  uint8_t kind;\n"
+    ));
+    assert!(header.contains("  struct {\n    uint8_t flag;\n"));
+    assert!(header.contains("} block_t;\n\n_Static_assert"));
+    assert!(header.contains(
+        "_Static_assert(_Alignof(block_t) == 1u, \"Mint ABI packing mismatch for block_t\");"
+    ));
+    assert!(header.contains("offsetof(block_t, inner.word) * CHAR_BIT == 13u * 8u"));
+    assert!(header.contains("offsetof(block_t, tail) * CHAR_BIT == 15u * 8u"));
+
+    let unpacked =
+        common::write_layout_file("unpacked-header", &EEPROM.replace("packed = true\n", ""));
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&unpacked)]).expect("header generates");
+    assert!(!header.contains("MINT_PACKED"));
+
+    let nested = common::write_layout_file(
+        "nested-packed-header",
+        &EEPROM
+            .replace("packed = true\nkind", "kind")
+            .replace("inner.flag", "inner.packed = true\ninner.flag"),
+    );
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&nested)]).expect("header generates");
+    assert!(header.contains("#error \"block_t nests packed and unpacked structs"));
+    assert!(header.contains("  struct MINT_PACKED {\n    uint8_t flag;\n"));
+    assert!(!header.contains("#pragma pack(push, 1)\n#endif\ntypedef struct {"));
+}
+
+#[test]
+fn packing_rejects_non_boolean_values_and_struct_fields() {
+    let cases = [
+        (
+            EEPROM.replace("packed = true\nkind", "packed = 1\nkind"),
+            "branch 'packed' must be true or false",
+        ),
+        (
+            EEPROM.replace(
+                "sample = { struct = \"sample\" }",
+                "sample = { struct = \"sample\", packed = true }",
+            ),
+            "struct field may only set 'struct', 'size', 'name' and 'offset'; found 'packed'",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build_artifact(&layout_text).expect_err("packing should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
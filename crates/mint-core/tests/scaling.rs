@@ -0,0 +1,152 @@
+use mint_core::build::{BlockSelector, BuildArtifact};
+use mint_core::data::JsonDataSource;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const SENSORS: &str = r#"
+[mint]
+abi = "generic-le"
+
+[block.header]
+start_address = 0x1000
+length = 0x20
+padding = 0xFF
+
+[block.data]
+temperature = { name = "Temperature", type = "i16", scale = { factor = 0.1, offset = -40 } }
+pressure = { value = 101.3, type = "u16", scale = { factor = "1.0 / 16" } }
+currents = { name = "Currents", type = "u8", size = 3, scale = { factor = 0.5 } }
+"#;
+
+fn build_artifact(layout_text: &str, data: &str, strict: bool) -> Result<BuildArtifact, String> {
+    let source = JsonDataSource::from_str(data, &["Default".to_owned()]).expect("JSON data parses");
+    let options = common::BuildOptions {
+        data_source: Some(&source),
+        strict,
+        ..Default::default()
+    };
+    common::build_layout_text("scaling.toml", layout_text, options)
+}
+
+fn data(temperature: f64) -> String {
+    format!(r#"{{ "Default": {{ "Temperature": {temperature}, "Currents": [1.0, 2.25, 2.75] }} }}"#)
+}
+
+#[test]
+fn physical_values_convert_to_rounded_raw_counts() {
+    let artifact = build_artifact(SENSORS, &data(25.0), true).expect("scaled values build");
+
+    let mut expected = Vec::new();
+    expected.extend(650i16.to_le_bytes()); // (25 + 40) / 0.1
+    expected.extend(1621u16.to_le_bytes()); // 101.3 * 16 = 1620.8
+    expected.extend([2, 4, 6]); // 2.25 / 0.5 = 4.5 and 2.75 / 0.5 = 5.5 round half to even
+    assert_eq!(&artifact.ranges[0].bytestream[..expected.len()], expected);
+
+    let used = artifact.used_values.expect("used values captured");
+    let block = &used["scaling.toml"]["block"];
+    assert_eq!(block["temperature"], 25.0);
+    assert_eq!(block["pressure"], 101.3);
+    assert_eq!(block["currents"], serde_json::json!([1.0, 2.25, 2.75]));
+}
+
+#[test]
+fn out_of_range_raw_values_error_in_strict_mode_and_clamp_otherwise() {
+    let error = build_artifact(SENSORS, &data(4000.0), true).expect_err("raw value overflows");
+    assert!(
+        error.contains("in field 'temperature'")
+            && error.contains(
+                "value 4000 scales to raw value 40400, outside the i16 range -32768..=32767"
+            ),
+        "{error}"
+    );
+
+    let artifact = build_artifact(SENSORS, &data(4000.0), false).expect("raw value clamps");
+    assert_eq!(&artifact.ranges[0].bytestream[..2], i16::MAX.to_le_bytes());
+}
+
+#[test]
+fn strict_range_check_is_exact_for_64_bit_types() {
+    let temperature = "type = \"i16\", scale = { factor = 0.1, offset = -40 }";
+    let cases = [
+        (
+            "type = \"u64\", scale = { factor = 1 }",
+            2f64.powi(64),
+            "u64",
+        ),
+        (
+            "type = \"i64\", scale = { factor = 1 }",
+            2f64.powi(63),
+            "i64",
+        ),
+        (
+            "type = \"i64\", scale = { factor = 1 }",
+            -2f64.powi(63) * 1.001,
+            "i64",
+        ),
+    ];
+    for (storage, value, scalar_type) in cases {
+        let layout_text = SENSORS.replace(temperature, storage);
+        let error =
+            build_artifact(&layout_text, &data(value), true).expect_err("raw value overflows");
+        assert!(
+            error.contains(&format!("outside the {scalar_type} range")),
+            "{value} as {scalar_type}: {error}"
+        );
+    }
+
+    let layout_text = SENSORS.replace(temperature, "type = \"i64\", scale = { factor = 1 }");
+    let artifact =
+        build_artifact(&layout_text, &data(-2f64.powi(63)), true).expect("i64::MIN is in range");
+    assert_eq!(&artifact.ranges[0].bytestream[..8], i64::MIN.to_le_bytes());
+}
+
+#[test]
+fn scale_is_validated_with_the_layout() {
+    let temperature = "scale = { factor = 0.1, offset = -40 }";
+    let cases = [
+        (
+            SENSORS.replace("type = \"i16\", scale", "type = \"f32\", scale"),
+            "'scale' requires an integer type, got f32",
+        ),
+        (
+            SENSORS.replace(temperature, "scale = { factor = 0 }"),
+            "'scale' factor must be a finite, non-zero number, got 0",
+        ),
+        (
+            SENSORS.replace(temperature, "scale = { factor = 0.1, bias = 2 }"),
+            "unknown field `bias`",
+        ),
+        (
+            SENSORS.replace(temperature, "scale = { factor = 0.1 }, enum = \"mode\""),
+            "'scale' cannot be combined with 'enum'",
+        ),
+        (
+            SENSORS.replace(
+                "name = \"Temperature\", type = \"i16\"",
+                "checksum = \"crc\", type = \"u32\"",
+            ),
+            "'scale' is only valid with a 'name', 'value', 'const' or 'axis' source",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build_artifact(&layout_text, &data(25.0), false).expect_err("scale rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
+
+#[test]
+fn header_comments_the_physical_conversion() {
+    let path = common::write_layout_file("scaling-header", SENSORS);
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&path)]).expect("header generates");
+
+    assert!(
+        header.contains("  int16_t temperature; /* physical = raw * 0.1 - 40 */\n"),
+        "{header}"
+    );
+    assert!(header.contains("  uint16_t pressure; /* physical = raw * 0.0625 */\n"));
+}
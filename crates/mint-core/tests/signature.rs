@@ -0,0 +1,213 @@
+use ed25519_dalek::Verifier as _;
+use mint_core::keys::{KeyRing, KeySource};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const ED25519_PUBLIC_KEY: &str = "316a80cdca25d412c88a82369056a48825f89655e2c203fd09bb96df45f06bed";
+const P256_PUBLIC_KEY: &str = "04fda0a7f3f0df7ce2c55c7fc8e06b0b84b7b39cd7f2e26884b3401227e8dbca\
+c9c30cbaba5936f210059f7b75d22a59ee0c8b9d1d00160ab7b7da12e0e66292f8";
+
+fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).expect("hex digit"))
+        .collect()
+}
+
+fn signed_layout(signature: &str, header: &str, data: &str) -> String {
+    format!(
+        r#"
+[mint]
+abi = "generic-le"
+
+[mint.signature.boot]
+{signature}
+
+[block.header]
+start_address = 0x1000
+length = 0x100
+{header}
+
+[block.data]
+{data}
+"#
+    )
+}
+
+/// Builds as if the layout lived in `tests/data`, so `key_file = "keys/..."` resolves to the
+/// fixture keys.
+fn build_bytes(layout_text: &str, keys: KeyRing) -> Result<Vec<u8>, String> {
+    let options = common::BuildOptions {
+        keys,
+        ..Default::default()
+    };
+    let artifact = common::build_layout_text("tests/data/signed.toml", layout_text, options)?;
+    Ok(artifact.ranges[0].bytestream.clone())
+}
+
+#[test]
+fn ed25519_signature_field_and_public_key_hash_use_layout_key_file() {
+    let bytes = build_bytes(
+        &signed_layout(
+            "algorithm = \"ed25519\"\nkey_file = \"keys/ed25519.pem\"",
+            "",
+            r#"
+key_hash = { public_key_hash = "boot", type = "u8", size = 32 }
+payload = { value = [1, 2, 3, 4], type = "u32", size = 4 }
+signature = { signature = "boot", type = "u8", size = 64 }
+"#,
+        ),
+        KeyRing::new(),
+    )
+    .expect("signed block builds");
+
+    let public_key = unhex(ED25519_PUBLIC_KEY);
+    assert_eq!(&bytes[..32], Sha256::digest(&public_key).as_slice());
+
+    let verifying_key =
+        ed25519_dalek::VerifyingKey::from_bytes(&public_key.try_into().expect("32-byte key"))
+            .expect("valid public key");
+    let signature = ed25519_dalek::Signature::from_slice(&bytes[48..112]).expect("signature");
+    verifying_key
+        .verify(&bytes[..48], &signature)
+        .expect("signature verifies over preceding bytes");
+}
+
+#[test]
+fn build_time_key_overrides_layout_and_accepts_der_and_sec1_keys() {
+    let data = r#"
+signature = { signature = "boot", type = "u8", size = 64, from = "payload" }
+payload = { value = "firmware parameters", type = "u8", size = 32 }
+"#;
+    let mut keys = KeyRing::new();
+    keys.insert(
+        "boot",
+        KeySource::File(PathBuf::from("tests/data/keys/p256-sec1.pem")),
+    );
+    let bytes = build_bytes(
+        &signed_layout(
+            "algorithm = \"ecdsa-p256\"\nkey_file = \"keys/missing.pem\"",
+            "",
+            data,
+        ),
+        keys,
+    )
+    .expect("P-256 SEC1 key signs");
+    let verifying_key =
+        p256::ecdsa::VerifyingKey::from_sec1_bytes(&unhex(P256_PUBLIC_KEY)).expect("public key");
+    let signature = p256::ecdsa::Signature::from_slice(&bytes[..64]).expect("signature");
+    verifying_key
+        .verify(&bytes[64..96], &signature)
+        .expect("signature verifies over configured coverage");
+
+    let mut keys = KeyRing::new();
+    keys.insert(
+        "boot",
+        KeySource::File(PathBuf::from("tests/data/keys/ed25519.der")),
+    );
+    let der = build_bytes(&signed_layout("algorithm = \"ed25519\"", "", data), keys)
+        .expect("DER key signs");
+    let pem = build_bytes(
+        &signed_layout(
+            "algorithm = \"ed25519\"\nkey_file = \"keys/ed25519.pem\"",
+            "",
+            data,
+        ),
+        KeyRing::new(),
+    )
+    .expect("PEM key signs");
+    assert_eq!(der, pem, "Ed25519 signatures are deterministic");
+}
+
+#[test]
+fn header_signature_appends_trailer_over_block_data() {
+    let bytes = build_bytes(
+        &signed_layout(
+            "algorithm = \"ecdsa-p256\"\nkey_file = \"keys/p256.pem\"",
+            "signature = \"boot\"",
+            r#"
+version = { value = 3, type = "u16" }
+payload = { value = [10, 20, 30], type = "u16", size = 3 }
+"#,
+        ),
+        KeyRing::new(),
+    )
+    .expect("trailer signs");
+
+    assert_eq!(bytes.len(), 8 + 64);
+    let verifying_key =
+        p256::ecdsa::VerifyingKey::from_sec1_bytes(&unhex(P256_PUBLIC_KEY)).expect("public key");
+    let signature = p256::ecdsa::Signature::from_slice(&bytes[8..]).expect("signature");
+    verifying_key
+        .verify(&bytes[..8], &signature)
+        .expect("trailer verifies over block data");
+}
+
+#[test]
+fn signature_configuration_errors_are_reported() {
+    let field = r#"
+value = { value = 1, type = "u32" }
+signature = { signature = "boot", type = "u8", size = 64 }
+"#;
+    let cases = [
+        (
+            signed_layout("algorithm = \"ed25519\"", "", field),
+            "no key for [mint.signature.boot]; set 'key_file' in the layout or pass --key boot=PATH",
+        ),
+        (
+            signed_layout(
+                "algorithm = \"ed25519\"\nkey_file = \"keys/p256.pem\"",
+                "",
+                field,
+            ),
+            "[mint.signature.boot]: invalid Ed25519 PKCS#8 private key",
+        ),
+        (
+            signed_layout(
+                "algorithm = \"ed25519\"\nkey_file = \"keys/missing.pem\"",
+                "",
+                field,
+            ),
+            "failed to read key file 'tests/data/keys/missing.pem'",
+        ),
+        (
+            signed_layout(
+                "algorithm = \"ed25519\"\nkey_file = \"keys/ed25519.pem\"",
+                "",
+                "value = { value = 1, type = \"u32\" }\nsignature = { signature = \"boot\", type = \"u8\", size = 32 }",
+            ),
+            "Signature 'ed25519' produces 64 bytes; size must be 64.",
+        ),
+        (
+            signed_layout(
+                "algorithm = \"ed25519\"\nkey_file = \"keys/ed25519.pem\"",
+                "",
+                "value = { value = 1, type = \"u32\" }\nhash = { public_key_hash = \"boot\", type = \"u8\", size = 64 }",
+            ),
+            "Public key hash (SHA-256) produces 32 bytes; size must be 32.",
+        ),
+        (
+            signed_layout(
+                "algorithm = \"ed25519\"\nkey_file = \"keys/ed25519.pem\"",
+                "signature = \"boot\"",
+                "payload = { value = 1, type = \"u8\", size = 200 }",
+            ),
+            "plus the 'boot' signature trailer (64 octets) exceeds configured block length",
+        ),
+        (
+            signed_layout("algorithm = \"rsa\"", "", field),
+            "unknown variant `rsa`",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build_bytes(&layout_text, KeyRing::new())
+            .expect_err("signature configuration should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use mint_core::build::{BlockSelector, BuildArtifact};
+use mint_core::data::{DataSource, JsonDataSource};
+
+#[path = "common/mod.rs"]
+mod common;
+
+const CHANNELS: &str = r#"
+[mint]
+abi = "generic-le"
+
+[block.header]
+start_address = 0x1000
+length = 0x100
+
+[block.data]
+count = { value = 3, type = "u8" }
+channel = { name = "Channel", size = 3, gain = { name = "Gain", type = "f32" }, offset = { name = "Offset", type = "i16" }, enabled = { value = 1, type = "u8" } }
+second = { ref = "channel[1].offset", type = "u32" }
+"#;
+
+fn build_artifact(
+    layout_text: &str,
+    data_source: Option<&dyn DataSource>,
+) -> Result<BuildArtifact, String> {
+    let options = common::BuildOptions {
+        data_source,
+        ..Default::default()
+    };
+    common::build_layout_text("struct_arrays.toml", layout_text, options)
+}
+
+fn json(text: &str) -> JsonDataSource {
+    JsonDataSource::from_str(text, &["Default".to_owned()]).expect("JSON data parses")
+}
+
+fn channel_bytes(gain: f32, offset: i16) -> Vec<u8> {
+    let mut bytes = gain.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&offset.to_le_bytes());
+    bytes.extend_from_slice(&[1, 0xFF]);
+    bytes
+}
+
+#[test]
+fn elements_use_the_struct_stride_and_read_indexed_names() {
+    let source = json(
+        r#"{ "Default": {
+            "Channel": [
+                { "Gain": 1.5, "Offset": -1 },
+                { "Gain": 2.5, "Offset": 2 }
+            ],
+            "Channel[2].Gain": 3.5,
+            "Channel[2].Offset": 3
+        } }"#,
+    );
+    let artifact = build_artifact(CHANNELS, Some(&source)).expect("struct array builds");
+    let bytes = &artifact.ranges[0].bytestream;
+
+    assert_eq!(&bytes[..4], &[3, 0xFF, 0xFF, 0xFF], "array aligns to f32");
+    assert_eq!(&bytes[4..12], channel_bytes(1.5, -1).as_slice());
+    assert_eq!(&bytes[12..20], channel_bytes(2.5, 2).as_slice());
+    assert_eq!(&bytes[20..28], channel_bytes(3.5, 3).as_slice());
+    assert_eq!(&bytes[28..32], &0x1010u32.to_le_bytes());
+
+    let used = artifact.used_values.expect("used values captured");
+    assert_eq!(
+        used["struct_arrays.toml"]["block"]["channel"],
+        serde_json::json!([
+            { "gain": 1.5, "offset": -1, "enabled": 1 },
+            { "gain": 2.5, "offset": 2, "enabled": 1 },
+            { "gain": 3.5, "offset": 3, "enabled": 1 },
+        ])
+    );
+}
+
+#[test]
+fn two_dimensional_and_nested_arrays_index_every_level() {
+    let layout_text = r#"
+[mint]
+abi = "generic-le"
+
+[block.header]
+start_address = 0x1000
+length = 0x100
+
+[block.data.grid]
+size = [2, 2]
+cell = { name = "Cell", type = "u8" }
+
+[block.data.bank]
+size = 2
+tap = { size = 2, coeff = { name = "Coeff", type = "u16" } }
+"#;
+    let source = json(
+        r#"{ "Default": {
+            "grid": [[{ "Cell": 1 }, { "Cell": 2 }], [{ "Cell": 3 }, { "Cell": 4 }]],
+            "bank": [
+                { "tap": [{ "Coeff": 10 }, { "Coeff": 11 }] },
+                { "tap": [{ "Coeff": 20 }, { "Coeff": 21 }] }
+            ]
+        } }"#,
+    );
+    let artifact = build_artifact(layout_text, Some(&source)).expect("nested arrays build");
+
+    assert_eq!(
+        artifact.ranges[0].bytestream,
+        [1, 2, 3, 4, 10, 0, 11, 0, 20, 0, 21, 0]
+    );
+    let used = artifact.used_values.expect("used values captured");
+    assert_eq!(
+        used["struct_arrays.toml"]["block"]["grid"][1][0]["cell"], 3,
+        "2D elements nest row-major"
+    );
+    assert_eq!(
+        used["struct_arrays.toml"]["block"]["bank"][1]["tap"][0]["coeff"],
+        20
+    );
+}
+
+#[test]
+fn header_declares_struct_arrays_with_length_macros() {
+    let path = common::write_layout_file("struct-arrays-header", CHANNELS);
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&path)]).expect("header generates");
+
+    assert!(header.contains("#define BLOCK_CHANNEL_LEN 3u"), "{header}");
+    assert!(header.contains(
+        "  struct {\n    float gain;\n    int16_t offset;\n    uint8_t enabled;\n  } channel[BLOCK_CHANNEL_LEN];"
+    ));
+    assert!(header.contains(
+        "_Static_assert(sizeof(((block_t *)0)->channel[0]) * CHAR_BIT == 8u * 8u, \"Mint ABI stride mismatch for block.channel\");"
+    ));
+    assert!(header.contains("offsetof(block_t, channel[0].offset) * CHAR_BIT == 8u * 8u"));
+}
+
+#[test]
+fn struct_array_errors_are_reported() {
+    let cases = [
+        (
+            CHANNELS.replace("size = 3,", "size = 0,"),
+            "array 'channel' has a zero extent",
+        ),
+        (
+            CHANNELS.replace("size = 3,", "size = true,"),
+            "invalid branch 'size'",
+        ),
+        (
+            CHANNELS.replace("size = 3,", ""),
+            "branch 'name' requires a 'size'",
+        ),
+        (
+            CHANNELS.replace("channel[1].offset", "channel[3].offset"),
+            "ref target 'channel[3].offset' not found in block",
+        ),
+        (
+            CHANNELS.replace("size = 3,", "size = [65536, 65536],"),
+            "array 'channel' has more elements than fit",
+        ),
+        (
+            CHANNELS.replace("size = 3,", "size = [16384, 16384],"),
+            "array 'channel' of 268435456 8-octet elements exceeds Mint's materialized block limit",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build_artifact(&layout_text, Some(&json(r#"{ "Default": {} }"#)))
+            .expect_err("struct array should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+
+    let error = build_artifact(CHANNELS, Some(&json(r#"{ "Default": { "Channel": [] } }"#)))
+        .expect_err("missing element data should be rejected");
+    assert!(error.contains("Channel[0].Gain"), "got: {error}");
+}
@@ -0,0 +1,161 @@
+use mint_core::build::{BlockSelector, BuildArtifact};
+use mint_core::data::{DataSource, JsonDataSource};
+use mint_core::fingerprint;
+use mint_core::layout;
+
+#[path = "common/mod.rs"]
+mod common;
+
+const CALIBRATION: &str = r#"
+[mint]
+abi = "generic-le"
+
+[mint.types.cal_point]
+x = { name = "X", type = "i16" }
+y = { name = "Y", type = "f32" }
+
+[mint.types.cal_curve]
+count = { value = 2, type = "u8" }
+points = { struct = "cal_point", size = 2, name = "Point" }
+
+[block.header]
+start_address = 0x1000
+length = 0x100
+
+[block.data]
+low = { struct = "cal_point", name = "Low" }
+high = { struct = "cal_point", name = "High" }
+curve = { struct = "cal_curve", name = "Curve" }
+"#;
+
+fn build_artifact(
+    layout_text: &str,
+    data_source: Option<&dyn DataSource>,
+) -> Result<BuildArtifact, String> {
+    let options = common::BuildOptions {
+        data_source,
+        ..Default::default()
+    };
+    common::build_layout_text("struct_types.toml", layout_text, options)
+}
+
+fn point_bytes(x: i16, y: f32) -> Vec<u8> {
+    let mut bytes = x.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&[0xFF, 0xFF]);
+    bytes.extend_from_slice(&y.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn struct_fields_share_the_type_layout_and_read_prefixed_names() {
+    let source = JsonDataSource::from_str(
+        r#"{ "Default": {
+            "Low.X": -1, "Low.Y": 0.5,
+            "High.X": 1, "High.Y": 1.5,
+            "Curve.Point": [{ "X": 10, "Y": 2.5 }, { "X": 20, "Y": 3.5 }]
+        } }"#,
+        &["Default".to_owned()],
+    )
+    .expect("JSON data parses");
+    let artifact = build_artifact(CALIBRATION, Some(&source)).expect("typed layout builds");
+    let bytes = &artifact.ranges[0].bytestream;
+
+    assert_eq!(&bytes[..8], point_bytes(-1, 0.5).as_slice());
+    assert_eq!(&bytes[8..16], point_bytes(1, 1.5).as_slice());
+    assert_eq!(&bytes[16..20], &[2, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(&bytes[20..28], point_bytes(10, 2.5).as_slice());
+    assert_eq!(&bytes[28..36], point_bytes(20, 3.5).as_slice());
+
+    let used = artifact.used_values.expect("used values captured");
+    assert_eq!(
+        used["struct_types.toml"]["block"]["curve"]["points"][1],
+        serde_json::json!({ "x": 20, "y": 3.5 })
+    );
+}
+
+#[test]
+fn identical_types_and_inline_branches_fingerprint_identically() {
+    let fingerprint_of = |source: &str| {
+        let config = layout::parse_toml_layout(source).expect("layout parses");
+        fingerprint::calculate(&config).expect("fingerprint calculates")[0].value
+    };
+    let inline = CALIBRATION
+        .replace(
+            "low = { struct = \"cal_point\", name = \"Low\" }",
+            "low = { x = { name = \"Low.X\", type = \"i16\" }, y = { name = \"Low.Y\", type = \"f32\" } }",
+        )
+        .replace("[mint.types.cal_point]", "[mint.types.renamed]")
+        .replace("struct = \"cal_point\"", "struct = \"renamed\"");
+
+    assert_eq!(fingerprint_of(CALIBRATION), fingerprint_of(&inline));
+    assert_ne!(
+        fingerprint_of(CALIBRATION),
+        fingerprint_of(&CALIBRATION.replace("type = \"i16\"", "type = \"i32\"")),
+        "changing the type changes every block that uses it"
+    );
+}
+
+#[test]
+fn header_emits_one_typedef_per_type_before_the_blocks() {
+    let path = common::write_layout_file("struct-types-header", CALIBRATION);
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&path)]).expect("header generates");
+
+    let point = header
+        .find("typedef struct {\n  int16_t x;\n  float y;\n} cal_point_t;")
+        .expect("cal_point typedef");
+    let curve = header
+        .find(
+            "typedef struct {\n  uint8_t count;\n  cal_point_t points[CAL_CURVE_POINTS_LEN];\n} cal_curve_t;",
+        )
+        .expect("cal_curve typedef");
+    let block = header
+        .find("typedef struct {\n  cal_point_t low;\n  cal_point_t high;\n  cal_curve_t curve;\n} block_t;")
+        .expect("block typedef");
+    assert!(point < curve && curve < block, "{header}");
+    assert_eq!(header.matches("} cal_point_t;").count(), 1);
+    assert!(header.contains("#define CAL_CURVE_POINTS_LEN 2u"));
+    assert!(header.contains("offsetof(block_t, curve.points[0].y) * CHAR_BIT == 24u * 8u"));
+}
+
+#[test]
+fn struct_type_errors_are_reported() {
+    let cases = [
+        (
+            CALIBRATION.replace("struct = \"cal_curve\"", "struct = \"missing\""),
+            "in block 'block': struct type 'missing' not found in [mint.types]. Available: [cal_point, cal_curve]",
+        ),
+        (
+            CALIBRATION.replace(
+                "x = { name = \"X\", type = \"i16\" }",
+                "x = { struct = \"cal_curve\" }",
+            ),
+            "struct type cycle: cal_point -> cal_curve -> cal_point",
+        ),
+        (
+            CALIBRATION.replace(
+                "name = \"Low\" }",
+                "name = \"Low\", extra = { value = 1, type = \"u8\" } }",
+            ),
+            "struct field may only set 'struct', 'size', 'name' and 'offset'; found 'extra'",
+        ),
+        (
+            CALIBRATION.replace(
+                "[mint.types.cal_point]\n",
+                "[mint.types.cal_point]\nsize = 2\n",
+            ),
+            "type 'cal_point' must be a table of fields",
+        ),
+        (
+            CALIBRATION.replace("[mint.types.cal_point]", "[mint.types.\"cal-point\"]"),
+            "type name 'cal-point'",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build_artifact(&layout_text, None).expect_err("struct type should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use mint_core::build::{BlockSelector, BuildArtifact};
+use mint_core::data::{DataSource, JsonDataSource};
+
+#[path = "common/mod.rs"]
+mod common;
+
+const SENSOR: &str = r#"
+[mint]
+abi = "generic-le"
+
+[mint.const]
+sensor = "strain"
+
+[block.header]
+start_address = 0x1000
+length = 0x40
+padding = 0xFF
+
+[block.data]
+mode = { value = 1, type = "u8" }
+params.union = "thermo"
+params.thermo.gain = { value = 0x11, type = "u8" }
+params.thermo.bias = { value = 0x2233, type = "u16" }
+params.strain = { value = 0x44556677, type = "u32" }
+params.raw = { value = [1, 2, 3, 4, 5, 6], type = "u8", size = 6 }
+tail = { value = 0x99, type = "u8" }
+"#;
+
+fn build_artifact(
+    layout_text: &str,
+    data_source: Option<&dyn DataSource>,
+) -> Result<BuildArtifact, String> {
+    let options = common::BuildOptions {
+        data_source,
+        ..Default::default()
+    };
+    common::build_layout_text("unions.toml", layout_text, options)
+}
+
+fn json(text: &str) -> JsonDataSource {
+    JsonDataSource::from_str(text, &["Default".to_owned()]).expect("JSON data parses")
+}
+
+#[test]
+fn unions_overlay_members_and_emit_only_the_selected_one() {
+    let artifact = build_artifact(SENSOR, None).expect("union builds");
+    assert_eq!(
+        artifact.ranges[0].bytestream,
+        [
+            0x01, 0xFF, 0xFF, 0xFF, // mode, then the union aligns to u32
+            0x11, 0xFF, 0x33, 0x22, // thermo
+            0xFF, 0xFF, 0xFF, 0xFF, // rest of the six-byte raw member and tail padding
+            0x99, 0xFF, 0xFF, 0xFF, // tail
+        ]
+    );
+    let used = artifact.used_values.expect("used values captured");
+    let params = &used["unions.toml"]["block"]["params"];
+    assert_eq!(params["thermo"]["gain"], 0x11);
+    assert!(params.get("strain").is_none() && params.get("raw").is_none());
+
+    let by_const = SENSOR.replace(
+        "params.union = \"thermo\"",
+        "params.union = { const = \"sensor\" }",
+    );
+    let artifact = build_artifact(&by_const, None).expect("const-selected union builds");
+    assert_eq!(
+        &artifact.ranges[0].bytestream[4..12],
+        [0x77, 0x66, 0x55, 0x44, 0xFF, 0xFF, 0xFF, 0xFF]
+    );
+}
+
+#[test]
+fn data_selected_unions_pick_a_member_per_array_element() {
+    let layout_text = SENSOR.replace(
+        "params.union = \"thermo\"",
+        "params.union = { name = \"SensorType\" }\nparams.size = 2\nparams.name = \"Sensor\"",
+    );
+    let source = json(
+        r#"{ "Default": {
+            "Sensor[0].SensorType": "raw",
+            "Sensor[1].SensorType": "strain"
+        } }"#,
+    );
+    let artifact = build_artifact(&layout_text, Some(&source)).expect("union array builds");
+    assert_eq!(
+        &artifact.ranges[0].bytestream[4..20],
+        [
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0xFF, 0xFF, // params[0].raw
+            0x77, 0x66, 0x55, 0x44, 0xFF, 0xFF, 0xFF, 0xFF, // params[1].strain
+        ]
+    );
+
+    let error = build_artifact(&layout_text, None).expect_err("data source required");
+    assert!(
+        error.contains("params[0]") && error.contains("'SensorType'"),
+        "{error}"
+    );
+    let source = json(r#"{ "Default": { "Sensor[0].SensorType": "optical" } }"#);
+    let error = build_artifact(&layout_text, Some(&source)).expect_err("unknown member");
+    assert!(
+        error.contains("union has no member 'optical'. Available members: [thermo, strain, raw]"),
+        "{error}"
+    );
+}
+
+#[test]
+fn header_declares_a_c_union() {
+    let path = common::write_layout_file("union-header", SENSOR);
+    let header =
+        mint_core::header::generate(&[BlockSelector::all(&path)]).expect("header generates");
+
+    assert!(
+        header.contains(
+            "  union {\n    struct {\n      uint8_t gain;\n      uint16_t bias;\n    } thermo;\n    uint32_t strain;\n    uint8_t raw[BLOCK_PARAMS_RAW_LEN];\n  } params;\n"
+        ),
+        "{header}"
+    );
+    assert!(header.contains("offsetof(block_t, params.raw) * CHAR_BIT == 4u * 8u"));
+    assert!(header.contains("sizeof(block_t) * CHAR_BIT == 16u * 8u"));
+}
+
+#[test]
+fn union_selectors_and_members_are_validated() {
+    let cases = [
+        (
+            SENSOR.replace("params.union = \"thermo\"", "params.union = \"optical\""),
+            "union has no member 'optical'",
+        ),
+        (
+            SENSOR.replace("sensor = \"strain\"", "sensor = 3").replace(
+                "params.union = \"thermo\"",
+                "params.union = { const = \"sensor\" }",
+            ),
+            "union const 'sensor' must be a string naming a member",
+        ),
+        (
+            SENSOR.replace(
+                "params.union = \"thermo\"",
+                "params.union = { const = \"missing\" }",
+            ),
+            "Const 'missing' not found in [mint.const]",
+        ),
+        (
+            SENSOR.replace("params.union = \"thermo\"", "params.union = 1"),
+            "'union' must be a member name or a table with one string 'value', 'const' or 'name'",
+        ),
+        (
+            SENSOR.replace("size = 6 }", "size = 6, offset = 0 }"),
+            "union member 'params.raw' cannot set 'offset'",
+        ),
+        (
+            SENSOR.replace(
+                "params.strain = { value = 0x44556677, type = \"u32\" }",
+                "params.strain = { checksum = \"crc\", type = \"u32\" }",
+            ),
+            "field 'params.strain' lies in a union member, which cannot hold integrity or encryption fields",
+        ),
+        (
+            SENSOR.replace(
+                "mode = { value = 1, type = \"u8\" }",
+                "union = \"mode\"\nmode = { value = 1, type = \"u8\" }",
+            ),
+            "block data cannot be a union",
+        ),
+    ];
+    for (layout_text, expected) in cases {
+        let error = build_artifact(&layout_text, None).expect_err("union should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected '{expected}', got: {error}"
+        );
+    }
+}